@@ -13,7 +13,7 @@ type DiscordShared = Arc<Mutex<discord::DiscordState>>;
 type XmppShared = Arc<Mutex<riot::xmpp::XmppState>>;
 
 #[tauri::command]
-async fn connect(state: tauri::State<'_, SharedState>) -> Result<riot::PlayerInfo, String> {
+async fn connect(state: tauri::State<'_, SharedState>) -> Result<riot::PlayerInfo, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::connect_and_store(&state))
         .await
@@ -35,6 +35,24 @@ fn get_player(state: tauri::State<'_, SharedState>) -> Option<riot::PlayerInfo>
     riot::get_cached_player(&state)
 }
 
+#[tauri::command]
+fn list_profiles() -> riot::profiles::Registry {
+    riot::profiles::list()
+}
+
+#[tauri::command]
+fn get_profile(puuid: Option<String>) -> Option<riot::profiles::Profile> {
+    match puuid {
+        Some(p) => riot::profiles::get(&p),
+        None => riot::profiles::active(),
+    }
+}
+
+#[tauri::command]
+fn remove_profile(puuid: String) -> Result<(), String> {
+    riot::profiles::remove(&puuid)
+}
+
 #[tauri::command]
 fn is_valorant_running() -> bool {
     riot::is_valorant_running()
@@ -45,6 +63,88 @@ fn find_valorant_path() -> Result<String, String> {
     riot::find_valorant_path()
 }
 
+#[tauri::command]
+async fn get_process_status() -> Result<riot::ProcessStatus, String> {
+    tauri::async_runtime::spawn_blocking(riot::process_status)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))
+}
+
+#[tauri::command]
+async fn run_diagnostics() -> Result<riot::diagnostics::DiagnosticsReport, String> {
+    tauri::async_runtime::spawn_blocking(|| riot::diagnostics::run_diagnostics(CURRENT_VERSION))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))
+}
+
+#[tauri::command]
+fn get_riot_paths() -> Result<riot::paths::RiotPaths, String> {
+    riot::paths::resolve()
+}
+
+#[tauri::command]
+fn get_region_override() -> Option<riot::region::RegionShard> {
+    riot::region::get_override()
+}
+
+#[tauri::command]
+fn set_region_override(region: Option<String>, shard: Option<String>) -> Result<Option<riot::region::RegionShard>, String> {
+    riot::region::set_override(region.as_deref(), shard.as_deref())
+}
+
+#[tauri::command]
+fn get_agent_select_rules() -> riot::agentselect::AgentRules {
+    riot::agentselect::get_rules()
+}
+
+#[tauri::command]
+fn set_agent_select_rules(rules: riot::agentselect::AgentRules) {
+    riot::agentselect::set_rules(rules)
+}
+
+#[tauri::command]
+fn get_dodge_config() -> riot::dodge::DodgeConfig {
+    riot::dodge::get_config()
+}
+
+#[tauri::command]
+fn set_dodge_config(config: riot::dodge::DodgeConfig) {
+    riot::dodge::set_config(config)
+}
+
+#[tauri::command]
+fn get_dodge_stats(state: tauri::State<'_, SharedState>) -> riot::dodge::DodgeStats {
+    riot::dodge::stats(&state)
+}
+
+#[tauri::command]
+fn get_agent_roles() -> riot::roles::RoleTable {
+    riot::roles::table()
+}
+
+#[tauri::command]
+async fn refresh_agent_roles() -> Result<riot::roles::RoleTable, riot::RiotError> {
+    tauri::async_runtime::spawn_blocking(riot::roles::refresh)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+fn get_game_log_snapshot() -> Result<riot::logtail::LogSnapshot, String> {
+    riot::logtail::snapshot()
+}
+
+#[tauri::command]
+fn get_riot_base_dir() -> Option<String> {
+    riot::paths::get_base_dir().map(|d| d.display().to_string())
+}
+
+#[tauri::command]
+fn set_riot_base_dir(path: Option<String>) -> Result<riot::paths::RiotPaths, String> {
+    riot::paths::set_base_dir(path.map(std::path::PathBuf::from));
+    riot::paths::resolve()
+}
+
 #[tauri::command]
 fn compute_file_hash(path: String) -> Result<String, String> {
     use std::hash::{Hash, Hasher};
@@ -75,18 +175,6 @@ fn toggle_devtools(app: tauri::AppHandle) {
     }
 }
 
-#[tauri::command]
-fn check_node_installed() -> bool {
-    let mut cmd = std::process::Command::new("node");
-    cmd.args(["--version"]);
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        cmd.creation_flags(0x08000000);
-    }
-    cmd.output().map(|o| o.status.success()).unwrap_or(false)
-}
-
 #[tauri::command]
 async fn health_check(state: tauri::State<'_, SharedState>) -> Result<Option<riot::PlayerInfo>, String> {
     let state = Arc::clone(&state);
@@ -96,7 +184,7 @@ async fn health_check(state: tauri::State<'_, SharedState>) -> Result<Option<rio
 }
 
 #[tauri::command]
-async fn check_current_game(state: tauri::State<'_, SharedState>) -> Result<String, String> {
+async fn check_current_game(state: tauri::State<'_, SharedState>) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::check_current_game(&state))
         .await
@@ -104,7 +192,7 @@ async fn check_current_game(state: tauri::State<'_, SharedState>) -> Result<Stri
 }
 
 #[tauri::command]
-async fn select_agent(state: tauri::State<'_, SharedState>, match_id: String, agent_id: String) -> Result<String, String> {
+async fn select_agent(state: tauri::State<'_, SharedState>, match_id: String, agent_id: String) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::select_agent(&state, &match_id, &agent_id))
         .await
@@ -112,7 +200,7 @@ async fn select_agent(state: tauri::State<'_, SharedState>, match_id: String, ag
 }
 
 #[tauri::command]
-async fn lock_agent(state: tauri::State<'_, SharedState>, match_id: String, agent_id: String) -> Result<String, String> {
+async fn lock_agent(state: tauri::State<'_, SharedState>, match_id: String, agent_id: String) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::lock_agent(&state, &match_id, &agent_id))
         .await
@@ -120,7 +208,7 @@ async fn lock_agent(state: tauri::State<'_, SharedState>, match_id: String, agen
 }
 
 #[tauri::command]
-async fn pregame_quit(state: tauri::State<'_, SharedState>, match_id: String) -> Result<String, String> {
+async fn pregame_quit(state: tauri::State<'_, SharedState>, match_id: String) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::pregame_quit(&state, &match_id))
         .await
@@ -128,7 +216,7 @@ async fn pregame_quit(state: tauri::State<'_, SharedState>, match_id: String) ->
 }
 
 #[tauri::command]
-async fn coregame_quit(state: tauri::State<'_, SharedState>, match_id: String) -> Result<String, String> {
+async fn coregame_quit(state: tauri::State<'_, SharedState>, match_id: String) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::coregame_quit(&state, &match_id))
         .await
@@ -136,7 +224,7 @@ async fn coregame_quit(state: tauri::State<'_, SharedState>, match_id: String) -
 }
 
 #[tauri::command]
-async fn get_home_stats(state: tauri::State<'_, SharedState>, queue_filter: String) -> Result<String, String> {
+async fn get_home_stats(state: tauri::State<'_, SharedState>, queue_filter: String) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::get_home_stats(&state, &queue_filter))
         .await
@@ -144,7 +232,7 @@ async fn get_home_stats(state: tauri::State<'_, SharedState>, queue_filter: Stri
 }
 
 #[tauri::command]
-async fn check_loadout(state: tauri::State<'_, SharedState>) -> Result<String, String> {
+async fn check_loadout(state: tauri::State<'_, SharedState>) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::check_loadout(&state))
         .await
@@ -152,15 +240,31 @@ async fn check_loadout(state: tauri::State<'_, SharedState>) -> Result<String, S
 }
 
 #[tauri::command]
-async fn get_match_page(state: tauri::State<'_, SharedState>, page: u64, page_size: u64) -> Result<String, String> {
+async fn get_match_loadouts(state: tauri::State<'_, SharedState>, match_id: String) -> Result<riot::loadouts::MatchLoadouts, riot::RiotError> {
+    let state = Arc::clone(&state);
+    tauri::async_runtime::spawn_blocking(move || riot::loadouts::get_match_loadouts(&state, &match_id))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn get_enriched_roster(state: tauri::State<'_, SharedState>, match_id: String) -> Result<riot::roster::EnrichedRoster, riot::RiotError> {
     let state = Arc::clone(&state);
-    tauri::async_runtime::spawn_blocking(move || riot::get_match_page(&state, page, page_size))
+    tauri::async_runtime::spawn_blocking(move || riot::roster::get_enriched_roster(&state, &match_id))
         .await
         .map_err(|e| format!("Task failed: {}", e))?
 }
 
 #[tauri::command]
-async fn get_owned_agents(state: tauri::State<'_, SharedState>) -> Result<Vec<String>, String> {
+async fn get_match_page(state: tauri::State<'_, SharedState>, page: u64, page_size: u64, queue: Option<String>) -> Result<String, riot::RiotError> {
+    let state = Arc::clone(&state);
+    tauri::async_runtime::spawn_blocking(move || riot::get_match_page(&state, page, page_size, queue.as_deref()))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn get_owned_agents(state: tauri::State<'_, SharedState>) -> Result<Vec<String>, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::get_owned_agents(&state))
         .await
@@ -168,7 +272,7 @@ async fn get_owned_agents(state: tauri::State<'_, SharedState>) -> Result<Vec<St
 }
 
 #[tauri::command]
-async fn get_party(state: tauri::State<'_, SharedState>) -> Result<String, String> {
+async fn get_party(state: tauri::State<'_, SharedState>) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::get_party(&state))
         .await
@@ -176,7 +280,7 @@ async fn get_party(state: tauri::State<'_, SharedState>) -> Result<String, Strin
 }
 
 #[tauri::command]
-async fn get_friends(state: tauri::State<'_, SharedState>) -> Result<String, String> {
+async fn get_friends(state: tauri::State<'_, SharedState>) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::get_friends(&state))
         .await
@@ -184,7 +288,7 @@ async fn get_friends(state: tauri::State<'_, SharedState>) -> Result<String, Str
 }
 
 #[tauri::command]
-async fn set_party_accessibility(state: tauri::State<'_, SharedState>, open: bool) -> Result<String, String> {
+async fn set_party_accessibility(state: tauri::State<'_, SharedState>, open: bool) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::set_party_accessibility(&state, open))
         .await
@@ -192,7 +296,7 @@ async fn set_party_accessibility(state: tauri::State<'_, SharedState>, open: boo
 }
 
 #[tauri::command]
-async fn disable_party_code(state: tauri::State<'_, SharedState>) -> Result<String, String> {
+async fn disable_party_code(state: tauri::State<'_, SharedState>) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::disable_party_code(&state))
         .await
@@ -200,7 +304,7 @@ async fn disable_party_code(state: tauri::State<'_, SharedState>) -> Result<Stri
 }
 
 #[tauri::command]
-async fn kick_from_party(state: tauri::State<'_, SharedState>, target_puuid: String) -> Result<String, String> {
+async fn kick_from_party(state: tauri::State<'_, SharedState>, target_puuid: String) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::kick_from_party(&state, &target_puuid))
         .await
@@ -208,7 +312,7 @@ async fn kick_from_party(state: tauri::State<'_, SharedState>, target_puuid: Str
 }
 
 #[tauri::command]
-async fn invite_to_party(state: tauri::State<'_, SharedState>, name: String, tag: String) -> Result<String, String> {
+async fn invite_to_party(state: tauri::State<'_, SharedState>, name: String, tag: String) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::invite_to_party(&state, &name, &tag))
         .await
@@ -216,7 +320,7 @@ async fn invite_to_party(state: tauri::State<'_, SharedState>, name: String, tag
 }
 
 #[tauri::command]
-async fn request_to_join_party(state: tauri::State<'_, SharedState>, target_puuid: String) -> Result<String, String> {
+async fn request_to_join_party(state: tauri::State<'_, SharedState>, target_puuid: String) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::request_to_join_party(&state, &target_puuid))
         .await
@@ -224,7 +328,7 @@ async fn request_to_join_party(state: tauri::State<'_, SharedState>, target_puui
 }
 
 #[tauri::command]
-async fn generate_party_code(state: tauri::State<'_, SharedState>) -> Result<String, String> {
+async fn generate_party_code(state: tauri::State<'_, SharedState>) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::generate_party_code(&state))
         .await
@@ -232,7 +336,7 @@ async fn generate_party_code(state: tauri::State<'_, SharedState>) -> Result<Str
 }
 
 #[tauri::command]
-async fn join_party_by_code(state: tauri::State<'_, SharedState>, code: String) -> Result<String, String> {
+async fn join_party_by_code(state: tauri::State<'_, SharedState>, code: String) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::join_party_by_code(&state, &code))
         .await
@@ -240,7 +344,7 @@ async fn join_party_by_code(state: tauri::State<'_, SharedState>, code: String)
 }
 
 #[tauri::command]
-async fn change_queue(state: tauri::State<'_, SharedState>, queue_id: String) -> Result<String, String> {
+async fn change_queue(state: tauri::State<'_, SharedState>, queue_id: String) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::change_queue(&state, &queue_id))
         .await
@@ -248,7 +352,7 @@ async fn change_queue(state: tauri::State<'_, SharedState>, queue_id: String) ->
 }
 
 #[tauri::command]
-async fn get_custom_configs(state: tauri::State<'_, SharedState>) -> Result<String, String> {
+async fn get_custom_configs(state: tauri::State<'_, SharedState>) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::get_custom_configs(&state))
         .await
@@ -262,7 +366,7 @@ async fn set_custom_settings(
     allow_cheats: bool, play_out_all_rounds: bool,
     skip_match_history: bool, tournament_mode: bool,
     overtime_win_by_two: bool,
-) -> Result<String, String> {
+) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || {
         riot::set_custom_settings(&state, &map, &mode, &pod, allow_cheats, play_out_all_rounds, skip_match_history, tournament_mode, overtime_win_by_two)
@@ -272,7 +376,7 @@ async fn set_custom_settings(
 }
 
 #[tauri::command]
-async fn get_chat_conversations(state: tauri::State<'_, SharedState>) -> Result<String, String> {
+async fn get_chat_conversations(state: tauri::State<'_, SharedState>) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::get_chat_conversations(&state))
         .await
@@ -280,7 +384,7 @@ async fn get_chat_conversations(state: tauri::State<'_, SharedState>) -> Result<
 }
 
 #[tauri::command]
-async fn get_chat_messages(state: tauri::State<'_, SharedState>, cid: String) -> Result<String, String> {
+async fn get_chat_messages(state: tauri::State<'_, SharedState>, cid: String) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::get_chat_messages(&state, &cid))
         .await
@@ -288,7 +392,7 @@ async fn get_chat_messages(state: tauri::State<'_, SharedState>, cid: String) ->
 }
 
 #[tauri::command]
-async fn send_chat_message(state: tauri::State<'_, SharedState>, cid: String, message: String, msg_type: Option<String>) -> Result<String, String> {
+async fn send_chat_message(state: tauri::State<'_, SharedState>, cid: String, message: String, msg_type: Option<String>) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     let t = msg_type.unwrap_or_default();
     tauri::async_runtime::spawn_blocking(move || riot::send_chat_message(&state, &cid, &message, &t))
@@ -297,7 +401,7 @@ async fn send_chat_message(state: tauri::State<'_, SharedState>, cid: String, me
 }
 
 #[tauri::command]
-async fn get_chat_participants(state: tauri::State<'_, SharedState>, cid: String) -> Result<String, String> {
+async fn get_chat_participants(state: tauri::State<'_, SharedState>, cid: String) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::get_chat_participants(&state, &cid))
         .await
@@ -305,7 +409,7 @@ async fn get_chat_participants(state: tauri::State<'_, SharedState>, cid: String
 }
 
 #[tauri::command]
-async fn start_custom_game_match(state: tauri::State<'_, SharedState>) -> Result<String, String> {
+async fn start_custom_game_match(state: tauri::State<'_, SharedState>) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::start_custom_game_match(&state))
         .await
@@ -313,7 +417,7 @@ async fn start_custom_game_match(state: tauri::State<'_, SharedState>) -> Result
 }
 
 #[tauri::command]
-async fn enter_queue(state: tauri::State<'_, SharedState>) -> Result<String, String> {
+async fn enter_queue(state: tauri::State<'_, SharedState>) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::enter_queue(&state))
         .await
@@ -321,7 +425,7 @@ async fn enter_queue(state: tauri::State<'_, SharedState>) -> Result<String, Str
 }
 
 #[tauri::command]
-async fn leave_queue(state: tauri::State<'_, SharedState>) -> Result<String, String> {
+async fn leave_queue(state: tauri::State<'_, SharedState>) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::leave_queue(&state))
         .await
@@ -438,19 +542,7 @@ const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 #[tauri::command]
 async fn check_for_update() -> Result<String, String> {
     tauri::async_runtime::spawn_blocking(|| {
-        let script = r#"const https=require('https');const o={hostname:'api.github.com',path:'/repos/AjaxFNC-YT/Valorant-Thing/releases?per_page=20',headers:{'User-Agent':'ValorantThing'}};https.get(o,res=>{let d='';res.on('data',c=>d+=c);res.on('end',()=>process.stdout.write(d))}).on('error',e=>{process.stderr.write(e.message);process.exit(1)})"#;
-        let mut cmd = std::process::Command::new("node");
-        cmd.args(["-e", script]);
-        #[cfg(target_os = "windows")]
-        {
-            use std::os::windows::process::CommandExt;
-            cmd.creation_flags(0x08000000);
-        }
-        let output = cmd.output().map_err(|e| format!("node failed: {}", e))?;
-        if !output.status.success() {
-            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
-        }
-        let body = String::from_utf8_lossy(&output.stdout).to_string();
+        let body = riot::github_api_get("/repos/AjaxFNC-YT/Valorant-Thing/releases?per_page=20")?;
         let releases: Vec<serde_json::Value> = serde_json::from_str(&body).map_err(|e| format!("parse: {}", e))?;
         let current = CURRENT_VERSION;
         let cur_parts: Vec<u32> = current.split('.').filter_map(|s| s.parse().ok()).collect();
@@ -558,7 +650,32 @@ fn get_token_age(state: tauri::State<'_, SharedState>) -> u64 {
 }
 
 #[tauri::command]
-async fn get_player_mmr(state: tauri::State<'_, SharedState>, target_puuid: String) -> Result<String, String> {
+fn get_token_info(state: tauri::State<'_, SharedState>) -> Result<riot::TokenInfo, riot::RiotError> {
+    riot::get_token_info(&state)
+}
+
+#[tauri::command]
+fn clear_response_cache() -> u64 {
+    riot::cache::clear()
+}
+
+#[tauri::command]
+fn get_game_phase() -> riot::watcher::PhaseSnapshot {
+    riot::watcher::current()
+}
+
+#[tauri::command]
+fn get_local_event_status() -> riot::websocket::LocalEventStatus {
+    riot::websocket::status()
+}
+
+#[tauri::command]
+fn set_local_event_topics(topics: Vec<String>) -> Result<Vec<String>, riot::RiotError> {
+    riot::websocket::set_topics(topics)
+}
+
+#[tauri::command]
+async fn get_player_mmr(state: tauri::State<'_, SharedState>, target_puuid: String) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::get_player_mmr(&state, &target_puuid))
         .await
@@ -566,7 +683,7 @@ async fn get_player_mmr(state: tauri::State<'_, SharedState>, target_puuid: Stri
 }
 
 #[tauri::command]
-async fn resolve_player_names(state: tauri::State<'_, SharedState>, puuids: Vec<String>) -> Result<String, String> {
+async fn resolve_player_names(state: tauri::State<'_, SharedState>, puuids: Vec<String>) -> Result<String, riot::RiotError> {
     let state = Arc::clone(&state);
     tauri::async_runtime::spawn_blocking(move || riot::resolve_player_names(&state, puuids))
         .await
@@ -574,7 +691,7 @@ async fn resolve_player_names(state: tauri::State<'_, SharedState>, puuids: Vec<
 }
 
 #[tauri::command]
-async fn henrik_get_account(puuid: String, api_key: String) -> Result<String, String> {
+async fn henrik_get_account(puuid: String, api_key: String) -> Result<String, riot::RiotError> {
     tauri::async_runtime::spawn_blocking(move || {
         let path = format!("/valorant/v1/by-puuid/account/{}", puuid);
         riot::henrik_api_get(&path, &api_key)
@@ -584,7 +701,7 @@ async fn henrik_get_account(puuid: String, api_key: String) -> Result<String, St
 }
 
 #[tauri::command]
-async fn henrik_get_mmr(puuid: String, region: String, api_key: String) -> Result<String, String> {
+async fn henrik_get_mmr(puuid: String, region: String, api_key: String) -> Result<String, riot::RiotError> {
     tauri::async_runtime::spawn_blocking(move || {
         let path = format!("/valorant/v2/by-puuid/mmr/{}/{}", region, puuid);
         riot::henrik_api_get(&path, &api_key)
@@ -594,9 +711,9 @@ async fn henrik_get_mmr(puuid: String, region: String, api_key: String) -> Resul
 }
 
 #[tauri::command]
-async fn splooshima_lookup(puuids: Vec<String>, api_key: String) -> Result<String, String> {
+async fn splooshima_lookup(puuids: Vec<String>, api_key: String) -> Result<String, riot::RiotError> {
     tauri::async_runtime::spawn_blocking(move || {
-        let body = serde_json::json!(puuids).to_string();
+        let body = serde_json::json!(puuids);
         riot::splooshima_api_post("/v1/lookup", &body, &api_key)
     })
     .await
@@ -628,6 +745,20 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .setup(|app| {
             riot::logging::init(app.handle().clone());
+            if let Ok(dir) = app.path().app_data_dir() {
+                riot::cache::init(dir.join("cache"));
+                riot::paths::init(dir.join("riot-dir.json"));
+                riot::profiles::init(dir.join("profiles.json"));
+                riot::dodge::init(dir.join("dodges.json"), dir.join("dodge-config.json"));
+                riot::agentselect::init(dir.join("agent-select.json"));
+                riot::region::init(dir.join("region.json"));
+            }
+            let state = app.state::<SharedState>().inner().clone();
+            riot::watcher::spawn(app.handle().clone(), state.clone());
+            riot::websocket::spawn(app.handle().clone(), state.clone());
+            let xmpp = app.state::<XmppShared>().inner().clone();
+            riot::lockwatch::spawn(app.handle().clone(), state, xmpp);
+            riot::logtail::spawn(app.handle().clone());
             let show_item = MenuItemBuilder::with_id("show", "Show").build(app)?;
             let quit_item = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
             let menu = MenuBuilder::new(app)
@@ -676,12 +807,29 @@ pub fn run() {
             disconnect,
             get_status,
             get_player,
+            list_profiles,
+            get_profile,
+            remove_profile,
             is_valorant_running,
             find_valorant_path,
+            get_process_status,
+            run_diagnostics,
+            get_riot_paths,
+            get_riot_base_dir,
+            set_riot_base_dir,
+            get_game_log_snapshot,
+            get_region_override,
+            set_region_override,
+            get_agent_select_rules,
+            set_agent_select_rules,
+            get_agent_roles,
+            get_dodge_config,
+            set_dodge_config,
+            get_dodge_stats,
+            refresh_agent_roles,
             compute_file_hash,
             force_copy_file,
             toggle_devtools,
-            check_node_installed,
             health_check,
             exit_app,
             check_for_update,
@@ -693,10 +841,17 @@ pub fn run() {
             coregame_quit,
             get_owned_agents,
             get_token_age,
+            get_token_info,
             get_player_mmr,
             get_home_stats,
             check_loadout,
+            get_match_loadouts,
+            get_enriched_roster,
             get_match_page,
+            clear_response_cache,
+            get_game_phase,
+            get_local_event_status,
+            set_local_event_topics,
             resolve_player_names,
             henrik_get_account,
             henrik_get_mmr,
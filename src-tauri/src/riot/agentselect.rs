@@ -0,0 +1,545 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use super::client::RiotClient;
+use super::dodge;
+use super::game::{get_owned_agents, lock_agent, pregame_match, select_agent};
+use super::logging::{log_error, log_info};
+use super::models::{self, PregameMatch};
+use super::roles::{self, AgentRole, Role};
+use super::types::ConnectionState;
+use super::watcher::{self, GamePhase};
+
+// How often a run waiting on the dodge engine re-checks that the pregame is still on.
+const DODGE_POLL: Duration = Duration::from_millis(250);
+
+// Base roster, never listed in the agent entitlements.
+const FREE_AGENTS: &[&str] = &[
+    "9f0d8ba9-4140-b941-57d3-a7ad57c6b417", // Brimstone
+    "add6443a-41bd-e414-f6ad-e58d267f4e95", // Jett
+    "eb93336a-449b-9c1b-0a54-a891f7921d69", // Phoenix
+    "569fdd95-4d10-43ab-ca70-79becc718b46", // Sage
+    "320b2a48-4d9b-a075-30f1-1f93a9b638fa", // Sova
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentRules {
+    pub enabled: bool,
+    pub select_delay_ms: u64,
+    pub lock_delay_ms: u64,
+    // Used for maps without their own entry.
+    pub default: Vec<String>,
+    // Keyed by pregame `MapID` (e.g. "/Game/Maps/Ascent/Ascent"). An empty list skips the map.
+    pub per_map: HashMap<String, Vec<String>>,
+    // Tried after the map's (or default) list, unless that list is empty.
+    #[serde(default)]
+    pub fallbacks: Vec<String>,
+    // Roles the team should end up with, most important first.
+    #[serde(default)]
+    pub role_goals: Vec<Role>,
+}
+
+impl Default for AgentRules {
+    fn default() -> Self {
+        AgentRules {
+            enabled: false,
+            select_delay_ms: 0,
+            lock_delay_ms: 500,
+            default: Vec::new(),
+            per_map: HashMap::new(),
+            fallbacks: Vec::new(),
+            role_goals: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Select,
+    Lock,
+    Fallback,
+    Skip,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Decision {
+    pub match_id: String,
+    pub map_id: String,
+    pub action: Action,
+    pub agent_id: Option<String>,
+    pub reason: String,
+}
+
+static RULES_PATH: OnceLock<PathBuf> = OnceLock::new();
+static RULES: Mutex<Option<AgentRules>> = Mutex::new(None);
+static RUNNING: Mutex<Option<String>> = Mutex::new(None);
+
+fn save(rules: &AgentRules) {
+    let Some(path) = RULES_PATH.get() else { return };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let Ok(body) = serde_json::to_string_pretty(rules) else { return };
+    let tmp = path.with_extension("tmp");
+    if let Err(e) = fs::write(&tmp, body).and_then(|_| fs::rename(&tmp, path)) {
+        log_error(&format!("[AgentSelect] write {}: {}", path.display(), e));
+        let _ = fs::remove_file(&tmp);
+    }
+}
+
+// Loads the saved rules; the frontend reads and edits them through `get_rules` / `set_rules`.
+pub fn init(path: PathBuf) {
+    if let Ok(raw) = fs::read_to_string(&path) {
+        match serde_json::from_str::<AgentRules>(&raw) {
+            Ok(rules) => { apply(rules, "loaded"); }
+            Err(e) => log_error(&format!("[AgentSelect] {} is corrupt, ignoring it: {}", path.display(), e)),
+        }
+    }
+    let _ = RULES_PATH.set(path);
+}
+
+fn apply(mut rules: AgentRules, how: &str) -> AgentRules {
+    let normalize = |list: &mut Vec<String>| list.iter_mut().for_each(|a| *a = a.trim().to_lowercase());
+    normalize(&mut rules.default);
+    normalize(&mut rules.fallbacks);
+    rules.per_map.values_mut().for_each(normalize);
+    log_info(&format!(
+        "[AgentSelect] Rules {}: enabled={} default={} maps={} fallbacks={} roles={:?} delays={}ms/{}ms",
+        how, rules.enabled, rules.default.len(), rules.per_map.len(), rules.fallbacks.len(), rules.role_goals, rules.select_delay_ms, rules.lock_delay_ms
+    ));
+    *RULES.lock().unwrap_or_else(|e| e.into_inner()) = Some(rules.clone());
+    rules
+}
+
+pub fn set_rules(rules: AgentRules) {
+    save(&apply(rules, "updated"));
+}
+
+pub fn get_rules() -> AgentRules {
+    RULES.lock().unwrap_or_else(|e| e.into_inner()).clone().unwrap_or_default()
+}
+
+fn preferences(rules: &AgentRules, map_id: &str) -> Vec<String> {
+    let mut list = rules.per_map.get(map_id).unwrap_or(&rules.default).clone();
+    if !list.is_empty() {
+        for agent in &rules.fallbacks {
+            if !list.contains(agent) {
+                list.push(agent.clone());
+            }
+        }
+    }
+    list
+}
+
+fn is_owned(owned: &Option<HashSet<String>>, agent_id: &str) -> bool {
+    match owned {
+        Some(set) => set.contains(agent_id) || FREE_AGENTS.contains(&agent_id),
+        None => true,
+    }
+}
+
+struct Candidates<'a> {
+    prefs: &'a [String],
+    goals: &'a [Role],
+    roles: &'a [AgentRole],
+    owned: &'a Option<HashSet<String>>,
+    tried: &'a HashSet<String>,
+}
+
+impl Candidates<'_> {
+    // Preferences first, then every agent of that role in the content table.
+    fn for_role(&self, role: Role, taken: &HashSet<String>) -> Option<String> {
+        self.prefs.iter()
+            .chain(self.roles.iter().map(|a| &a.uuid))
+            .filter(|a| roles::role_of(self.roles, a) == Some(role))
+            .find(|a| !self.tried.contains(*a) && is_owned(self.owned, a) && !taken.contains(*a))
+            .cloned()
+    }
+
+    // First preference that is owned and not locked by a teammate, plus the reasons the
+    // earlier ones were passed over. Role goals override the preference once the open slots
+    // (this player included) can no longer cover the missing roles, and fill in when no
+    // preference is left.
+    fn pick(&self, pregame: &PregameMatch, me: &str) -> (Option<String>, Vec<String>) {
+        let allies: Vec<_> = pregame.ally_team.iter().flat_map(|t| t.players.iter()).collect();
+        let locked: Vec<_> = allies.iter()
+            .filter(|p| p.subject != me && p.character_selection_state == "locked")
+            .collect();
+        let taken: HashSet<String> = locked.iter().map(|p| p.character_id.to_lowercase()).collect();
+        let filled: HashSet<Role> = locked.iter().filter_map(|p| roles::role_of(self.roles, &p.character_id)).collect();
+        let mut missing: Vec<Role> = Vec::new();
+        for role in self.goals {
+            if !filled.contains(role) && !missing.contains(role) {
+                missing.push(*role);
+            }
+        }
+        let open = allies.len().saturating_sub(locked.len()).max(1);
+
+        let mut skipped = Vec::new();
+        let mut preferred = None;
+        for agent in self.prefs {
+            if self.tried.contains(agent) {
+                continue;
+            }
+            if !is_owned(self.owned, agent) {
+                skipped.push(format!("{} is not owned", agent));
+            } else if taken.contains(agent) {
+                skipped.push(format!("{} is locked by a teammate", agent));
+            } else {
+                preferred = Some(agent.clone());
+                break;
+            }
+        }
+
+        let fills_gap = preferred.as_ref()
+            .and_then(|a| roles::role_of(self.roles, a))
+            .is_some_and(|r| missing.contains(&r));
+        let must_fill = !missing.is_empty() && missing.len() >= open;
+        if fills_gap || (preferred.is_some() && !must_fill) {
+            return (preferred, skipped);
+        }
+        for role in &missing {
+            if let Some(agent) = self.for_role(*role, &taken) {
+                skipped.push(format!("team has no {}", role.label()));
+                return (Some(agent), skipped);
+            }
+        }
+        (preferred, skipped)
+    }
+}
+
+struct Run {
+    app: AppHandle,
+    state: Arc<Mutex<ConnectionState>>,
+    match_id: String,
+    map_id: String,
+}
+
+impl Run {
+    fn emit(&self, action: Action, agent_id: Option<&str>, reason: String) {
+        log_info(&format!("[AgentSelect] {:?} {} ({})", action, agent_id.unwrap_or("-"), reason));
+        let _ = self.app.emit("agent-select-decision", Decision {
+            match_id: self.match_id.clone(),
+            map_id: self.map_id.clone(),
+            action,
+            agent_id: agent_id.map(|s| s.to_string()),
+            reason,
+        });
+    }
+
+    fn in_pregame(&self) -> bool {
+        let snap = watcher::current();
+        snap.phase == GamePhase::Pregame && snap.match_id.as_deref() == Some(self.match_id.as_str())
+    }
+
+    // Holds off until the dodge engine has decided on this match. False once the match is gone.
+    fn wait_for_dodge(&self) -> bool {
+        if dodge::wait_for_evaluation(&self.match_id, Duration::ZERO) {
+            return true;
+        }
+        log_info("[AgentSelect] Waiting for the dodge rules to finish");
+        while !dodge::wait_for_evaluation(&self.match_id, DODGE_POLL) {
+            if !self.in_pregame() {
+                return false;
+            }
+        }
+        true
+    }
+
+    // The pregame is gone once the watcher has moved on, agent select has ended or the match is being dodged.
+    fn fetch(&self) -> Option<PregameMatch> {
+        if dodge::is_dodging(&self.match_id) || !self.in_pregame() {
+            return None;
+        }
+        let client = RiotClient::from_state(&self.state).ok()?;
+        let raw = pregame_match(&client, &self.match_id).ok()?;
+        let pregame: PregameMatch = models::decode("/pregame/v1/matches", &raw).ok()?;
+        (pregame.pregame_state == "character_select_active").then_some(pregame)
+    }
+
+    fn execute(&self, rules: &AgentRules, me: &str) {
+        let prefs = preferences(rules, &self.map_id);
+        if prefs.is_empty() {
+            let reason = if rules.per_map.contains_key(&self.map_id) { "Disabled for this map" } else { "No agent configured" };
+            self.emit(Action::Skip, None, reason.to_string());
+            return;
+        }
+
+        let owned = match get_owned_agents(&self.state) {
+            Ok(ids) => Some(ids.into_iter().collect::<HashSet<_>>()),
+            Err(e) => {
+                log_error(&format!("[AgentSelect] Owned agents unavailable, assuming all owned: {}", e));
+                None
+            }
+        };
+
+        let roles = roles::table().agents;
+
+        std::thread::sleep(Duration::from_millis(rules.select_delay_ms));
+        if !self.wait_for_dodge() {
+            self.emit(Action::Skip, None, "Agent select ended".to_string());
+            return;
+        }
+        if dodge::is_dodging(&self.match_id) {
+            self.emit(Action::Skip, None, "Match is being dodged".to_string());
+            return;
+        }
+        let mut tried = HashSet::new();
+        let mut selected: Option<String> = None;
+        loop {
+            let Some(pregame) = self.fetch() else {
+                self.emit(Action::Skip, selected.as_deref(), "Agent select ended".to_string());
+                return;
+            };
+            if let Some(mine) = pregame.ally_team.iter().flat_map(|t| t.players.iter()).find(|p| p.subject == me) {
+                if mine.character_selection_state == "locked" {
+                    self.emit(Action::Skip, Some(&mine.character_id.to_lowercase()), "Already locked in".to_string());
+                    return;
+                }
+            }
+
+            let candidates = Candidates { prefs: &prefs, goals: &rules.role_goals, roles: &roles, owned: &owned, tried: &tried };
+            let (agent, skipped) = candidates.pick(&pregame, me);
+            let Some(agent) = agent else {
+                self.emit(Action::Failed, None, format!("No preference available: {}", skipped.join("; ")));
+                return;
+            };
+            let switching = selected.as_deref() != Some(agent.as_str());
+            if switching && (!skipped.is_empty() || !tried.is_empty()) {
+                let reason = if skipped.is_empty() { "earlier choice could not be locked".to_string() } else { skipped.join("; ") };
+                self.emit(Action::Fallback, Some(&agent), reason);
+            }
+
+            if switching {
+                if let Err(e) = select_agent(&self.state, &self.match_id, &agent) {
+                    self.emit(Action::Failed, Some(&agent), format!("Select failed: {}", e));
+                    tried.insert(agent);
+                    continue;
+                }
+                self.emit(Action::Select, Some(&agent), format!("locking in {}ms", rules.lock_delay_ms));
+                selected = Some(agent.clone());
+                std::thread::sleep(Duration::from_millis(rules.lock_delay_ms));
+                // A teammate may have locked it during the delay.
+                continue;
+            }
+
+            match lock_agent(&self.state, &self.match_id, &agent) {
+                Ok(_) => {
+                    self.emit(Action::Lock, Some(&agent), "Locked".to_string());
+                    return;
+                }
+                Err(e) => {
+                    self.emit(Action::Failed, Some(&agent), format!("Lock failed: {}", e));
+                    tried.insert(agent);
+                    selected = None;
+                }
+            }
+        }
+    }
+}
+
+// Called by the phase watcher when agent select opens. Runs once per match on its own
+// thread, so it keeps going while the window is hidden.
+pub fn start(app: AppHandle, state: Arc<Mutex<ConnectionState>>, match_id: String, payload: &serde_json::Value) {
+    let rules = get_rules();
+    if !rules.enabled {
+        return;
+    }
+    {
+        let mut running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+        if running.as_deref() == Some(match_id.as_str()) {
+            return;
+        }
+        *running = Some(match_id.clone());
+    }
+    let map_id = payload["MapID"].as_str().unwrap_or_default().to_string();
+    let me = state.lock().ok().and_then(|s| s.puuid.clone()).unwrap_or_default();
+    std::thread::spawn(move || {
+        log_info(&format!("[AgentSelect] Agent select started in {} on {}", match_id, map_id));
+        let run = Run { app, state, match_id, map_id };
+        run.execute(&rules, &me);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ME: &str = "me";
+    const JETT: &str = "add6443a-41bd-e414-f6ad-e58d267f4e95";
+    const OMEN: &str = "8e253930-4c05-31dd-1b6c-968525494517";
+    const RAZE: &str = "f94c3b30-42be-e959-889c-5aa313dba261";
+    const REYNA: &str = "a3bfb853-43b2-7238-a4f1-ad90e9e46bcc";
+    const SOVA: &str = "320b2a48-4d9b-a075-30f1-1f93a9b638fa";
+    const VIPER: &str = "707eab51-4836-f488-046a-cda6bf494859";
+    const KILLJOY: &str = "1e58de9c-4950-5125-93e9-a0aee9f98746";
+    const CHAMBER: &str = "22697a3d-45bf-8dd7-4fec-84a9e28c69d7";
+    const CYPHER: &str = "117ed9e3-49f3-6512-3ccf-0cada7e3823b";
+    const ASCENT: &str = "/Game/Maps/Ascent/Ascent";
+
+    fn player(subject: &str, character_id: &str, state: &str) -> serde_json::Value {
+        serde_json::json!({
+            "Subject": subject,
+            "CharacterID": character_id,
+            "CharacterSelectionState": state,
+            "CompetitiveTier": 0,
+            "PlayerIdentity": {
+                "Subject": subject, "PlayerCardID": "", "PlayerTitleID": "",
+                "AccountLevel": 1, "Incognito": false, "HideAccountLevel": false,
+            },
+        })
+    }
+
+    // An agent select with this player still picking and the given teammates.
+    fn pregame(teammates: &[(&str, &str)]) -> PregameMatch {
+        let mut players = vec![player(ME, "", "")];
+        players.extend(teammates.iter().enumerate().map(|(i, (agent, state))| player(&format!("ally-{}", i), agent, state)));
+        serde_json::from_value(serde_json::json!({
+            "ID": "match", "MapID": ASCENT, "Mode": "", "QueueID": "competitive",
+            "PregameState": "character_select_active", "PhaseTimeRemainingNS": 0,
+            "AllyTeam": { "TeamID": "Blue", "Players": players },
+        }))
+        .unwrap()
+    }
+
+    fn owned(agents: &[&str]) -> Option<HashSet<String>> {
+        Some(agents.iter().map(|a| a.to_string()).collect())
+    }
+
+    fn prefs(agents: &[&str]) -> Vec<String> {
+        agents.iter().map(|a| a.to_string()).collect()
+    }
+
+    fn pick(prefs: &[String], owned: &Option<HashSet<String>>, tried: &HashSet<String>, pregame: &PregameMatch) -> (Option<String>, Vec<String>) {
+        Candidates { prefs, goals: &[], roles: &[], owned, tried }.pick(pregame, ME)
+    }
+
+    #[test]
+    fn map_list_replaces_the_default() {
+        let rules = apply(AgentRules {
+            default: prefs(&[JETT]),
+            per_map: HashMap::from([(ASCENT.to_string(), prefs(&[" 8E253930-4C05-31DD-1B6C-968525494517", SOVA]))]),
+            ..AgentRules::default()
+        }, "tested");
+        assert_eq!(preferences(&rules, ASCENT), prefs(&[OMEN, SOVA]), "ids are trimmed and lowercased");
+        assert_eq!(preferences(&rules, "/Game/Maps/Duality/Duality"), prefs(&[JETT]));
+
+        let (agent, skipped) = pick(&preferences(&rules, ASCENT), &owned(&[OMEN]), &HashSet::new(), &pregame(&[]));
+        assert_eq!(agent.as_deref(), Some(OMEN));
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn fallbacks_follow_the_list_unless_the_map_is_off() {
+        let rules = AgentRules {
+            default: prefs(&[JETT]),
+            per_map: HashMap::from([(ASCENT.to_string(), Vec::new())]),
+            fallbacks: prefs(&[RAZE, JETT, OMEN]),
+            ..AgentRules::default()
+        };
+        assert_eq!(preferences(&rules, "/Game/Maps/Duality/Duality"), prefs(&[JETT, RAZE, OMEN]));
+        assert!(preferences(&rules, ASCENT).is_empty());
+    }
+
+    #[test]
+    fn falls_back_past_locked_and_unowned_agents() {
+        let prefs = prefs(&[RAZE, REYNA, OMEN]);
+        let pregame = pregame(&[(REYNA, "locked"), (OMEN, "selected")]);
+        let (agent, skipped) = pick(&prefs, &owned(&[REYNA, OMEN]), &HashSet::new(), &pregame);
+        assert_eq!(agent.as_deref(), Some(OMEN), "a hovered agent is still free");
+        assert_eq!(skipped, vec![format!("{} is not owned", RAZE), format!("{} is locked by a teammate", REYNA)]);
+
+        let tried = HashSet::from([OMEN.to_string()]);
+        let (agent, _) = pick(&prefs, &owned(&[REYNA, OMEN]), &tried, &pregame);
+        assert_eq!(agent, None, "nothing left once the last preference failed");
+    }
+
+    #[test]
+    fn free_agents_count_as_owned() {
+        let (agent, skipped) = pick(&prefs(&[RAZE, JETT]), &owned(&[]), &HashSet::new(), &pregame(&[]));
+        assert_eq!(agent.as_deref(), Some(JETT));
+        assert_eq!(skipped, vec![format!("{} is not owned", RAZE)]);
+
+        // Without the entitlements every agent is assumed owned.
+        let (agent, _) = pick(&prefs(&[RAZE, JETT]), &None, &HashSet::new(), &pregame(&[]));
+        assert_eq!(agent.as_deref(), Some(RAZE));
+    }
+
+    // A role table without Brimstone, so no controller is owned for free.
+    fn role_table() -> Vec<AgentRole> {
+        [
+            (JETT, Role::Duelist), (RAZE, Role::Duelist), (REYNA, Role::Duelist),
+            (OMEN, Role::Controller), (VIPER, Role::Controller),
+            (CHAMBER, Role::Sentinel), (CYPHER, Role::Sentinel), (KILLJOY, Role::Sentinel),
+            (SOVA, Role::Initiator),
+        ]
+        .into_iter()
+        .map(|(uuid, role)| AgentRole { uuid: uuid.to_string(), name: String::new(), role })
+        .collect()
+    }
+
+    // This player picks last: all four teammates have locked in.
+    fn last_pick(teammates: [&str; 4]) -> PregameMatch {
+        pregame(&teammates.map(|agent| (agent, "locked")))
+    }
+
+    fn pick_for_roles(prefs: &[String], goals: &[Role], owned: &Option<HashSet<String>>, pregame: &PregameMatch) -> (Option<String>, Vec<String>) {
+        let roles = role_table();
+        Candidates { prefs, goals, roles: &roles, owned, tried: &HashSet::new() }.pick(pregame, ME)
+    }
+
+    #[test]
+    fn role_goals_pick_the_first_missing_role() {
+        let prefs = prefs(&[RAZE, KILLJOY, VIPER]);
+        let owned = owned(&[RAZE, KILLJOY, VIPER]);
+        // Three teammates locked, one only hovering Omen: two open slots for two missing roles.
+        let pregame = pregame(&[(REYNA, "locked"), (SOVA, "locked"), (JETT, "locked"), (OMEN, "selected")]);
+
+        let (agent, skipped) = pick_for_roles(&prefs, &[Role::Controller, Role::Sentinel], &owned, &pregame);
+        assert_eq!(agent.as_deref(), Some(VIPER), "controller is the first goal, and a hovered Omen does not fill it");
+        assert_eq!(skipped, vec!["team has no controller".to_string()]);
+
+        let (agent, skipped) = pick_for_roles(&prefs, &[Role::Controller, Role::Sentinel], &owned, &last_pick([REYNA, SOVA, JETT, OMEN]));
+        assert_eq!(agent.as_deref(), Some(KILLJOY));
+        assert_eq!(skipped, vec!["team has no sentinel".to_string()]);
+    }
+
+    #[test]
+    fn role_goals_keep_a_preference_while_slots_remain() {
+        let owned = owned(&[RAZE, VIPER, CYPHER]);
+        let pregame = pregame(&[(REYNA, "locked"), (SOVA, "selected")]);
+
+        // Four open slots for one missing role: the duelist preference stands.
+        let (agent, skipped) = pick_for_roles(&prefs(&[RAZE, VIPER]), &[Role::Controller], &owned, &pregame);
+        assert_eq!(agent.as_deref(), Some(RAZE));
+        assert!(skipped.is_empty());
+
+        // A preference that fills a missing role is kept.
+        let last_slot = last_pick([REYNA, SOVA, JETT, OMEN]);
+        let (agent, _) = pick_for_roles(&prefs(&[CYPHER, RAZE]), &[Role::Sentinel], &owned, &last_slot);
+        assert_eq!(agent.as_deref(), Some(CYPHER));
+
+        // Outside the preferences, the first owned agent of the role in the table: Chamber is not owned.
+        let (agent, skipped) = pick_for_roles(&prefs(&[RAZE]), &[Role::Sentinel], &owned, &last_slot);
+        assert_eq!(agent.as_deref(), Some(CYPHER));
+        assert_eq!(skipped, vec!["team has no sentinel".to_string()]);
+    }
+
+    #[test]
+    fn role_goals_give_way_when_no_owned_agent_fills_them() {
+        let (agent, skipped) = pick_for_roles(&prefs(&[RAZE]), &[Role::Controller], &owned(&[RAZE]), &last_pick([JETT, SOVA, CYPHER, REYNA]));
+        assert_eq!(agent.as_deref(), Some(RAZE), "falls back to the preference");
+        assert!(skipped.is_empty());
+
+        let (agent, _) = pick_for_roles(&[], &[Role::Controller], &owned(&[RAZE]), &last_pick([JETT, SOVA, CYPHER, REYNA]));
+        assert_eq!(agent, None);
+    }
+}
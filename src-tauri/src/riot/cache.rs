@@ -0,0 +1,117 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+
+use super::logging::log_error;
+
+static CACHE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheKind {
+    MatchDetails,
+    Names,
+    Mmr,
+    Content,
+}
+
+impl CacheKind {
+    fn dir_name(self) -> &'static str {
+        match self {
+            CacheKind::MatchDetails => "match-details",
+            CacheKind::Names => "names",
+            CacheKind::Mmr => "mmr",
+            CacheKind::Content => "content",
+        }
+    }
+
+    fn ttl(self) -> Option<Duration> {
+        match self {
+            CacheKind::MatchDetails => None,
+            CacheKind::Names => Some(Duration::from_secs(24 * 60 * 60)),
+            // Short enough that a rank change after the last match shows up in the next one.
+            CacheKind::Mmr => Some(Duration::from_secs(10 * 60)),
+            CacheKind::Content => Some(Duration::from_secs(6 * 60 * 60)),
+        }
+    }
+}
+
+pub fn init(dir: PathBuf) {
+    let _ = CACHE_DIR.set(dir);
+}
+
+fn sanitize(key: &str) -> String {
+    key.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+fn entry_path(kind: CacheKind, key: &str) -> Option<PathBuf> {
+    let dir = CACHE_DIR.get()?;
+    Some(dir.join(kind.dir_name()).join(format!("{}.json", sanitize(key))))
+}
+
+fn is_fresh(path: &Path, ttl: Option<Duration>) -> bool {
+    let Some(ttl) = ttl else { return true };
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .map(|age| age < ttl)
+        .unwrap_or(false)
+}
+
+pub fn get(kind: CacheKind, key: &str) -> Option<String> {
+    let path = entry_path(kind, key)?;
+    if !is_fresh(&path, kind.ttl()) {
+        let _ = fs::remove_file(&path);
+        return None;
+    }
+    fs::read_to_string(&path).ok().filter(|s| !s.is_empty())
+}
+
+pub fn get_json(kind: CacheKind, key: &str) -> Option<serde_json::Value> {
+    let raw = get(kind, key)?;
+    match serde_json::from_str(&raw) {
+        Ok(v) => Some(v),
+        Err(_) => {
+            invalidate(kind, key);
+            None
+        }
+    }
+}
+
+pub fn put(kind: CacheKind, key: &str, body: &str) {
+    let Some(path) = entry_path(kind, key) else { return };
+    if let Some(parent) = path.parent() {
+        if let Err(e) = fs::create_dir_all(parent) {
+            log_error(&format!("[Cache] create {}: {}", parent.display(), e));
+            return;
+        }
+    }
+    let tmp = path.with_extension("tmp");
+    if let Err(e) = fs::write(&tmp, body).and_then(|_| fs::rename(&tmp, &path)) {
+        log_error(&format!("[Cache] write {}: {}", path.display(), e));
+        let _ = fs::remove_file(&tmp);
+    }
+}
+
+pub fn invalidate(kind: CacheKind, key: &str) {
+    if let Some(path) = entry_path(kind, key) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+pub fn clear() -> u64 {
+    let Some(dir) = CACHE_DIR.get() else { return 0 };
+    let mut removed = 0;
+    for kind in [CacheKind::MatchDetails, CacheKind::Names, CacheKind::Mmr, CacheKind::Content] {
+        let Ok(entries) = fs::read_dir(dir.join(kind.dir_name())) else { continue };
+        for entry in entries.flatten() {
+            if fs::remove_file(entry.path()).is_ok() {
+                removed += 1;
+            }
+        }
+    }
+    removed
+}
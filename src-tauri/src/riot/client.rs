@@ -0,0 +1,179 @@
+use std::sync::Mutex;
+
+use serde::de::DeserializeOwned;
+
+use super::connection::refresh_tokens;
+use super::error::RiotError;
+use super::http;
+use super::logging::log_info;
+use super::models::decode_str;
+use super::types::ConnectionState;
+
+#[derive(Clone)]
+struct Tokens {
+    access_token: String,
+    entitlements: String,
+}
+
+pub struct RiotClient<'a> {
+    state: &'a Mutex<ConnectionState>,
+    tokens: Mutex<Tokens>,
+    puuid: String,
+    region: String,
+    shard: String,
+    client_version: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Service {
+    Pd,
+    Glz,
+}
+
+pub struct Api<'c> {
+    client: &'c RiotClient<'c>,
+    service: Service,
+}
+
+fn read_tokens(state: &Mutex<ConnectionState>) -> Result<Tokens, RiotError> {
+    let s = state.lock().map_err(|e| e.to_string())?;
+    if !s.connected {
+        return Err(RiotError::NotConnected);
+    }
+    Ok(Tokens {
+        access_token: s.access_token.clone().ok_or("No access_token")?,
+        entitlements: s.entitlements.clone().ok_or("No entitlements")?,
+    })
+}
+
+impl<'a> RiotClient<'a> {
+    pub fn from_state(state: &'a Mutex<ConnectionState>) -> Result<Self, RiotError> {
+        let tokens = read_tokens(state)?;
+        let s = state.lock().map_err(|e| e.to_string())?;
+        Ok(RiotClient {
+            state,
+            tokens: Mutex::new(tokens),
+            puuid: s.puuid.clone().ok_or("No puuid")?,
+            region: s.region.clone().ok_or("No region")?,
+            shard: s.shard.clone().ok_or("No shard")?,
+            client_version: s.client_version.clone().ok_or("No client_version")?,
+        })
+    }
+
+    pub fn puuid(&self) -> &str {
+        &self.puuid
+    }
+
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    pub fn shard(&self) -> &str {
+        &self.shard
+    }
+
+    pub fn pd(&self) -> Api<'_> {
+        Api { client: self, service: Service::Pd }
+    }
+
+    pub fn glz(&self) -> Api<'_> {
+        Api { client: self, service: Service::Glz }
+    }
+
+    fn tokens(&self) -> Tokens {
+        self.tokens.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn refresh(&self, stale: &Tokens) -> Result<Tokens, RiotError> {
+        let mut current = self.tokens.lock().unwrap_or_else(|e| e.into_inner());
+        if current.access_token != stale.access_token {
+            return Ok(current.clone());
+        }
+        log_info("[Client] Credentials rejected, refreshing tokens...");
+        refresh_tokens(self.state)?;
+        *current = read_tokens(self.state)?;
+        Ok(current.clone())
+    }
+
+    fn with_refresh<T>(&self, call: impl Fn(&Tokens) -> Result<T, RiotError>) -> Result<T, RiotError> {
+        let tokens = self.tokens();
+        match call(&tokens) {
+            Err(e) if e.is_credentials_invalid() => call(&self.refresh(&tokens)?),
+            other => other,
+        }
+    }
+}
+
+impl Api<'_> {
+    pub fn get(&self, path: &str) -> Result<String, RiotError> {
+        let c = self.client;
+        c.with_refresh(|t| match self.service {
+            Service::Pd => http::pd_get(&c.shard, path, &t.access_token, &t.entitlements, &c.client_version),
+            Service::Glz => http::glz_get(&c.region, &c.shard, path, &t.access_token, &t.entitlements, &c.client_version),
+        })
+    }
+
+    pub fn get_json(&self, path: &str) -> Result<serde_json::Value, RiotError> {
+        self.get_as(path)
+    }
+
+    pub fn get_as<T: DeserializeOwned>(&self, path: &str) -> Result<T, RiotError> {
+        decode_str(path, &self.get(path)?)
+    }
+
+    pub fn put(&self, path: &str, body: &serde_json::Value) -> Result<String, RiotError> {
+        let c = self.client;
+        match self.service {
+            Service::Pd => c.with_refresh(|t| http::pd_put(&c.shard, path, body, &t.access_token, &t.entitlements, &c.client_version)),
+            Service::Glz => Err(RiotError::Local(format!("PUT {} is not supported on GLZ", path))),
+        }
+    }
+
+    pub fn post(&self, path: &str) -> Result<String, RiotError> {
+        let c = self.client;
+        match self.service {
+            Service::Glz => c.with_refresh(|t| http::glz_post(&c.region, &c.shard, path, &t.access_token, &t.entitlements, &c.client_version)),
+            Service::Pd => Err(RiotError::Local(format!("POST {} is not supported on PD", path))),
+        }
+    }
+
+    pub fn post_json(&self, path: &str, body: &serde_json::Value) -> Result<String, RiotError> {
+        let c = self.client;
+        match self.service {
+            Service::Glz => c.with_refresh(|t| http::glz_post_body(&c.region, &c.shard, path, body, &t.access_token, &t.entitlements, &c.client_version)),
+            Service::Pd => Err(RiotError::Local(format!("POST {} is not supported on PD", path))),
+        }
+    }
+
+    pub fn delete(&self, path: &str) -> Result<String, RiotError> {
+        let c = self.client;
+        match self.service {
+            Service::Glz => c.with_refresh(|t| http::glz_delete(&c.region, &c.shard, path, &t.access_token, &t.entitlements, &c.client_version)),
+            Service::Pd => Err(RiotError::Local(format!("DELETE {} is not supported on PD", path))),
+        }
+    }
+
+    pub fn batch_get(&self, paths: &[String]) -> Result<Vec<Result<serde_json::Value, RiotError>>, RiotError> {
+        let c = self.client;
+        if self.service != Service::Pd {
+            return Err(RiotError::Local("Batch GET is only supported on PD".to_string()));
+        }
+        let tokens = c.tokens();
+        let mut results = http::pd_batch_get(&c.shard, paths, &tokens.access_token, &tokens.entitlements, &c.client_version)?;
+
+        let rejected: Vec<usize> = results.iter().enumerate()
+            .filter(|(_, r)| matches!(r, Err(e) if e.is_credentials_invalid()))
+            .map(|(i, _)| i)
+            .collect();
+        if rejected.is_empty() {
+            return Ok(results);
+        }
+        let tokens = c.refresh(&tokens)?;
+        let retry_paths: Vec<String> = rejected.iter().map(|&i| paths[i].clone()).collect();
+        let retried = http::pd_batch_get(&c.shard, &retry_paths, &tokens.access_token, &tokens.entitlements, &c.client_version)?;
+        for (i, result) in rejected.into_iter().zip(retried) {
+            results[i] = result;
+        }
+        Ok(results)
+    }
+}
@@ -1,18 +1,46 @@
 use base64::Engine;
 use std::sync::Mutex;
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
 
 use super::types::{ConnectionState, PlayerInfo};
-use super::http::{local_get, https_get, authed_get, pd_get};
-use super::process::{read_lockfile, is_pid_alive, is_riot_client_running, parse_region_shard};
+use super::error::{RiotError, TransportCause};
+use super::http::{local_get, authed_get, pd_get};
+use super::process::{read_lockfile, is_riot_client_running};
+use super::procinfo::is_pid_alive;
+use super::jwt::{self, TokenClaims};
 use super::logging::{log_info, log_error};
+use super::profiles;
+use super::region::{self, RegionShard};
+use super::version;
+use super::watcher;
+
+// Refresh this long before the earlier of the two tokens expires.
+const REFRESH_MARGIN_SECS: u64 = 300;
+const MIN_REFRESH_GAP_SECS: u64 = 60;
+// Used when a token has no readable `exp` claim.
+const FALLBACK_TOKEN_LIFETIME_SECS: u64 = 600;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenInfo {
+    pub access_token: TokenClaims,
+    pub entitlements: TokenClaims,
+    pub fetched_secs_ago: Option<u64>,
+    pub expires_in_secs: Option<u64>,
+    pub refresh_in_secs: Option<u64>,
+}
 
-pub fn connect_and_store(state: &Mutex<ConnectionState>) -> Result<PlayerInfo, String> {
+// The frontend and the lockfile watcher can both start a connect; run them one at a time.
+static CONNECT_LOCK: Mutex<()> = Mutex::new(());
+
+pub fn connect_and_store(state: &Mutex<ConnectionState>) -> Result<PlayerInfo, RiotError> {
+    let _guard = CONNECT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
     log_info("[Connect] Reading lockfile...");
     let (pid, port, password) = read_lockfile()?;
 
     if !is_pid_alive(pid) {
-        return Err(format!("Riot Client PID {} is dead (stale lockfile)", pid));
+        return Err(RiotError::Local(format!("Riot Client PID {} is dead (stale lockfile)", pid)));
     }
 
     let local_auth = format!(
@@ -23,13 +51,13 @@ pub fn connect_and_store(state: &Mutex<ConnectionState>) -> Result<PlayerInfo, S
     log_info(&format!("[Connect] Fetching entitlements (port={}, pid={})...", port, pid));
     let tokens_raw = match local_get(port, &local_auth, "/entitlements/v1/token") {
         Ok(r) => r,
-        Err(e) if e.contains("ECONNREFUSED") || e.contains("connect ECONNREFUSED") => {
-            return Err(format!("Riot Client API refused connection on port {} (restart Riot Client)", port));
+        Err(RiotError::Transport { cause: TransportCause::Connect, .. }) => {
+            return Err(RiotError::Local(format!("Riot Client API refused connection on port {} (restart Riot Client)", port)));
         }
         Err(e) => return Err(e),
     };
-    let tokens: serde_json::Value =
-        serde_json::from_str(&tokens_raw).map_err(|e| format!("Parse tokens: {}", e))?;
+    let tokens: serde_json::Value = serde_json::from_str(&tokens_raw)
+        .map_err(|e| RiotError::decode("/entitlements/v1/token", e))?;
 
     let access_token = tokens["accessToken"]
         .as_str()
@@ -65,22 +93,13 @@ pub fn connect_and_store(state: &Mutex<ConnectionState>) -> Result<PlayerInfo, S
     }
     log_info(&format!("[Connect] player={}#{}", game_name, game_tag));
 
-    let (region, shard) = parse_region_shard()?;
-
-    let client_version = match https_get("https://valorant-api.com/v1/version") {
-        Ok(body) => {
-            let clean = body.trim().trim_end_matches('\0');
-            match serde_json::from_str::<serde_json::Value>(clean) {
-                Ok(v) => v["data"]["riotClientVersion"]
-                    .as_str()
-                    .unwrap_or("unknown")
-                    .to_string(),
-                Err(_) => "unknown".to_string(),
-            }
-        }
-        Err(_) => "unknown".to_string(),
+    let RegionShard { region, shard, .. } = region::resolve(port, &local_auth, &access_token)?;
+
+    let (client_version, client_version_source) = match version::resolve(port, &local_auth) {
+        Some(v) => (v.version, Some(v.source)),
+        None => ("unknown".to_string(), None),
     };
-    log_info(&format!("[Connect] version={}", client_version));
+    log_info(&format!("[Connect] version={} (source={:?})", client_version, client_version_source));
 
     let mut player_card_url: Option<String> = None;
     let mut loadout_debug: Option<String> = None;
@@ -108,6 +127,7 @@ pub fn connect_and_store(state: &Mutex<ConnectionState>) -> Result<PlayerInfo, S
         region: region.clone(),
         shard: shard.clone(),
         client_version: client_version.clone(),
+        client_version_source,
         player_card_url: player_card_url.clone(),
         rso_debug,
         loadout_debug,
@@ -115,6 +135,7 @@ pub fn connect_and_store(state: &Mutex<ConnectionState>) -> Result<PlayerInfo, S
 
     let mut s = state.lock().map_err(|e| e.to_string())?;
     s.connected = true;
+    s.reconnecting = false;
     s.port = Some(port);
     s.local_auth = Some(local_auth);
     s.access_token = Some(access_token);
@@ -123,12 +144,16 @@ pub fn connect_and_store(state: &Mutex<ConnectionState>) -> Result<PlayerInfo, S
     s.region = Some(region);
     s.shard = Some(shard);
     s.client_version = Some(client_version);
+    s.client_version_source = client_version_source;
     s.game_name = Some(game_name);
     s.game_tag = Some(game_tag);
     s.player_card_url = player_card_url;
     s.token_fetched_at = Some(Instant::now());
+    drop(s);
 
+    profiles::activate(&info);
     log_info("[Connect] Connected successfully");
+    watcher::wake();
     Ok(info)
 }
 
@@ -144,29 +169,24 @@ pub fn health_check(state: &Mutex<ConnectionState>) -> Option<PlayerInfo> {
     }
 
     if !is_riot_client_running() {
-        log_error("[Health] Riot Client not running, disconnecting");
-        disconnect(state);
+        log_error("[Health] Riot Client not running, waiting for it to come back");
+        suspend(state);
         return None;
     }
 
-    let needs_refresh = {
-        let s = state.lock().ok()?;
-        match s.token_fetched_at {
-            Some(t) => t.elapsed().as_secs() > 600,
-            None => true,
-        }
-    };
+    let expiry_known = token_expiry(&*state.lock().ok()?).is_some();
 
-    if needs_refresh {
-        log_info("[Health] Token expired, refreshing...");
+    // The lockfile watcher normally refreshes on time; this catches a missed deadline.
+    if refresh_due_in(state).is_some_and(|d| d.is_zero()) {
+        log_info("[Health] Token close to expiry, refreshing...");
         if let Err(e) = refresh_tokens(state) {
-            log_error(&format!("[Health] Token refresh failed: {}, disconnecting", e));
-            disconnect(state);
+            end_after_failed_refresh(state, "Token refresh failed", &e);
             return None;
         }
     }
 
-    let should_validate = {
+    // Without a readable `exp` the only way to know a token still works is to use it.
+    let should_validate = !expiry_known && {
         let s = state.lock().ok()?;
         match s.last_token_check {
             Some(t) => t.elapsed().as_secs() > 60,
@@ -181,8 +201,7 @@ pub fn health_check(state: &Mutex<ConnectionState>) -> Option<PlayerInfo> {
         if !validate_token(state) {
             log_error("[Health] Token validation failed, refreshing...");
             if let Err(e) = refresh_tokens(state) {
-                log_error(&format!("[Health] Token refresh also failed: {}, disconnecting", e));
-                disconnect(state);
+                end_after_failed_refresh(state, "Token refresh also failed", &e);
                 return None;
             }
             if !validate_token(state) {
@@ -197,6 +216,18 @@ pub fn health_check(state: &Mutex<ConnectionState>) -> Option<PlayerInfo> {
     get_cached_player(state)
 }
 
+// Only a rejected credential ends the session. A timeout, a dropped connection or a Riot
+// Client that is restarting suspends it, and the lockfile watcher picks it back up.
+fn end_after_failed_refresh(state: &Mutex<ConnectionState>, what: &str, e: &RiotError) {
+    if e.is_credentials_invalid() {
+        log_error(&format!("[Health] {}: {}, disconnecting", what, e));
+        disconnect(state);
+    } else {
+        log_error(&format!("[Health] {}: {}, waiting to reconnect", what, e));
+        suspend(state);
+    }
+}
+
 fn validate_token(state: &Mutex<ConnectionState>) -> bool {
     let (shard, access_token, entitlements, client_version, puuid) = {
         let s = match state.lock() {
@@ -212,28 +243,22 @@ fn validate_token(state: &Mutex<ConnectionState>) -> bool {
 
     let path = format!("/personalization/v2/players/{}/playerloadout", puuid);
     match pd_get(&shard, &path, &access_token, &entitlements, &client_version) {
-        Ok(body) => {
-            if body.contains("CREDENTIALS_INVALID") || body.contains("\"httpStatus\":401") {
-                log_error("[Health] Token check: CREDENTIALS_INVALID");
-                return false;
-            }
-            true
+        Ok(_) => true,
+        Err(e) if e.is_credentials_invalid() => {
+            log_error(&format!("[Health] Token check error: {}", e));
+            false
         }
         Err(e) => {
-            if e.contains("401") || e.contains("CREDENTIALS_INVALID") {
-                log_error(&format!("[Health] Token check error: {}", e));
-                return false;
-            }
             log_error(&format!("[Health] Token check request failed: {} (network issue, keeping token)", e));
             true
         }
     }
 }
 
-fn refresh_tokens(state: &Mutex<ConnectionState>) -> Result<(), String> {
+pub fn refresh_tokens(state: &Mutex<ConnectionState>) -> Result<(), RiotError> {
     let (pid, port, password) = read_lockfile().map_err(|e| format!("lockfile re-read: {}", e))?;
     if !is_pid_alive(pid) {
-        return Err(format!("Riot Client PID {} is dead", pid));
+        return Err(RiotError::Local(format!("Riot Client PID {} is dead", pid)));
     }
     let local_auth = format!(
         "Basic {}",
@@ -242,13 +267,17 @@ fn refresh_tokens(state: &Mutex<ConnectionState>) -> Result<(), String> {
 
     log_info(&format!("[Health] Refreshing tokens (port={}, pid={})...", port, pid));
     let tokens_raw = local_get(port, &local_auth, "/entitlements/v1/token")?;
-    let tokens: serde_json::Value =
-        serde_json::from_str(&tokens_raw).map_err(|e| format!("Parse tokens: {}", e))?;
+    let tokens: serde_json::Value = serde_json::from_str(&tokens_raw)
+        .map_err(|e| RiotError::decode("/entitlements/v1/token", e))?;
 
     let access_token = tokens["accessToken"].as_str().ok_or("No accessToken")?.to_string();
     let entitlements_jwt = tokens["token"].as_str().ok_or("No entitlements token")?.to_string();
+    let subject = tokens["subject"].as_str().ok_or("No subject/puuid")?;
 
     let mut s = state.lock().map_err(|e| e.to_string())?;
+    if s.puuid.as_deref().is_some_and(|p| p != subject) {
+        return Err(RiotError::Local(format!("Signed-in account changed (puuid={})", subject)));
+    }
     s.port = Some(port);
     s.local_auth = Some(local_auth);
     s.access_token = Some(access_token);
@@ -258,14 +287,42 @@ fn refresh_tokens(state: &Mutex<ConnectionState>) -> Result<(), String> {
     Ok(())
 }
 
+// PUUID of whoever is signed in to the Riot Client right now, which may no
+// longer be the account the stored tokens belong to.
+pub fn session_puuid(state: &Mutex<ConnectionState>) -> Option<String> {
+    let (port, auth) = {
+        let s = state.lock().ok()?;
+        (s.port?, s.local_auth.clone()?)
+    };
+    let raw = local_get(port, &auth, "/entitlements/v1/token").ok()?;
+    let tokens: serde_json::Value = serde_json::from_str(&raw).ok()?;
+    tokens["subject"].as_str().filter(|s| !s.is_empty()).map(|s| s.to_string())
+}
+
 pub fn disconnect(state: &Mutex<ConnectionState>) {
+    end_session(state, false);
+}
+
+// Riot Client went away: drop the tokens but keep the player identity so the
+// lockfile watcher can pick the session back up when a new lockfile appears.
+pub fn suspend(state: &Mutex<ConnectionState>) {
+    end_session(state, true);
+}
+
+pub fn is_reconnecting(state: &Mutex<ConnectionState>) -> bool {
+    state.lock().map(|s| s.reconnecting).unwrap_or(false)
+}
+
+fn end_session(state: &Mutex<ConnectionState>, reconnecting: bool) {
     if let Ok(mut s) = state.lock() {
         s.connected = false;
+        s.reconnecting = reconnecting;
         s.port = None;
         s.local_auth = None;
         s.access_token = None;
         s.entitlements = None;
     }
+    watcher::wake();
 }
 
 pub fn get_status(state: &Mutex<ConnectionState>) -> String {
@@ -273,6 +330,8 @@ pub fn get_status(state: &Mutex<ConnectionState>) -> String {
         Ok(s) => {
             if s.connected {
                 "connected".to_string()
+            } else if s.reconnecting {
+                "reconnecting".to_string()
             } else {
                 "disconnected".to_string()
             }
@@ -293,12 +352,65 @@ pub fn get_cached_player(state: &Mutex<ConnectionState>) -> Option<PlayerInfo> {
         region: s.region.clone()?,
         shard: s.shard.clone()?,
         client_version: s.client_version.clone()?,
+        client_version_source: s.client_version_source,
         player_card_url: s.player_card_url.clone(),
         rso_debug: None,
         loadout_debug: None,
     })
 }
 
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn token_expiry(s: &ConnectionState) -> Option<u64> {
+    [&s.access_token, &s.entitlements].into_iter()
+        .flatten()
+        .filter_map(|t| jwt::claims(t).ok()?.expires_at)
+        .min()
+}
+
+// Time left until the tokens should be refreshed: REFRESH_MARGIN_SECS before the earlier
+// `exp`, or FALLBACK_TOKEN_LIFETIME_SECS after the fetch when neither token has one.
+// `None` while not connected.
+pub fn refresh_due_in(state: &Mutex<ConnectionState>) -> Option<Duration> {
+    let s = state.lock().ok()?;
+    if !s.connected {
+        return None;
+    }
+    Some(Duration::from_secs(refresh_due_secs(&s)))
+}
+
+fn refresh_due_secs(s: &ConnectionState) -> u64 {
+    let fetched_secs_ago = s.token_fetched_at.map(|t| t.elapsed().as_secs());
+    match token_expiry(s) {
+        // The Riot Client may hand back the same token until it renews it
+        // itself, so don't ask more than once a minute.
+        Some(exp) => exp.saturating_sub(now_secs() + REFRESH_MARGIN_SECS)
+            .max(fetched_secs_ago.map_or(0, |a| MIN_REFRESH_GAP_SECS.saturating_sub(a))),
+        None => fetched_secs_ago.map_or(0, |a| FALLBACK_TOKEN_LIFETIME_SECS.saturating_sub(a)),
+    }
+}
+
+pub fn get_token_info(state: &Mutex<ConnectionState>) -> Result<TokenInfo, RiotError> {
+    let s = state.lock().map_err(|e| e.to_string())?;
+    if !s.connected {
+        return Err(RiotError::NotConnected);
+    }
+    let access_token = jwt::claims(s.access_token.as_deref().unwrap_or_default())?;
+    let entitlements = jwt::claims(s.entitlements.as_deref().unwrap_or_default())?;
+    let now = now_secs();
+    let expiry = token_expiry(&s);
+    Ok(TokenInfo {
+        access_token,
+        entitlements,
+        fetched_secs_ago: s.token_fetched_at.map(|t| t.elapsed().as_secs()),
+        expires_in_secs: expiry.map(|exp| exp.saturating_sub(now)),
+        // Same schedule the lockfile watcher refreshes on.
+        refresh_in_secs: Some(refresh_due_secs(&s)),
+    })
+}
+
 pub fn get_token_age_secs(state: &Mutex<ConnectionState>) -> u64 {
     if let Ok(s) = state.lock() {
         if let Some(t) = s.token_fetched_at {
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use super::cache::{self, CacheKind};
+use super::error::RiotError;
+use super::http::https_get;
+use super::logging::{log_error, log_info};
+
+const CACHE_KEY: &str = "item-index";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentItem {
+    pub name: String,
+    pub icon: Option<String>,
+}
+
+// UUID (lowercase) → name and icon, built from valorant-api.com. Skin levels map to their
+// skin and buddy levels to their buddy, since loadouts may reference either.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContentIndex {
+    pub weapons: HashMap<String, ContentItem>,
+    pub skins: HashMap<String, ContentItem>,
+    pub chromas: HashMap<String, ContentItem>,
+    pub buddies: HashMap<String, ContentItem>,
+    pub sprays: HashMap<String, ContentItem>,
+    pub cards: HashMap<String, ContentItem>,
+}
+
+static INDEX: Mutex<Option<Arc<ContentIndex>>> = Mutex::new(None);
+
+fn fetch_data(path: &str) -> Result<Vec<serde_json::Value>, RiotError> {
+    let body = https_get(&format!("https://valorant-api.com{}", path))?;
+    let v: serde_json::Value = serde_json::from_str(&body).map_err(|e| format!("parse {}: {}", path, e))?;
+    match v["data"].as_array() {
+        Some(list) => Ok(list.clone()),
+        None => Err(RiotError::Local(format!("{} returned no data", path))),
+    }
+}
+
+fn item(v: &serde_json::Value, icon_keys: &[&str]) -> Option<(String, ContentItem)> {
+    let uuid = v["uuid"].as_str()?.to_lowercase();
+    let name = v["displayName"].as_str()?.to_string();
+    let icon = icon_keys.iter().find_map(|k| v[*k].as_str()).map(|s| s.to_string());
+    Some((uuid, ContentItem { name, icon }))
+}
+
+fn build() -> Result<ContentIndex, RiotError> {
+    let index = index_from(
+        &fetch_data("/v1/weapons")?,
+        &fetch_data("/v1/buddies")?,
+        &fetch_data("/v1/sprays")?,
+        &fetch_data("/v1/playercards")?,
+    );
+    log_info(&format!(
+        "[Content] Built item index: {} weapons, {} skins, {} chromas, {} buddies, {} sprays, {} cards",
+        index.weapons.len(), index.skins.len(), index.chromas.len(), index.buddies.len(), index.sprays.len(), index.cards.len()
+    ));
+    Ok(index)
+}
+
+pub(crate) fn index_from(
+    weapons: &[serde_json::Value],
+    buddies: &[serde_json::Value],
+    sprays: &[serde_json::Value],
+    cards: &[serde_json::Value],
+) -> ContentIndex {
+    let mut index = ContentIndex::default();
+
+    for weapon in weapons {
+        if let Some((uuid, w)) = item(weapon, &["displayIcon"]) {
+            index.weapons.insert(uuid, w);
+        }
+        for skin in weapon["skins"].as_array().into_iter().flatten() {
+            let levels = skin["levels"].as_array().cloned().unwrap_or_default();
+            let chromas = skin["chromas"].as_array().cloned().unwrap_or_default();
+            let Some((uuid, mut s)) = item(skin, &["displayIcon"]) else { continue };
+            // Default skins have no icon of their own.
+            if s.icon.is_none() {
+                s.icon = levels.first().and_then(|l| l["displayIcon"].as_str())
+                    .or_else(|| chromas.first().and_then(|c| c["fullRender"].as_str()))
+                    .map(|s| s.to_string());
+            }
+            for level in &levels {
+                if let Some(id) = level["uuid"].as_str() {
+                    index.skins.insert(id.to_lowercase(), s.clone());
+                }
+            }
+            for chroma in &chromas {
+                if let Some((id, c)) = item(chroma, &["fullRender", "displayIcon", "swatch"]) {
+                    index.chromas.insert(id, c);
+                }
+            }
+            index.skins.insert(uuid, s);
+        }
+    }
+
+    for buddy in buddies {
+        let Some((uuid, b)) = item(buddy, &["displayIcon"]) else { continue };
+        for level in buddy["levels"].as_array().into_iter().flatten() {
+            if let Some(id) = level["uuid"].as_str() {
+                index.buddies.insert(id.to_lowercase(), b.clone());
+            }
+        }
+        index.buddies.insert(uuid, b);
+    }
+
+    index.sprays = sprays.iter()
+        .filter_map(|s| item(s, &["fullTransparentIcon", "displayIcon"]))
+        .collect();
+    index.cards = cards.iter()
+        .filter_map(|c| item(c, &["smallArt", "displayIcon"]))
+        .collect();
+    index
+}
+
+// Memory, then the content cache, then a fresh build from valorant-api.com.
+pub fn index() -> Result<Arc<ContentIndex>, RiotError> {
+    let mut slot = INDEX.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(index) = slot.as_ref() {
+        return Ok(index.clone());
+    }
+    let cached = cache::get_json(CacheKind::Content, CACHE_KEY)
+        .and_then(|v| serde_json::from_value::<ContentIndex>(v).ok());
+    let index = match cached {
+        Some(index) => index,
+        None => {
+            let index = build()?;
+            match serde_json::to_string(&index) {
+                Ok(json) => cache::put(CacheKind::Content, CACHE_KEY, &json),
+                Err(e) => log_error(&format!("[Content] serialize item index: {}", e)),
+            }
+            index
+        }
+    };
+    let index = Arc::new(index);
+    *slot = Some(index.clone());
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> ContentIndex {
+        let v: serde_json::Value = serde_json::from_str(include_str!("../../fixtures/valorant_api_content.json")).unwrap();
+        let list = |key: &str| v[key].as_array().cloned().unwrap();
+        index_from(&list("weapons"), &list("buddies"), &list("sprays"), &list("playercards"))
+    }
+
+    fn icon(table: &HashMap<String, ContentItem>, id: &str) -> Option<String> {
+        table[id].icon.clone()
+    }
+
+    #[test]
+    fn skin_levels_resolve_to_their_skin() {
+        let index = fixture();
+        for id in ["4f7e5b5c-4a36-4c7a-8f4a-1d2e3f405061", "4f7e5b5c-1111-4c7a-8f4a-1d2e3f4050a1", "4f7e5b5c-1111-4c7a-8f4a-1d2e3f4050a2"] {
+            assert_eq!(index.skins[id].name, "Reaver Vandal");
+            assert!(icon(&index.skins, id).unwrap().ends_with("4f7e5b5c-4a36-4c7a-8f4a-1d2e3f405061/displayicon.png"));
+        }
+        assert_eq!(index.weapons["9c82e19d-4575-0200-1a81-3eacf00cf872"].name, "Vandal");
+        assert_eq!(index.skins.len(), 7);
+    }
+
+    #[test]
+    fn default_skins_borrow_a_level_or_chroma_icon() {
+        let index = fixture();
+        // Standard Classic has a level icon; Standard Vandal only a chroma render.
+        assert!(icon(&index.skins, "3a9c4d2e-5f60-4718-8a9b-0c1d2e3f4a51").unwrap().ends_with("weaponskinlevels/3a9c4d2e-1111-4718-8a9b-0c1d2e3f4aa1/displayicon.png"));
+        assert!(icon(&index.skins, "7e1b5a6d-0b9e-4b8a-9f1e-2c3d4e5f6071").unwrap().ends_with("7e1b5a6d-0000-4b8a-9f1e-2c3d4e5f60c1/fullrender.png"));
+        assert_eq!(icon(&index.skins, "7e1b5a6d-1111-4b8a-9f1e-2c3d4e5f60a1"), icon(&index.skins, "7e1b5a6d-0b9e-4b8a-9f1e-2c3d4e5f6071"));
+    }
+
+    #[test]
+    fn chromas_prefer_the_full_render() {
+        let index = fixture();
+        assert!(icon(&index.chromas, "4f7e5b5c-0000-4c7a-8f4a-1d2e3f4050c1").unwrap().ends_with("fullrender.png"));
+        // Stored lowercase even when the API answers in uppercase.
+        assert!(icon(&index.chromas, "4f7e5b5c-0000-4c7a-8f4a-1d2e3f4050c2").unwrap().ends_with("4050c2/displayicon.png"));
+        assert_eq!(index.chromas["4f7e5b5c-0000-4c7a-8f4a-1d2e3f4050c2"].name, "Reaver Vandal Variant 1 Red");
+    }
+
+    #[test]
+    fn buddies_sprays_and_cards_resolve() {
+        let index = fixture();
+        assert_eq!(index.buddies["b1d2c3e4-1111-4a7b-8c9d-0e1f2a3b4ca1"].name, "Spike Buddy");
+        assert_eq!(index.buddies["b1d2c3e4-5f60-4a7b-8c9d-0e1f2a3b4c51"].name, "Spike Buddy");
+        assert!(icon(&index.sprays, "c5e6f7a8-9b0c-4d1e-8f2a-3b4c5d6e7f81").unwrap().ends_with("fulltransparenticon.png"));
+        assert!(icon(&index.sprays, "c5e6f7a8-0000-4d1e-8f2a-3b4c5d6e7f82").unwrap().ends_with("displayicon.png"));
+        // Entries without a name are left out rather than indexed with an empty one.
+        assert!(!index.sprays.contains_key("c5e6f7a8-1111-4d1e-8f2a-3b4c5d6e7f83"));
+        assert!(icon(&index.cards, "9fb348bc-41a0-91ad-8a3e-818035c4e561").unwrap().ends_with("smallart.png"));
+    }
+}
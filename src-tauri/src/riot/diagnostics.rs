@@ -0,0 +1,186 @@
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+use serde::Serialize;
+
+use super::error::RiotError;
+use super::http::{authed_get, glz_get, local_get, pd_get};
+use super::logging::log_info;
+use super::paths;
+use super::process::read_lockfile;
+use super::procinfo::is_pid_alive;
+use super::region;
+use super::version;
+use super::xmpp::{decode_affinity, fetch_pas_token};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+    Pass,
+    Fail,
+    Skip,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticStep {
+    pub name: String,
+    pub status: StepStatus,
+    pub detail: String,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticsReport {
+    pub started_at: u64,
+    pub total_ms: u64,
+    pub passed: bool,
+    pub app_version: String,
+    pub os: String,
+    pub steps: Vec<DiagnosticStep>,
+}
+
+struct Runner {
+    steps: Vec<DiagnosticStep>,
+}
+
+impl Runner {
+    // Runs `check` unless an earlier step it depends on failed; `None` marks it skipped.
+    fn step<T>(&mut self, name: &str, ready: bool, check: impl FnOnce() -> Result<(T, String), String>) -> Option<T> {
+        if !ready {
+            self.steps.push(DiagnosticStep {
+                name: name.to_string(),
+                status: StepStatus::Skip,
+                detail: "Skipped, an earlier step failed".to_string(),
+                duration_ms: 0,
+            });
+            return None;
+        }
+        let start = Instant::now();
+        let result = check();
+        let duration_ms = start.elapsed().as_millis() as u64;
+        let (status, detail, value) = match result {
+            Ok((value, detail)) => (StepStatus::Pass, detail, Some(value)),
+            Err(detail) => (StepStatus::Fail, detail, None),
+        };
+        log_info(&format!("[Diagnostics] {}: {:?} ({}ms) {}", name, status, duration_ms, detail));
+        self.steps.push(DiagnosticStep { name: name.to_string(), status, detail, duration_ms });
+        value
+    }
+}
+
+// Any HTTP status proves the host answered; only a transport error or a
+// rejected token counts as unreachable.
+fn reachable(result: Result<String, RiotError>) -> Result<((), String), String> {
+    match result {
+        Ok(_) => Ok(((), "OK".to_string())),
+        Err(e) if e.is_credentials_invalid() => Err(e.to_string()),
+        Err(e) => match e.status() {
+            Some(status) => Ok(((), format!("Reachable (HTTP {})", status))),
+            None => Err(e.to_string()),
+        },
+    }
+}
+
+pub fn run_diagnostics(app_version: &str) -> DiagnosticsReport {
+    let started = Instant::now();
+    let started_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let mut r = Runner { steps: Vec::new() };
+    log_info("[Diagnostics] Running connection diagnostics...");
+
+    let lockfile = r.step("Lockfile", true, || {
+        let path = paths::resolve()?.lockfile();
+        let (pid, port, password) = read_lockfile()?;
+        Ok(((pid, port, password), format!("{} (pid={}, port={})", path.display(), pid, port)))
+    });
+
+    let pid_alive = r.step("Riot Client process", lockfile.is_some(), || {
+        let (pid, _, _) = lockfile.as_ref().ok_or("no lockfile")?;
+        if is_pid_alive(*pid) {
+            Ok(((), format!("PID {} is running", pid)))
+        } else {
+            Err(format!("PID {} is not running (stale lockfile)", pid))
+        }
+    });
+
+    let local = r.step("Local API", pid_alive.is_some(), || {
+        let (_, port, password) = lockfile.as_ref().ok_or("no lockfile")?;
+        let auth = format!(
+            "Basic {}",
+            base64::engine::general_purpose::STANDARD.encode(format!("riot:{}", password))
+        );
+        reachable(local_get(*port, &auth, "/riotclient/region-locale"))?;
+        Ok(((*port, auth), format!("https://127.0.0.1:{} answered", port)))
+    });
+
+    let tokens = r.step("Entitlements token", local.is_some(), || {
+        let (port, auth) = local.as_ref().ok_or("no local API")?;
+        let raw = local_get(*port, auth, "/entitlements/v1/token").map_err(|e| e.to_string())?;
+        let tokens: serde_json::Value = serde_json::from_str(&raw).map_err(|e| format!("parse token: {}", e))?;
+        let access = tokens["accessToken"].as_str().ok_or("No accessToken (not signed in?)")?.to_string();
+        let entitlements = tokens["token"].as_str().ok_or("No entitlements token")?.to_string();
+        let puuid = tokens["subject"].as_str().ok_or("No subject/puuid")?.to_string();
+        let detail = format!("puuid={}", puuid);
+        Ok(((access, entitlements, puuid), detail))
+    });
+
+    r.step("Account info", tokens.is_some(), || {
+        let (access, _, _) = tokens.as_ref().ok_or("no tokens")?;
+        let raw = authed_get("https://auth.riotgames.com/userinfo", access).map_err(|e| e.to_string())?;
+        let info: serde_json::Value = serde_json::from_str(&raw).map_err(|e| format!("parse userinfo: {}", e))?;
+        let name = info["acct"]["game_name"].as_str().unwrap_or("");
+        let tag = info["acct"]["tag_line"].as_str().unwrap_or("");
+        Ok(((), format!("{}#{}", name, tag)))
+    });
+
+    let region = r.step("Region/shard", true, || {
+        let (port, auth) = local.clone().unwrap_or_default();
+        let access = tokens.as_ref().map(|(a, _, _)| a.as_str()).unwrap_or_default();
+        let found = region::resolve(port, &auth, access)?;
+        let detail = format!("region={} shard={} (source: {})", found.region, found.shard, found.source);
+        Ok(((found.region, found.shard), detail))
+    });
+
+    let client_version = r.step("Client version", true, || {
+        let (port, auth) = local.clone().unwrap_or_default();
+        let v = version::resolve(port, &auth).ok_or("No source returned a client version")?;
+        let detail = format!("{} (source: {:?})", v.version, v.source);
+        Ok((v.version, detail))
+    });
+
+    let remote_ready = tokens.is_some() && region.is_some() && client_version.is_some();
+
+    r.step("PD reachability", remote_ready, || {
+        let (access, entitlements, puuid) = tokens.as_ref().ok_or("no tokens")?;
+        let (_, shard) = region.as_ref().ok_or("no shard")?;
+        let version = client_version.as_deref().unwrap_or_default();
+        let path = format!("/account-xp/v1/players/{}", puuid);
+        reachable(pd_get(shard, &path, access, entitlements, version))
+    });
+
+    r.step("GLZ reachability", remote_ready, || {
+        let (access, entitlements, puuid) = tokens.as_ref().ok_or("no tokens")?;
+        let (region, shard) = region.as_ref().ok_or("no region")?;
+        let version = client_version.as_deref().unwrap_or_default();
+        let path = format!("/parties/v1/players/{}", puuid);
+        reachable(glz_get(region, shard, &path, access, entitlements, version))
+    });
+
+    r.step("XMPP PAS token", tokens.is_some(), || {
+        let (access, _, _) = tokens.as_ref().ok_or("no tokens")?;
+        let pas = fetch_pas_token(access)?;
+        let affinity = decode_affinity(&pas)?;
+        Ok(((), format!("affinity={}", affinity)))
+    });
+
+    let passed = r.steps.iter().all(|s| s.status == StepStatus::Pass);
+    let total_ms = started.elapsed().as_millis() as u64;
+    log_info(&format!("[Diagnostics] Finished in {}ms, {}", total_ms, if passed { "all checks passed" } else { "some checks failed" }));
+    DiagnosticsReport {
+        started_at,
+        total_ms,
+        passed,
+        app_version: app_version.to_string(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        steps: r.steps,
+    }
+}
@@ -0,0 +1,606 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use super::client::RiotClient;
+use super::game::{party_size, pregame_quit};
+use super::logging::{log_error, log_info};
+use super::roster::fetch_mmrs;
+use super::types::ConnectionState;
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+// Default competitive dodge penalty: 3 RR for the first dodge in 24h, 10 RR for every further
+// one. No Riot API reports the penalty, so these are only the values players currently see.
+// `penalty_rr_first` / `penalty_rr_repeat` in `DodgeConfig` override them.
+const PENALTY_RR_FIRST: u32 = 3;
+const PENALTY_RR_REPEAT: u32 = 10;
+
+static LEDGER_PATH: OnceLock<PathBuf> = OnceLock::new();
+static CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+static LEDGER: Mutex<Option<Ledger>> = Mutex::new(None);
+static CONFIG: Mutex<Option<DodgeConfig>> = Mutex::new(None);
+static EVALUATED: Mutex<Option<String>> = Mutex::new(None);
+static DODGING: Mutex<Option<String>> = Mutex::new(None);
+static PENDING: Mutex<Option<String>> = Mutex::new(None);
+static SETTLED: Condvar = Condvar::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Team {
+    Ally,
+    Enemy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RankStat {
+    Avg,
+    Max,
+}
+
+// Tiers as in `get_player_mmr` (3 = Iron 1 ... 27 = Radiant); unranked players are ignored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankCondition {
+    pub team: Team,
+    pub stat: RankStat,
+    pub min_tier: Option<u64>,
+    pub max_tier: Option<u64>,
+}
+
+// Every set field must match; empty lists and `None` match anything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DodgeRule {
+    pub name: String,
+    #[serde(default)]
+    pub maps: Vec<String>,
+    #[serde(default)]
+    pub queues: Vec<String>,
+    pub min_party_size: Option<usize>,
+    pub max_party_size: Option<usize>,
+    #[serde(default)]
+    pub ranks: Vec<RankCondition>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DodgeConfig {
+    pub enabled: bool,
+    pub dry_run: bool,
+    pub rules: Vec<DodgeRule>,
+    pub max_dodges_per_day: Option<u32>,
+    pub max_penalty_rr: Option<u32>,
+    #[serde(default)]
+    pub penalty_rr_first: Option<u32>,
+    #[serde(default)]
+    pub penalty_rr_repeat: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DodgeRecord {
+    pub at: u64,
+    pub match_id: String,
+    pub map_id: String,
+    pub queue_id: String,
+    pub rule: String,
+    pub penalty_rr: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Ledger {
+    accounts: BTreeMap<String, Vec<DodgeRecord>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DodgeStats {
+    pub dodges_today: u32,
+    pub penalty_rr_today: u32,
+    pub next_penalty_rr: u32,
+    pub max_dodges_per_day: Option<u32>,
+    pub max_penalty_rr: Option<u32>,
+    pub recent: Vec<DodgeRecord>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    NoMatch,
+    Dodged,
+    DryRun,
+    Refused,
+    Failed,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TeamRanks {
+    pub avg: Option<f64>,
+    pub max: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DodgeDecision {
+    pub match_id: String,
+    pub map_id: String,
+    pub queue_id: String,
+    pub party_size: Option<usize>,
+    pub ally: Option<TeamRanks>,
+    pub enemy: Option<TeamRanks>,
+    pub rule: Option<String>,
+    pub outcome: Outcome,
+    pub reason: String,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn load<T: Default + DeserializeOwned>(slot: &OnceLock<PathBuf>) -> T {
+    let Some(path) = slot.get() else { return T::default() };
+    let Ok(raw) = fs::read_to_string(path) else { return T::default() };
+    serde_json::from_str(&raw).unwrap_or_else(|e| {
+        log_error(&format!("[Dodge] {} is corrupt, starting fresh: {}", path.display(), e));
+        T::default()
+    })
+}
+
+fn save<T: Serialize>(slot: &OnceLock<PathBuf>, value: &T) {
+    let Some(path) = slot.get() else { return };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let Ok(body) = serde_json::to_string_pretty(value) else { return };
+    let tmp = path.with_extension("tmp");
+    if let Err(e) = fs::write(&tmp, body).and_then(|_| fs::rename(&tmp, path)) {
+        log_error(&format!("[Dodge] write {}: {}", path.display(), e));
+        let _ = fs::remove_file(&tmp);
+    }
+}
+
+// Records older than 24h are dropped on every access, so what is left is today's count.
+fn with_records<T>(puuid: &str, f: impl FnOnce(&mut Vec<DodgeRecord>) -> T) -> T {
+    let mut guard = LEDGER.lock().unwrap_or_else(|e| e.into_inner());
+    let ledger = guard.get_or_insert_with(|| load(&LEDGER_PATH));
+    let cutoff = now_secs().saturating_sub(DAY_SECS);
+    ledger.accounts.values_mut().for_each(|list| list.retain(|r| r.at >= cutoff));
+    ledger.accounts.retain(|_, list| !list.is_empty());
+    let records = ledger.accounts.entry(puuid.to_string()).or_default();
+    let result = f(records);
+    save(&LEDGER_PATH, ledger);
+    result
+}
+
+// Loads the saved config; the frontend reads and edits it through `get_config` / `set_config`.
+pub fn init(ledger: PathBuf, config: PathBuf) {
+    let _ = LEDGER_PATH.set(ledger);
+    let saved = config.is_file();
+    let _ = CONFIG_PATH.set(config);
+    if saved {
+        apply_config(load(&CONFIG_PATH), "loaded");
+    }
+}
+
+fn apply_config(config: DodgeConfig, how: &str) {
+    log_info(&format!(
+        "[Dodge] Config {}: enabled={} dry_run={} rules={} max_dodges={:?} max_penalty_rr={:?}",
+        how, config.enabled, config.dry_run, config.rules.len(), config.max_dodges_per_day, config.max_penalty_rr
+    ));
+    *CONFIG.lock().unwrap_or_else(|e| e.into_inner()) = Some(config);
+}
+
+pub fn set_config(config: DodgeConfig) {
+    save(&CONFIG_PATH, &config);
+    apply_config(config, "updated");
+}
+
+pub fn get_config() -> DodgeConfig {
+    CONFIG.lock().unwrap_or_else(|e| e.into_inner()).clone().unwrap_or_default()
+}
+
+fn next_penalty(config: &DodgeConfig, records: &[DodgeRecord], queue_id: &str) -> u32 {
+    if queue_id != "competitive" {
+        0
+    } else if records.iter().any(|r| r.penalty_rr > 0) {
+        config.penalty_rr_repeat.unwrap_or(PENALTY_RR_REPEAT)
+    } else {
+        config.penalty_rr_first.unwrap_or(PENALTY_RR_FIRST)
+    }
+}
+
+pub fn stats(state: &Mutex<ConnectionState>) -> DodgeStats {
+    let puuid = state.lock().ok().and_then(|s| s.puuid.clone()).unwrap_or_default();
+    let config = get_config();
+    with_records(&puuid, |records| DodgeStats {
+        dodges_today: records.len() as u32,
+        penalty_rr_today: records.iter().map(|r| r.penalty_rr).sum(),
+        next_penalty_rr: next_penalty(&config, records, "competitive"),
+        max_dodges_per_day: config.max_dodges_per_day,
+        max_penalty_rr: config.max_penalty_rr,
+        recent: records.clone(),
+    })
+}
+
+// Set while a dodge is being sent, so the agent select engine stands down for that match.
+pub fn is_dodging(match_id: &str) -> bool {
+    DODGING.lock().unwrap_or_else(|e| e.into_inner()).as_deref() == Some(match_id)
+}
+
+fn begin_evaluation(match_id: &str) {
+    *PENDING.lock().unwrap_or_else(|e| e.into_inner()) = Some(match_id.to_string());
+}
+
+fn end_evaluation(match_id: &str) {
+    let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+    if pending.as_deref() == Some(match_id) {
+        *pending = None;
+    }
+    SETTLED.notify_all();
+}
+
+// Blocks up to `timeout` while the rules for `match_id` are still being evaluated. Returns
+// false if they still are, so the agent select engine can check the match is still on.
+pub fn wait_for_evaluation(match_id: &str, timeout: Duration) -> bool {
+    let pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+    let (pending, _) = SETTLED
+        .wait_timeout_while(pending, timeout, |p| p.as_deref() == Some(match_id))
+        .unwrap_or_else(|e| e.into_inner());
+    pending.as_deref() != Some(match_id)
+}
+
+struct Context<'a> {
+    client: &'a RiotClient<'a>,
+    payload: &'a serde_json::Value,
+    party_size: Option<Option<usize>>,
+    ally: Option<Option<TeamRanks>>,
+    enemy: Option<Option<TeamRanks>>,
+}
+
+impl Context<'_> {
+    // Fetched on first use, so rules that only look at the map cost no extra calls.
+    fn party_size(&mut self) -> Option<usize> {
+        *self.party_size.get_or_insert_with(|| party_size(self.client)
+            .map_err(|e| log_error(&format!("[Dodge] party lookup failed: {}", e)))
+            .ok())
+    }
+
+    fn ranks(&mut self, team: Team) -> Option<TeamRanks> {
+        let (slot, key) = match team {
+            Team::Ally => (&mut self.ally, "AllyTeam"),
+            Team::Enemy => (&mut self.enemy, "EnemyTeam"),
+        };
+        if let Some(cached) = slot {
+            return cached.clone();
+        }
+        // Riot only lists the enemy team in pregame for some modes; without it the condition can't match.
+        let players = self.payload[key]["Players"].as_array().filter(|p| !p.is_empty());
+        let ranks = players.map(|players| {
+            let puuids: Vec<String> = players.iter()
+                .filter_map(|p| p["Subject"].as_str())
+                .map(|s| s.to_string())
+                .collect();
+            let tiers: Vec<u64> = fetch_mmrs(self.client, &puuids).values()
+                .filter_map(|m| m.latest_competitive_update.as_ref())
+                .map(|u| u.tier_after_update)
+                .filter(|t| *t > 2)
+                .collect();
+            TeamRanks {
+                avg: (!tiers.is_empty()).then(|| tiers.iter().sum::<u64>() as f64 / tiers.len() as f64),
+                max: tiers.iter().max().copied(),
+            }
+        });
+        *slot = Some(ranks.clone());
+        ranks
+    }
+
+    fn matches(&mut self, rule: &DodgeRule, map_id: &str, queue_id: &str) -> bool {
+        if !rule.maps.is_empty() && !rule.maps.iter().any(|m| m.eq_ignore_ascii_case(map_id)) {
+            return false;
+        }
+        if !rule.queues.is_empty() && !rule.queues.iter().any(|q| q.eq_ignore_ascii_case(queue_id)) {
+            return false;
+        }
+        if rule.min_party_size.is_some() || rule.max_party_size.is_some() {
+            let Some(size) = self.party_size() else { return false };
+            if rule.min_party_size.is_some_and(|min| size < min) || rule.max_party_size.is_some_and(|max| size > max) {
+                return false;
+            }
+        }
+        rule.ranks.iter().all(|cond| {
+            let Some(ranks) = self.ranks(cond.team) else { return false };
+            let value = match cond.stat {
+                RankStat::Avg => ranks.avg,
+                RankStat::Max => ranks.max.map(|m| m as f64),
+            };
+            let Some(value) = value else { return false };
+            cond.min_tier.is_none_or(|min| value >= min as f64) && cond.max_tier.is_none_or(|max| value <= max as f64)
+        })
+    }
+}
+
+fn evaluate(state: &Mutex<ConnectionState>, config: &DodgeConfig, match_id: &str, payload: &serde_json::Value) -> DodgeDecision {
+    let map_id = payload["MapID"].as_str().unwrap_or_default().to_string();
+    let queue_id = payload["QueueID"].as_str().unwrap_or_default().to_string();
+    let mut decision = DodgeDecision {
+        match_id: match_id.to_string(),
+        map_id: map_id.clone(),
+        queue_id: queue_id.clone(),
+        party_size: None,
+        ally: None,
+        enemy: None,
+        rule: None,
+        outcome: Outcome::NoMatch,
+        reason: "No rule matched".to_string(),
+    };
+    let client = match RiotClient::from_state(state) {
+        Ok(c) => c,
+        Err(e) => {
+            decision.outcome = Outcome::Failed;
+            decision.reason = e.to_string();
+            return decision;
+        }
+    };
+    let mut ctx = Context { client: &client, payload, party_size: None, ally: None, enemy: None };
+    let fired = config.rules.iter().find(|rule| ctx.matches(rule, &map_id, &queue_id));
+    decision.party_size = ctx.party_size.flatten();
+    decision.ally = ctx.ally.flatten();
+    decision.enemy = ctx.enemy.flatten();
+    let Some(rule) = fired else { return decision };
+    decision.rule = Some(rule.name.clone());
+
+    if config.dry_run {
+        decision.outcome = Outcome::DryRun;
+        decision.reason = format!("Dry run: rule '{}' would have dodged", rule.name);
+        return decision;
+    }
+
+    let puuid = client.puuid().to_string();
+    let (count, penalty, next) = with_records(&puuid, |records| {
+        (records.len() as u32, records.iter().map(|r| r.penalty_rr).sum::<u32>(), next_penalty(config, records, &queue_id))
+    });
+    if config.max_dodges_per_day.is_some_and(|max| count >= max) {
+        decision.outcome = Outcome::Refused;
+        decision.reason = format!("Rule '{}' matched, but {} dodges today reached the limit", rule.name, count);
+        return decision;
+    }
+    if config.max_penalty_rr.is_some_and(|max| penalty + next > max) {
+        decision.outcome = Outcome::Refused;
+        decision.reason = format!("Rule '{}' matched, but another dodge would bring today's penalty to {} RR", rule.name, penalty + next);
+        return decision;
+    }
+
+    *DODGING.lock().unwrap_or_else(|e| e.into_inner()) = Some(match_id.to_string());
+    match pregame_quit(state, match_id) {
+        Ok(_) => {
+            with_records(&puuid, |records| records.push(DodgeRecord {
+                at: now_secs(),
+                match_id: match_id.to_string(),
+                map_id,
+                queue_id,
+                rule: rule.name.clone(),
+                penalty_rr: next,
+            }));
+            decision.outcome = Outcome::Dodged;
+            decision.reason = format!("Rule '{}' matched", rule.name);
+        }
+        Err(e) => {
+            *DODGING.lock().unwrap_or_else(|e| e.into_inner()) = None;
+            decision.outcome = Outcome::Failed;
+            decision.reason = format!("Rule '{}' matched, but the dodge failed: {}", rule.name, e);
+        }
+    }
+    decision
+}
+
+// Called by the phase watcher on entering pregame. Each match is evaluated once, on its own thread.
+pub fn start(app: AppHandle, state: Arc<Mutex<ConnectionState>>, match_id: String, payload: serde_json::Value) {
+    let config = get_config();
+    if !config.enabled || config.rules.is_empty() {
+        return;
+    }
+    {
+        let mut evaluated = EVALUATED.lock().unwrap_or_else(|e| e.into_inner());
+        if evaluated.as_deref() == Some(match_id.as_str()) {
+            return;
+        }
+        *evaluated = Some(match_id.clone());
+    }
+    // Marked before the thread starts: the watcher starts agent select right after this returns.
+    begin_evaluation(&match_id);
+    std::thread::spawn(move || {
+        let decision = evaluate(&state, &config, &match_id, &payload);
+        end_evaluation(&match_id);
+        log_info(&format!(
+            "[Dodge] {:?} in {} on {} ({}): {}",
+            decision.outcome, decision.match_id, decision.map_id, decision.queue_id, decision.reason
+        ));
+        let _ = app.emit("dodge-decision", decision);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASCENT: &str = "/Game/Maps/Ascent/Ascent";
+    const BIND: &str = "/Game/Maps/Duality/Duality";
+
+    // A connected session with made-up tokens: enough for `RiotClient::from_state`, never used on the wire.
+    fn session(puuid: &str) -> Mutex<ConnectionState> {
+        Mutex::new(ConnectionState {
+            connected: true,
+            access_token: Some("access".to_string()),
+            entitlements: Some("entitlements".to_string()),
+            puuid: Some(puuid.to_string()),
+            region: Some("eu".to_string()),
+            shard: Some("eu".to_string()),
+            client_version: Some("release-09.07-shipping-11-2790213".to_string()),
+            ..ConnectionState::default()
+        })
+    }
+
+    fn rule(name: &str) -> DodgeRule {
+        DodgeRule { name: name.to_string(), ..DodgeRule::default() }
+    }
+
+    fn ranks(avg: f64, max: u64) -> Option<Option<TeamRanks>> {
+        Some(Some(TeamRanks { avg: Some(avg), max: Some(max) }))
+    }
+
+    fn record(at: u64, penalty_rr: u32) -> DodgeRecord {
+        DodgeRecord {
+            at,
+            match_id: "00000000-0000-4000-8000-000000000001".to_string(),
+            map_id: ASCENT.to_string(),
+            queue_id: "competitive".to_string(),
+            rule: "earlier".to_string(),
+            penalty_rr,
+        }
+    }
+
+    fn payload(map_id: &str, queue_id: &str) -> serde_json::Value {
+        serde_json::json!({ "MapID": map_id, "QueueID": queue_id, "AllyTeam": { "Players": [] } })
+    }
+
+    #[test]
+    fn rules_match_map_queue_party_and_ranks() {
+        let state = session("rules-player");
+        let client = RiotClient::from_state(&state).unwrap();
+        let payload = payload(ASCENT, "competitive");
+        // Party size and team ranks are pre-filled, so no lookup goes out.
+        let mut ctx = Context { client: &client, payload: &payload, party_size: Some(Some(2)), ally: ranks(17.5, 21), enemy: ranks(19.0, 24) };
+
+        let mut r = rule("ascent comp");
+        r.maps = vec![ASCENT.to_lowercase()];
+        r.queues = vec!["Competitive".to_string()];
+        assert!(ctx.matches(&r, ASCENT, "competitive"), "map and queue compare case-insensitively");
+        assert!(!ctx.matches(&r, BIND, "competitive"));
+        assert!(!ctx.matches(&r, ASCENT, "unrated"));
+
+        let mut solo = rule("solo");
+        solo.max_party_size = Some(1);
+        assert!(!ctx.matches(&solo, ASCENT, "competitive"));
+        let mut group = rule("group");
+        group.min_party_size = Some(2);
+        assert!(ctx.matches(&group, ASCENT, "competitive"));
+
+        let mut stacked = rule("enemy max immortal");
+        stacked.ranks = vec![RankCondition { team: Team::Enemy, stat: RankStat::Max, min_tier: Some(24), max_tier: None }];
+        assert!(ctx.matches(&stacked, ASCENT, "competitive"));
+        stacked.ranks[0].min_tier = Some(25);
+        assert!(!ctx.matches(&stacked, ASCENT, "competitive"));
+
+        let mut low_allies = rule("ally avg below plat 3");
+        low_allies.ranks = vec![RankCondition { team: Team::Ally, stat: RankStat::Avg, min_tier: None, max_tier: Some(17) }];
+        assert!(!ctx.matches(&low_allies, ASCENT, "competitive"), "17.5 is above the max");
+        low_allies.ranks[0].max_tier = Some(18);
+        assert!(ctx.matches(&low_allies, ASCENT, "competitive"));
+
+        // Every condition has to hold.
+        let mut both = rule("both");
+        both.maps = vec![ASCENT.to_string()];
+        both.min_party_size = Some(3);
+        assert!(!ctx.matches(&both, ASCENT, "competitive"));
+    }
+
+    #[test]
+    fn unknown_ranks_never_match() {
+        let state = session("unranked-player");
+        let client = RiotClient::from_state(&state).unwrap();
+        let payload = payload(ASCENT, "competitive");
+        let mut ctx = Context { client: &client, payload: &payload, party_size: Some(None), ally: Some(Some(TeamRanks::default())), enemy: Some(None) };
+
+        let mut r = rule("enemy avg");
+        r.ranks = vec![RankCondition { team: Team::Enemy, stat: RankStat::Avg, min_tier: None, max_tier: None }];
+        assert!(!ctx.matches(&r, ASCENT, "competitive"), "enemy team not listed in pregame");
+        r.ranks[0].team = Team::Ally;
+        assert!(!ctx.matches(&r, ASCENT, "competitive"), "every ally unranked");
+        let mut party = rule("party");
+        party.max_party_size = Some(5);
+        assert!(!ctx.matches(&party, ASCENT, "competitive"), "party lookup failed");
+    }
+
+    #[test]
+    fn dry_run_reports_without_dodging() {
+        let state = session("dry-run-player");
+        let mut blacklist = rule("Blacklisted map");
+        blacklist.maps = vec![ASCENT.to_string()];
+        let config = DodgeConfig { enabled: true, dry_run: true, rules: vec![blacklist], ..DodgeConfig::default() };
+
+        let decision = evaluate(&state, &config, "dry-run-match", &payload(BIND, "competitive"));
+        assert_eq!(decision.outcome, Outcome::NoMatch);
+
+        let decision = evaluate(&state, &config, "dry-run-match", &payload(ASCENT, "competitive"));
+        assert_eq!(decision.outcome, Outcome::DryRun);
+        assert_eq!(decision.rule.as_deref(), Some("Blacklisted map"));
+        assert!(with_records("dry-run-player", |records| records.is_empty()));
+        assert!(!is_dodging("dry-run-match"));
+    }
+
+    #[test]
+    fn waits_until_the_match_is_evaluated() {
+        assert!(wait_for_evaluation("never-evaluated", Duration::ZERO));
+
+        begin_evaluation("slow-match");
+        assert!(!wait_for_evaluation("slow-match", Duration::from_millis(20)));
+        let done = std::thread::spawn(|| {
+            std::thread::sleep(Duration::from_millis(50));
+            end_evaluation("slow-match");
+        });
+        assert!(wait_for_evaluation("slow-match", Duration::from_secs(5)));
+        done.join().unwrap();
+    }
+
+    #[test]
+    fn ledger_drops_records_older_than_a_day() {
+        let now = now_secs();
+        with_records("rollover-player", |records| {
+            records.push(record(now - DAY_SECS - 5, 10));
+            records.push(record(now - 60, 3));
+        });
+        let (count, penalty) = with_records("rollover-player", |records| (records.len(), records.iter().map(|r| r.penalty_rr).sum::<u32>()));
+        assert_eq!((count, penalty), (1, 3));
+
+        with_records("rollover-player", |records| records[0].at = now - DAY_SECS - 1);
+        let config = DodgeConfig::default();
+        assert_eq!(with_records("rollover-player", |records| next_penalty(&config, records, "competitive")), PENALTY_RR_FIRST);
+    }
+
+    #[test]
+    fn penalties_follow_queue_and_overrides() {
+        let mut config = DodgeConfig::default();
+        let earlier = [record(now_secs(), 3)];
+        assert_eq!(next_penalty(&config, &[], "competitive"), 3);
+        assert_eq!(next_penalty(&config, &earlier, "competitive"), 10);
+        assert_eq!(next_penalty(&config, &earlier, "unrated"), 0);
+        config.penalty_rr_first = Some(5);
+        config.penalty_rr_repeat = Some(15);
+        assert_eq!(next_penalty(&config, &[], "competitive"), 5);
+        assert_eq!(next_penalty(&config, &earlier, "competitive"), 15);
+    }
+
+    #[test]
+    fn refuses_past_daily_limits() {
+        let mut any = rule("any map");
+        any.maps = vec![ASCENT.to_string()];
+        let now = now_secs();
+
+        let state = session("count-limit-player");
+        with_records("count-limit-player", |records| records.extend([record(now - 100, 0), record(now - 50, 0)]));
+        let config = DodgeConfig { enabled: true, rules: vec![any.clone()], max_dodges_per_day: Some(2), ..DodgeConfig::default() };
+        let decision = evaluate(&state, &config, "count-limit-match", &payload(ASCENT, "unrated"));
+        assert_eq!(decision.outcome, Outcome::Refused);
+        assert!(decision.reason.contains("2 dodges today"), "{}", decision.reason);
+
+        // 3 RR already spent; the next competitive dodge costs 10 and would end at 13.
+        let state = session("penalty-limit-player");
+        with_records("penalty-limit-player", |records| records.push(record(now - 100, 3)));
+        let config = DodgeConfig { enabled: true, rules: vec![any], max_penalty_rr: Some(12), ..DodgeConfig::default() };
+        let decision = evaluate(&state, &config, "penalty-limit-match", &payload(ASCENT, "competitive"));
+        assert_eq!(decision.outcome, Outcome::Refused);
+        assert!(decision.reason.contains("13 RR"), "{}", decision.reason);
+        assert!(!is_dodging("penalty-limit-match"));
+    }
+}
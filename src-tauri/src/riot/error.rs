@@ -0,0 +1,210 @@
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportCause {
+    Timeout,
+    Connect,
+    Other,
+}
+
+#[derive(Debug, Clone)]
+pub enum RiotError {
+    NotConnected,
+    Local(String),
+    Transport {
+        endpoint: String,
+        message: String,
+        cause: TransportCause,
+    },
+    Http {
+        endpoint: String,
+        status: u16,
+        error_code: Option<String>,
+        message: Option<String>,
+        retry_after: Option<u64>,
+    },
+    Decode {
+        endpoint: String,
+        message: String,
+    },
+}
+
+impl RiotError {
+    pub fn from_response(endpoint: &str, status: u16, body: &str) -> Self {
+        let parsed = serde_json::from_str::<serde_json::Value>(body).ok();
+        let field = |key: &str| {
+            parsed.as_ref()
+                .and_then(|v| v[key].as_str())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+        };
+        let message = field("message").or_else(|| {
+            let trimmed = body.trim();
+            if trimmed.is_empty() { None } else { Some(trimmed.chars().take(300).collect()) }
+        });
+        RiotError::Http {
+            endpoint: endpoint.to_string(),
+            status,
+            error_code: field("errorCode"),
+            message,
+            retry_after: None,
+        }
+    }
+
+    pub fn with_retry_after(mut self, secs: Option<u64>) -> Self {
+        if let RiotError::Http { retry_after, .. } = &mut self {
+            *retry_after = secs;
+        }
+        self
+    }
+
+    pub fn decode(endpoint: &str, err: impl fmt::Display) -> Self {
+        RiotError::Decode {
+            endpoint: endpoint.to_string(),
+            message: err.to_string(),
+        }
+    }
+
+    pub fn endpoint(&self) -> Option<&str> {
+        match self {
+            RiotError::Transport { endpoint, .. }
+            | RiotError::Http { endpoint, .. }
+            | RiotError::Decode { endpoint, .. } => Some(endpoint),
+            _ => None,
+        }
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            RiotError::Http { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    pub fn error_code(&self) -> Option<&str> {
+        match self {
+            RiotError::Http { error_code, .. } => error_code.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn retry_after(&self) -> Option<u64> {
+        match self {
+            RiotError::Http { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RiotError::NotConnected => "not_connected",
+            RiotError::Local(_) => "local",
+            RiotError::Decode { .. } => "decode",
+            RiotError::Transport { cause: TransportCause::Timeout, .. } => "timeout",
+            RiotError::Transport { cause: TransportCause::Connect, .. } => "connection_refused",
+            RiotError::Transport { .. } => "network",
+            RiotError::Http { status, error_code, .. } => {
+                let code = error_code.as_deref().unwrap_or("");
+                if *status == 401 || code == "CREDENTIALS_INVALID" || code == "BAD_CLAIMS" {
+                    "credentials_invalid"
+                } else if *status == 429 || code.contains("RATE_LIMIT") {
+                    "rate_limited"
+                } else if code.starts_with("PARTY") && (code.contains("DNE") || code.contains("NOT_FOUND") || code.contains("DOES_NOT_EXIST")) {
+                    "party_not_found"
+                } else if code.contains("RESTRICT") || code.contains("PENALTY") || code.contains("QUEUE_NOT_ENABLED") || code.contains("INELIGIBLE") {
+                    "queue_restricted"
+                } else if *status == 404 {
+                    "not_found"
+                } else {
+                    "http"
+                }
+            }
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            RiotError::Transport { .. } => true,
+            RiotError::Http { status, .. } => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+
+    pub fn is_credentials_invalid(&self) -> bool {
+        self.kind() == "credentials_invalid"
+    }
+}
+
+impl fmt::Display for RiotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RiotError::NotConnected => write!(f, "Not connected"),
+            RiotError::Local(msg) => write!(f, "{}", msg),
+            RiotError::Transport { endpoint, message, .. } => write!(f, "{}: {}", endpoint, message),
+            RiotError::Http { endpoint, status, error_code, message, .. } => {
+                write!(f, "{}: HTTP {}", endpoint, status)?;
+                if let Some(code) = error_code {
+                    write!(f, " {}", code)?;
+                }
+                if let Some(msg) = message {
+                    write!(f, " - {}", msg)?;
+                }
+                Ok(())
+            }
+            RiotError::Decode { endpoint, message } => write!(f, "Parse {}: {}", endpoint, message),
+        }
+    }
+}
+
+impl std::error::Error for RiotError {}
+
+impl From<String> for RiotError {
+    fn from(msg: String) -> Self {
+        RiotError::Local(msg)
+    }
+}
+
+impl From<&str> for RiotError {
+    fn from(msg: &str) -> Self {
+        RiotError::Local(msg.to_string())
+    }
+}
+
+impl From<RiotError> for String {
+    fn from(err: RiotError) -> Self {
+        err.to_string()
+    }
+}
+
+#[derive(Serialize)]
+struct RiotErrorPayload<'a> {
+    kind: &'static str,
+    message: String,
+    endpoint: Option<&'a str>,
+    status: Option<u16>,
+    error_code: Option<&'a str>,
+    riot_message: Option<&'a str>,
+    retry_after: Option<u64>,
+    retryable: bool,
+}
+
+impl Serialize for RiotError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let riot_message = match self {
+            RiotError::Http { message, .. } => message.as_deref(),
+            _ => None,
+        };
+        RiotErrorPayload {
+            kind: self.kind(),
+            message: self.to_string(),
+            endpoint: self.endpoint(),
+            status: self.status(),
+            error_code: self.error_code(),
+            riot_message,
+            retry_after: self.retry_after(),
+            retryable: self.is_retryable(),
+        }
+        .serialize(serializer)
+    }
+}
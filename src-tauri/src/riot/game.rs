@@ -1,13 +1,19 @@
 use std::sync::Mutex;
 
 use super::types::ConnectionState;
-use super::http::{glz_get, glz_post, glz_post_body, glz_delete, local_get, local_post, pd_get, pd_put, pd_batch_get};
-use super::logging::log_info;
-
-fn get_local_creds(state: &Mutex<ConnectionState>) -> Result<(u16, String), String> {
+use super::error::RiotError;
+use super::query::Query;
+use super::client::RiotClient;
+use super::http::{local_get, local_post};
+use super::logging::{log_error, log_info};
+use super::cache::{self, CacheKind};
+use super::profiles;
+use super::models::{self, CoreGameMatch, CustomGameSettings, MatchDetails, MatchHistory, MatchRef, Party, PartyPlayer, PlayerMmr, PregameMatch};
+
+fn get_local_creds(state: &Mutex<ConnectionState>) -> Result<(u16, String), RiotError> {
     let s = state.lock().map_err(|e| e.to_string())?;
     if !s.connected {
-        return Err("Not connected".to_string());
+        return Err(RiotError::NotConnected);
     }
     Ok((
         s.port.ok_or("No port")?,
@@ -15,181 +21,228 @@ fn get_local_creds(state: &Mutex<ConnectionState>) -> Result<(u16, String), Stri
     ))
 }
 
-fn get_glz_creds(state: &Mutex<ConnectionState>) -> Result<(String, String, String, String, String, String), String> {
-    let s = state.lock().map_err(|e| e.to_string())?;
-    if !s.connected {
-        return Err("Not connected".to_string());
+pub fn current_pregame(client: &RiotClient) -> Result<Option<serde_json::Value>, RiotError> {
+    let player_path = format!("/pregame/v1/players/{}", client.puuid());
+    let match_id = match client.glz().get_as::<MatchRef>(&player_path) {
+        Ok(player) if !player.match_id.is_empty() => player.match_id,
+        _ => return Ok(None),
+    };
+    match pregame_match(client, &match_id) {
+        Ok(result) => Ok(Some(result)),
+        Err(e @ RiotError::Decode { .. }) => Err(e),
+        Err(_) => Ok(None),
     }
-    Ok((
-        s.access_token.clone().ok_or("No access_token")?,
-        s.entitlements.clone().ok_or("No entitlements")?,
-        s.puuid.clone().ok_or("No puuid")?,
-        s.region.clone().ok_or("No region")?,
-        s.shard.clone().ok_or("No shard")?,
-        s.client_version.clone().ok_or("No client_version")?,
-    ))
 }
 
-pub fn check_current_game(state: &Mutex<ConnectionState>) -> Result<String, String> {
-    let (access_token, entitlements, puuid, region, shard, client_version) = get_glz_creds(state)?;
+// The typed decode only validates the shape, so drift fails here as a `Decode` error. The raw
+// payload is what gets returned: MatchInfoPage and the phase events read fields the models
+// don't cover (GamePodID, PlayerIdentity flags, Teams), and a typed return would drop them.
+pub fn pregame_match(client: &RiotClient, match_id: &str) -> Result<serde_json::Value, RiotError> {
+    let match_path = format!("/pregame/v1/matches/{}", match_id);
+    let mut result = client.glz().get_json(&match_path)?;
+    models::decode::<PregameMatch>(&match_path, &result)?;
+    result["_phase"] = serde_json::json!("pregame");
+    Ok(result)
+}
 
-    let pregame_player_path = format!("/pregame/v1/players/{}", puuid);
-    if let Ok(pregame_raw) = glz_get(&region, &shard, &pregame_player_path, &access_token, &entitlements, &client_version) {
-        if let Ok(pregame_json) = serde_json::from_str::<serde_json::Value>(&pregame_raw) {
-            if let Some(match_id) = pregame_json["MatchID"].as_str().filter(|s| !s.is_empty()) {
-                let match_path = format!("/pregame/v1/matches/{}", match_id);
-                if let Ok(match_raw) = glz_get(&region, &shard, &match_path, &access_token, &entitlements, &client_version) {
-                    let mut result: serde_json::Value = serde_json::from_str(&match_raw)
-                        .unwrap_or(serde_json::json!({}));
-                    result["_phase"] = serde_json::json!("pregame");
-                    return Ok(result.to_string());
-                }
-            }
-        }
+pub fn current_coregame(client: &RiotClient) -> Result<Option<serde_json::Value>, RiotError> {
+    let player_path = format!("/core-game/v1/players/{}", client.puuid());
+    let match_id = match client.glz().get_as::<MatchRef>(&player_path) {
+        Ok(player) if !player.match_id.is_empty() => player.match_id,
+        _ => return Ok(None),
+    };
+    match coregame_match(client, &match_id) {
+        Ok(result) => Ok(Some(result)),
+        Err(e @ RiotError::Decode { .. }) => Err(e),
+        Err(_) => Ok(None),
     }
+}
 
-    let coregame_player_path = format!("/core-game/v1/players/{}", puuid);
-    if let Ok(coregame_raw) = glz_get(&region, &shard, &coregame_player_path, &access_token, &entitlements, &client_version) {
-        if let Ok(coregame_json) = serde_json::from_str::<serde_json::Value>(&coregame_raw) {
-            if let Some(match_id) = coregame_json["MatchID"].as_str().filter(|s| !s.is_empty()) {
-                let match_path = format!("/core-game/v1/matches/{}", match_id);
-                if let Ok(match_raw) = glz_get(&region, &shard, &match_path, &access_token, &entitlements, &client_version) {
-                    let mut result: serde_json::Value = serde_json::from_str(&match_raw)
-                        .unwrap_or(serde_json::json!({}));
-                    result["_phase"] = serde_json::json!("ingame");
-                    return Ok(result.to_string());
-                }
-            }
+pub fn coregame_match(client: &RiotClient, match_id: &str) -> Result<serde_json::Value, RiotError> {
+    let match_path = format!("/core-game/v1/matches/{}", match_id);
+    let mut result = client.glz().get_json(&match_path)?;
+    models::decode::<CoreGameMatch>(&match_path, &result)?;
+    result["_phase"] = serde_json::json!("ingame");
+    Ok(result)
+}
+
+pub fn decode_presence_private(presence: &serde_json::Value) -> Option<serde_json::Value> {
+    let priv_b64 = presence["private"].as_str().filter(|s| !s.is_empty())?;
+    let decoded = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, priv_b64).ok()?;
+    serde_json::from_slice(&decoded).ok()
+}
+
+pub fn session_loop_state_from(private: &serde_json::Value) -> Option<String> {
+    private["sessionLoopState"].as_str()
+        .or_else(|| private["matchPresenceData"]["sessionLoopState"].as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+}
+
+pub fn session_loop_state(state: &Mutex<ConnectionState>) -> Result<Option<String>, RiotError> {
+    let (port, auth) = get_local_creds(state)?;
+    let puuid = state.lock().map_err(|e| e.to_string())?.puuid.clone().unwrap_or_default();
+    let pres_json: serde_json::Value = models::decode_str("/chat/v4/presences", &local_get(port, &auth, "/chat/v4/presences")?)?;
+    let own = pres_json["presences"].as_array()
+        .and_then(|list| list.iter().find(|p| p["puuid"].as_str() == Some(puuid.as_str()) && p["product"].as_str() == Some("valorant")));
+    Ok(own.and_then(decode_presence_private).and_then(|p| session_loop_state_from(&p)))
+}
+
+// PUUID → party ID for every Valorant presence the local chat server knows about.
+pub fn presence_party_ids(state: &Mutex<ConnectionState>) -> Result<std::collections::HashMap<String, String>, RiotError> {
+    let (port, auth) = get_local_creds(state)?;
+    let pres_json: serde_json::Value = models::decode_str("/chat/v4/presences", &local_get(port, &auth, "/chat/v4/presences")?)?;
+    let mut parties = std::collections::HashMap::new();
+    for p in pres_json["presences"].as_array().into_iter().flatten() {
+        if p["product"].as_str() != Some("valorant") {
+            continue;
+        }
+        let (Some(puuid), Some(private)) = (p["puuid"].as_str(), decode_presence_private(p)) else { continue };
+        let party_id = private["partyId"].as_str()
+            .or_else(|| private["partyPresenceData"]["partyId"].as_str())
+            .filter(|s| !s.is_empty());
+        if let Some(party_id) = party_id {
+            parties.insert(puuid.to_string(), party_id.to_string());
         }
     }
+    Ok(parties)
+}
 
-    Err("Not in a match".to_string())
+pub fn check_current_game(state: &Mutex<ConnectionState>) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    if let Some(result) = current_pregame(&client)? {
+        return Ok(result.to_string());
+    }
+    if let Some(result) = current_coregame(&client)? {
+        return Ok(result.to_string());
+    }
+    Err(RiotError::Local("Not in a match".to_string()))
 }
 
-pub fn select_agent(state: &Mutex<ConnectionState>, match_id: &str, agent_id: &str) -> Result<String, String> {
-    let (access_token, entitlements, _, region, shard, client_version) = get_glz_creds(state)?;
+pub fn select_agent(state: &Mutex<ConnectionState>, match_id: &str, agent_id: &str) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
     let path = format!("/pregame/v1/matches/{}/select/{}", match_id, agent_id);
     log_info(&format!("[Game] Selecting agent {} in match {}", agent_id, match_id));
-    glz_post(&region, &shard, &path, &access_token, &entitlements, &client_version)
+    client.glz().post(&path)
 }
 
-pub fn lock_agent(state: &Mutex<ConnectionState>, match_id: &str, agent_id: &str) -> Result<String, String> {
-    let (access_token, entitlements, _, region, shard, client_version) = get_glz_creds(state)?;
+pub fn lock_agent(state: &Mutex<ConnectionState>, match_id: &str, agent_id: &str) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
     let path = format!("/pregame/v1/matches/{}/lock/{}", match_id, agent_id);
     log_info(&format!("[Game] Locking agent {} in match {}", agent_id, match_id));
-    glz_post(&region, &shard, &path, &access_token, &entitlements, &client_version)
+    client.glz().post(&path)
 }
 
-pub fn pregame_quit(state: &Mutex<ConnectionState>, match_id: &str) -> Result<String, String> {
-    let (access_token, entitlements, _, region, shard, client_version) = get_glz_creds(state)?;
+pub fn pregame_quit(state: &Mutex<ConnectionState>, match_id: &str) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
     let quit_path = format!("/pregame/v1/matches/{}/quit", match_id);
     log_info(&format!("[Game] Dodging match {}", match_id));
-    glz_post(&region, &shard, &quit_path, &access_token, &entitlements, &client_version)
+    client.glz().post(&quit_path)
 }
 
-pub fn coregame_quit(state: &Mutex<ConnectionState>, match_id: &str) -> Result<String, String> {
-    let (access_token, entitlements, puuid, region, shard, client_version) = get_glz_creds(state)?;
+pub fn coregame_quit(state: &Mutex<ConnectionState>, match_id: &str) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let puuid = client.puuid();
     let path = format!("/core-game/v1/players/{}/disassociate/{}", puuid, match_id);
     log_info(&format!("[Game] Leaving match {}", match_id));
-    glz_post(&region, &shard, &path, &access_token, &entitlements, &client_version)
+    client.glz().post(&path)
 }
 
-pub fn get_party(state: &Mutex<ConnectionState>) -> Result<String, String> {
-    let (access_token, entitlements, puuid, region, shard, client_version) = get_glz_creds(state)?;
+pub fn get_party(state: &Mutex<ConnectionState>) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let puuid = client.puuid();
 
     let player_path = format!("/parties/v1/players/{}", puuid);
-    let player_raw = glz_get(&region, &shard, &player_path, &access_token, &entitlements, &client_version)?;
-    let player_json: serde_json::Value = serde_json::from_str(&player_raw).map_err(|e| format!("Parse party player: {}", e))?;
-    let party_id = player_json["CurrentPartyID"].as_str().filter(|s| !s.is_empty())
-        .ok_or("No party ID found")?;
+    let player: PartyPlayer = client.glz().get_as(&player_path)?;
+    if player.current_party_id.is_empty() {
+        return Err(RiotError::Local("No party ID found".to_string()));
+    }
 
-    let party_path = format!("/parties/v1/parties/{}", party_id);
-    let party_raw = glz_get(&region, &shard, &party_path, &access_token, &entitlements, &client_version)?;
-    let party_json: serde_json::Value = serde_json::from_str(&party_raw).map_err(|e| format!("Parse party: {}", e))?;
+    let party_path = format!("/parties/v1/parties/{}", player.current_party_id);
+    let party: Party = client.glz().get_as(&party_path)?;
 
-    let members = party_json["Members"].as_array().ok_or("No Members array")?;
-    let puuids: Vec<String> = members.iter()
-        .filter_map(|m| m["Subject"].as_str().map(|s| s.to_string()))
-        .collect();
+    let puuids: Vec<String> = party.members.iter().map(|m| m.subject.clone()).collect();
 
-    let puuids_json = serde_json::to_string(&puuids).unwrap_or_default();
     let mut name_map: std::collections::HashMap<String, (String, String)> = std::collections::HashMap::new();
 
-    if let Ok(names_raw) = pd_put(&shard, "/name-service/v2/players", &puuids_json, &access_token, &entitlements, &client_version) {
-        if let Ok(names) = serde_json::from_str::<Vec<serde_json::Value>>(&names_raw) {
-            for n in names {
-                if let (Some(subject), Some(game_name), Some(tag)) = (
-                    n["Subject"].as_str(),
-                    n["GameName"].as_str(),
-                    n["TagLine"].as_str(),
-                ) {
-                    name_map.insert(subject.to_string(), (game_name.to_string(), tag.to_string()));
-                }
+    if let Ok(names) = lookup_names(&client, &puuids) {
+        for n in names {
+            if let (Some(subject), Some(game_name), Some(tag)) = (
+                n["Subject"].as_str(),
+                n["GameName"].as_str(),
+                n["TagLine"].as_str(),
+            ) {
+                name_map.insert(subject.to_string(), (game_name.to_string(), tag.to_string()));
             }
         }
     }
 
     let mut result_members = Vec::new();
-    for m in members {
-        let subject = m["Subject"].as_str().unwrap_or_default();
-        let (game_name, game_tag) = name_map.get(subject)
+    for m in &party.members {
+        let (game_name, game_tag) = name_map.get(&m.subject)
             .map(|(n, t)| (n.as_str(), t.as_str()))
             .unwrap_or(("Unknown", "0000"));
-        let identity = &m["PlayerIdentity"];
-        let card_id = identity["PlayerCardID"].as_str().unwrap_or_default();
-        let card_url = if !card_id.is_empty() {
-            format!("https://media.valorant-api.com/playercards/{}/smallart.png", card_id)
+        let identity = &m.player_identity;
+        let card_url = if !identity.player_card_id.is_empty() {
+            format!("https://media.valorant-api.com/playercards/{}/smallart.png", identity.player_card_id)
         } else {
             String::new()
         };
 
         result_members.push(serde_json::json!({
-            "puuid": subject,
+            "puuid": m.subject,
             "game_name": game_name,
             "game_tag": game_tag,
             "player_card_url": card_url,
-            "account_level": identity["AccountLevel"].as_u64().unwrap_or(0),
-            "incognito": identity["Incognito"].as_bool().unwrap_or(false),
-            "hide_account_level": identity["HideAccountLevel"].as_bool().unwrap_or(false),
-            "competitive_tier": m["CompetitiveTier"].as_u64().unwrap_or(0),
-            "is_owner": m["IsOwner"].as_bool().unwrap_or(false),
-            "is_ready": m["IsReady"].as_bool().unwrap_or(false),
+            "account_level": identity.account_level,
+            "incognito": identity.incognito,
+            "hide_account_level": identity.hide_account_level,
+            "competitive_tier": m.competitive_tier,
+            "is_owner": m.is_owner,
+            "is_ready": m.is_ready,
         }));
     }
 
-    let queue_id = party_json["MatchmakingData"]["QueueID"].as_str().unwrap_or("");
-    let party_state = party_json["State"].as_str().unwrap_or("");
-
-    let settings = &party_json["CustomGameData"]["Settings"];
-    if party_state == "CUSTOM_GAME_SETUP" {
+    if party.state == "CUSTOM_GAME_SETUP" {
         log_info("[Custom] Parsed custom game settings");
     }
-    let rules = &settings["GameRules"];
+    let settings = party.custom_game_data.as_ref().map(|d| &d.settings);
+    let field = |f: fn(&CustomGameSettings) -> &str| settings.map(f).unwrap_or("");
+    let rule = |key: &str, default: bool| settings.map(|s| s.rule(key, default)).unwrap_or(default);
     let result = serde_json::json!({
-        "party_id": party_id,
+        "party_id": party.id,
         "my_puuid": puuid,
         "members": result_members,
-        "state": party_state,
-        "accessibility": party_json["Accessibility"].as_str().unwrap_or(""),
-        "invite_code": party_json["InviteCode"].as_str().unwrap_or(""),
-        "queue_id": queue_id,
-        "custom_map": settings["Map"].as_str().unwrap_or(""),
-        "custom_mode": settings["Mode"].as_str().unwrap_or(""),
-        "custom_pod": settings["GamePod"].as_str().unwrap_or(""),
-        "custom_allow_cheats": rules["AllowGameModifiers"].as_str().unwrap_or("false") == "true",
-        "custom_play_out_all_rounds": rules["PlayOutAllRounds"].as_str().unwrap_or("false") == "true",
-        "custom_skip_match_history": rules["SkipMatchHistory"].as_str().unwrap_or("false") == "true",
-        "custom_tournament_mode": rules["TournamentMode"].as_str().unwrap_or("false") == "true",
-        "custom_overtime_win_by_two": rules["IsOvertimeWinByTwo"].as_str().unwrap_or("true") == "true",
+        "state": party.state,
+        "accessibility": party.accessibility,
+        "invite_code": party.invite_code.as_deref().unwrap_or(""),
+        "queue_id": party.matchmaking_data.queue_id,
+        "custom_map": field(|s| &s.map),
+        "custom_mode": field(|s| &s.mode),
+        "custom_pod": field(|s| &s.game_pod),
+        "custom_allow_cheats": rule("AllowGameModifiers", false),
+        "custom_play_out_all_rounds": rule("PlayOutAllRounds", false),
+        "custom_skip_match_history": rule("SkipMatchHistory", false),
+        "custom_tournament_mode": rule("TournamentMode", false),
+        "custom_overtime_win_by_two": rule("IsOvertimeWinByTwo", true),
     });
 
     Ok(result.to_string())
 }
 
-pub fn get_friends(state: &Mutex<ConnectionState>) -> Result<String, String> {
+pub fn party_size(client: &RiotClient) -> Result<usize, RiotError> {
+    let player: PartyPlayer = client.glz().get_as(&format!("/parties/v1/players/{}", client.puuid()))?;
+    if player.current_party_id.is_empty() {
+        return Ok(1);
+    }
+    let party: Party = client.glz().get_as(&format!("/parties/v1/parties/{}", player.current_party_id))?;
+    Ok(party.members.len().max(1))
+}
+
+pub fn get_friends(state: &Mutex<ConnectionState>) -> Result<String, RiotError> {
     let (port, auth) = get_local_creds(state)?;
     let raw = local_get(port, &auth, "/chat/v4/friends")?;
-    let json: serde_json::Value = serde_json::from_str(&raw).map_err(|e| format!("Parse friends: {}", e))?;
+    let json: serde_json::Value = serde_json::from_str(&raw).map_err(|e| RiotError::decode("/chat/v4/friends", e))?;
     let friends = json["friends"].as_array().cloned().unwrap_or_default();
 
     log_info(&format!("[Friends] Raw friends count: {}", friends.len()));
@@ -315,11 +368,35 @@ pub fn get_friends(state: &Mutex<ConnectionState>) -> Result<String, String> {
     Ok(serde_json::json!(result).to_string())
 }
 
-pub fn resolve_player_names(state: &Mutex<ConnectionState>, puuids: Vec<String>) -> Result<String, String> {
-    let (access_token, entitlements, _, _, shard, client_version) = get_glz_creds(state)?;
-    let body = serde_json::json!(puuids).to_string();
-    let raw = pd_put(&shard, "/name-service/v2/players", &body, &access_token, &entitlements, &client_version)?;
-    let names: Vec<serde_json::Value> = serde_json::from_str(&raw).map_err(|e| format!("Parse names: {}", e))?;
+pub fn lookup_names(client: &RiotClient, puuids: &[String]) -> Result<Vec<serde_json::Value>, RiotError> {
+    let mut found: std::collections::HashMap<String, serde_json::Value> = std::collections::HashMap::new();
+    let mut missing: Vec<&String> = Vec::new();
+    for puuid in puuids {
+        match cache::get_json(CacheKind::Names, puuid) {
+            Some(entry) => { found.insert(puuid.clone(), entry); }
+            None => missing.push(puuid),
+        }
+    }
+
+    if !missing.is_empty() {
+        let body = serde_json::json!(missing);
+        let raw = client.pd().put("/name-service/v2/players", &body)?;
+        let names: Vec<serde_json::Value> = serde_json::from_str(&raw).map_err(|e| RiotError::decode("/name-service/v2/players", e))?;
+        for n in names {
+            let Some(subject) = n["Subject"].as_str().map(|s| s.to_string()) else { continue };
+            if n["GameName"].as_str().is_some_and(|g| !g.is_empty()) {
+                cache::put(CacheKind::Names, &subject, &n.to_string());
+            }
+            found.insert(subject, n);
+        }
+    }
+
+    Ok(puuids.iter().filter_map(|p| found.remove(p)).collect())
+}
+
+pub fn resolve_player_names(state: &Mutex<ConnectionState>, puuids: Vec<String>) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let names = lookup_names(&client, &puuids)?;
     let mut result = Vec::new();
     for n in names {
         result.push(serde_json::json!({
@@ -331,11 +408,20 @@ pub fn resolve_player_names(state: &Mutex<ConnectionState>, puuids: Vec<String>)
     Ok(serde_json::json!(result).to_string())
 }
 
-pub fn get_player_mmr(state: &Mutex<ConnectionState>, target_puuid: &str) -> Result<String, String> {
-    let (access_token, entitlements, _, _, shard, client_version) = get_glz_creds(state)?;
-    let path = format!("/mmr/v1/players/{}", target_puuid);
-    let raw = pd_get(&shard, &path, &access_token, &entitlements, &client_version)?;
-    let json: serde_json::Value = serde_json::from_str(&raw).map_err(|e| format!("Parse MMR: {}", e))?;
+pub fn player_mmr(client: &RiotClient, puuid: &str) -> Result<serde_json::Value, RiotError> {
+    if let Some(json) = cache::get_json(CacheKind::Mmr, puuid) {
+        return Ok(json);
+    }
+    let path = format!("/mmr/v1/players/{}", puuid);
+    let json = client.pd().get_json(&path)?;
+    models::decode::<PlayerMmr>(&path, &json)?;
+    cache::put(CacheKind::Mmr, puuid, &json.to_string());
+    Ok(json)
+}
+
+pub fn get_player_mmr(state: &Mutex<ConnectionState>, target_puuid: &str) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let json = player_mmr(&client, target_puuid)?;
 
     let tier = json["LatestCompetitiveUpdate"]["TierAfterUpdate"].as_u64().unwrap_or(0);
     let rr = json["LatestCompetitiveUpdate"]["RankedRatingAfterUpdate"].as_u64().unwrap_or(0);
@@ -348,74 +434,75 @@ pub fn get_player_mmr(state: &Mutex<ConnectionState>, target_puuid: &str) -> Res
     Ok(result.to_string())
 }
 
-pub fn set_party_accessibility(state: &Mutex<ConnectionState>, open: bool) -> Result<String, String> {
-    let (access_token, entitlements, puuid, region, shard, client_version) = get_glz_creds(state)?;
+pub fn set_party_accessibility(state: &Mutex<ConnectionState>, open: bool) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let puuid = client.puuid();
     let player_path = format!("/parties/v1/players/{}", puuid);
-    let player_raw = glz_get(&region, &shard, &player_path, &access_token, &entitlements, &client_version)?;
-    let player_json: serde_json::Value = serde_json::from_str(&player_raw).map_err(|e| format!("Parse: {}", e))?;
+    let player_json = client.glz().get_json(&player_path)?;
     let party_id = player_json["CurrentPartyID"].as_str().filter(|s| !s.is_empty())
         .ok_or("No party ID")?;
     let path = format!("/parties/v1/parties/{}/accessibility", party_id);
-    let body = format!(r#"{{"accessibility":"{}"}}"#, if open { "OPEN" } else { "CLOSED" });
-    glz_post_body(&region, &shard, &path, &body, &access_token, &entitlements, &client_version)
+    let body = serde_json::json!({ "accessibility": if open { "OPEN" } else { "CLOSED" } });
+    client.glz().post_json(&path, &body)
 }
 
-pub fn disable_party_code(state: &Mutex<ConnectionState>) -> Result<String, String> {
-    let (access_token, entitlements, puuid, region, shard, client_version) = get_glz_creds(state)?;
+pub fn disable_party_code(state: &Mutex<ConnectionState>) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let puuid = client.puuid();
     let player_path = format!("/parties/v1/players/{}", puuid);
-    let player_raw = glz_get(&region, &shard, &player_path, &access_token, &entitlements, &client_version)?;
-    let player_json: serde_json::Value = serde_json::from_str(&player_raw).map_err(|e| format!("Parse: {}", e))?;
+    let player_json = client.glz().get_json(&player_path)?;
     let party_id = player_json["CurrentPartyID"].as_str().filter(|s| !s.is_empty())
         .ok_or("No party ID")?;
     let path = format!("/parties/v1/parties/{}/invitecode", party_id);
-    glz_delete(&region, &shard, &path, &access_token, &entitlements, &client_version)
+    client.glz().delete(&path)
 }
 
-pub fn kick_from_party(state: &Mutex<ConnectionState>, target_puuid: &str) -> Result<String, String> {
-    let (access_token, entitlements, puuid, region, shard, client_version) = get_glz_creds(state)?;
+pub fn kick_from_party(state: &Mutex<ConnectionState>, target_puuid: &str) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let puuid = client.puuid();
     let player_path = format!("/parties/v1/players/{}", puuid);
-    let player_raw = glz_get(&region, &shard, &player_path, &access_token, &entitlements, &client_version)?;
-    let player_json: serde_json::Value = serde_json::from_str(&player_raw).map_err(|e| format!("Parse: {}", e))?;
+    let player_json = client.glz().get_json(&player_path)?;
     let party_id = player_json["CurrentPartyID"].as_str().filter(|s| !s.is_empty())
         .ok_or("No party ID")?;
     let kick_path = format!("/parties/v1/parties/{}/members/{}", party_id, target_puuid);
     log_info(&format!("[Party] Kick {} from party {}", target_puuid, party_id));
-    glz_delete(&region, &shard, &kick_path, &access_token, &entitlements, &client_version)
+    client.glz().delete(&kick_path)
 }
 
-pub fn generate_party_code(state: &Mutex<ConnectionState>) -> Result<String, String> {
-    let (access_token, entitlements, puuid, region, shard, client_version) = get_glz_creds(state)?;
+pub fn generate_party_code(state: &Mutex<ConnectionState>) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let puuid = client.puuid();
     let player_path = format!("/parties/v1/players/{}", puuid);
-    let player_raw = glz_get(&region, &shard, &player_path, &access_token, &entitlements, &client_version)?;
-    let player_json: serde_json::Value = serde_json::from_str(&player_raw).map_err(|e| format!("Parse: {}", e))?;
+    let player_json = client.glz().get_json(&player_path)?;
     let party_id = player_json["CurrentPartyID"].as_str().filter(|s| !s.is_empty())
         .ok_or("No party ID")?;
     let code_path = format!("/parties/v1/parties/{}/invitecode", party_id);
-    glz_post(&region, &shard, &code_path, &access_token, &entitlements, &client_version)
+    client.glz().post(&code_path)
 }
 
-pub fn invite_to_party(state: &Mutex<ConnectionState>, name: &str, tag: &str) -> Result<String, String> {
-    let (access_token, entitlements, puuid, region, shard, client_version) = get_glz_creds(state)?;
+pub fn invite_to_party(state: &Mutex<ConnectionState>, name: &str, tag: &str) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let puuid = client.puuid();
     let player_path = format!("/parties/v1/players/{}", puuid);
-    let player_raw = glz_get(&region, &shard, &player_path, &access_token, &entitlements, &client_version)?;
-    let player_json: serde_json::Value = serde_json::from_str(&player_raw).map_err(|e| format!("Parse: {}", e))?;
+    let player_json = client.glz().get_json(&player_path)?;
     let party_id = player_json["CurrentPartyID"].as_str().filter(|s| !s.is_empty())
         .ok_or("No party ID")?;
     let invite_path = format!("/parties/v1/parties/{}/invites/name/{}/tag/{}", party_id, name, tag);
     log_info(&format!("[Party] Inviting {}#{} to party {}", name, tag, party_id));
-    glz_post(&region, &shard, &invite_path, &access_token, &entitlements, &client_version)
+    client.glz().post(&invite_path)
 }
 
-pub fn request_to_join_party(state: &Mutex<ConnectionState>, target_puuid: &str) -> Result<String, String> {
+pub fn request_to_join_party(state: &Mutex<ConnectionState>, target_puuid: &str) -> Result<String, RiotError> {
     let (port, auth) = get_local_creds(state)?;
-    let (access_token, entitlements, puuid, region, shard, client_version) = get_glz_creds(state)?;
+    let client = RiotClient::from_state(state)?;
+    let puuid = client.puuid();
 
     let pres_raw = local_get(port, &auth, "/chat/v4/presences")?;
-    let pres_json: serde_json::Value = serde_json::from_str(&pres_raw).map_err(|e| format!("Parse presences: {}", e))?;
+    let pres_json: serde_json::Value = serde_json::from_str(&pres_raw).map_err(|e| RiotError::decode("/chat/v4/presences", e))?;
     let presences = pres_json["presences"].as_array().ok_or("No presences array")?;
 
     let my_party_path = format!("/parties/v1/players/{}", puuid);
-    let my_party_id = glz_get(&region, &shard, &my_party_path, &access_token, &entitlements, &client_version)
+    let my_party_id = client.glz().get(&my_party_path)
         .ok()
         .and_then(|r| serde_json::from_str::<serde_json::Value>(&r).ok())
         .and_then(|j| j["CurrentPartyID"].as_str().map(|s| s.to_string()))
@@ -461,22 +548,23 @@ pub fn request_to_join_party(state: &Mutex<ConnectionState>, target_puuid: &str)
     if target_party.is_none() && !all_matches.is_empty() {
         let (_, ref pid, _) = all_matches[0];
         if *pid == my_party_id {
-            return Err("Player is already in your party".to_string());
+            return Err(RiotError::Local("Player is already in your party".to_string()));
         }
     }
 
     let target_party = target_party.ok_or("Player has no party (not found in presence data)")?;
     let path = format!("/parties/v1/parties/{}/request", target_party);
-    let body = format!(r#"{{"Subjects":["{}"]}}"#, puuid);
+    let body = serde_json::json!({ "Subjects": [puuid] });
     log_info(&format!("[Party] Requesting to join party {} (player {})", target_party, target_puuid));
-    glz_post_body(&region, &shard, &path, &body, &access_token, &entitlements, &client_version)
+    client.glz().post_json(&path, &body)
 }
 
-pub fn join_party_by_code(state: &Mutex<ConnectionState>, code: &str) -> Result<String, String> {
-    let (access_token, entitlements, puuid, region, shard, client_version) = get_glz_creds(state)?;
+pub fn join_party_by_code(state: &Mutex<ConnectionState>, code: &str) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let puuid = client.puuid();
     let path = format!("/parties/v1/players/{}/joinbycode/{}", puuid, code);
     log_info(&format!("[Party] Joining by code '{}' -> {}", code, path));
-    let result = glz_post(&region, &shard, &path, &access_token, &entitlements, &client_version);
+    let result = client.glz().post(&path);
     match &result {
         Ok(r) => log_info(&format!("[Party] Join by code response: {}", &r[..r.len().min(200)])),
         Err(e) => log_info(&format!("[Party] Join by code error: {}", e)),
@@ -484,10 +572,17 @@ pub fn join_party_by_code(state: &Mutex<ConnectionState>, code: &str) -> Result<
     result
 }
 
-pub fn get_custom_configs(state: &Mutex<ConnectionState>) -> Result<String, String> {
-    let (access_token, entitlements, _puuid, region, shard, client_version) = get_glz_creds(state)?;
-    let configs_raw = glz_get(&region, &shard, "/parties/v1/parties/customgameconfigs", &access_token, &entitlements, &client_version)?;
-    let configs: serde_json::Value = serde_json::from_str(&configs_raw).map_err(|e| format!("Parse configs: {}", e))?;
+pub fn get_custom_configs(state: &Mutex<ConnectionState>) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let cache_key = format!("customgameconfigs-{}-{}", client.region(), client.shard());
+    let configs = match cache::get_json(CacheKind::Content, &cache_key) {
+        Some(cached) => cached,
+        None => {
+            let configs = client.glz().get_json("/parties/v1/parties/customgameconfigs")?;
+            cache::put(CacheKind::Content, &cache_key, &configs.to_string());
+            configs
+        }
+    };
 
     let raw_maps: Vec<&str> = configs["EnabledMaps"].as_array()
         .map(|a| a.iter().filter_map(|v| v.as_str()).collect())
@@ -560,11 +655,11 @@ pub fn set_custom_settings(
     skip_match_history: bool,
     tournament_mode: bool,
     overtime_win_by_two: bool,
-) -> Result<String, String> {
-    let (access_token, entitlements, puuid, region, shard, client_version) = get_glz_creds(state)?;
+) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let puuid = client.puuid();
     let player_path = format!("/parties/v1/players/{}", puuid);
-    let player_raw = glz_get(&region, &shard, &player_path, &access_token, &entitlements, &client_version)?;
-    let player_json: serde_json::Value = serde_json::from_str(&player_raw).map_err(|e| format!("Parse: {}", e))?;
+    let player_json = client.glz().get_json(&player_path)?;
     let party_id = player_json["CurrentPartyID"].as_str().filter(|s| !s.is_empty())
         .ok_or("No party ID")?;
 
@@ -583,9 +678,8 @@ pub fn set_custom_settings(
     });
 
     let path = format!("/parties/v1/parties/{}/customgamesettings", party_id);
-    let body_str = body.to_string();
-    log_info(&format!("[Custom] POST {} body={}", path, body_str));
-    let resp = glz_post_body(&region, &shard, &path, &body_str, &access_token, &entitlements, &client_version);
+    log_info(&format!("[Custom] POST {} body={}", path, body));
+    let resp = client.glz().post_json(&path, &body);
     match &resp {
         Ok(r) => log_info(&format!("[Custom] Response: {}", &r[..r.len().min(200)])),
         Err(e) => log_info(&format!("[Custom] Error: {}", e)),
@@ -593,59 +687,60 @@ pub fn set_custom_settings(
     resp
 }
 
-pub fn change_queue(state: &Mutex<ConnectionState>, queue_id: &str) -> Result<String, String> {
-    let (access_token, entitlements, puuid, region, shard, client_version) = get_glz_creds(state)?;
+pub fn change_queue(state: &Mutex<ConnectionState>, queue_id: &str) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let puuid = client.puuid();
     let player_path = format!("/parties/v1/players/{}", puuid);
-    let player_raw = glz_get(&region, &shard, &player_path, &access_token, &entitlements, &client_version)?;
-    let player_json: serde_json::Value = serde_json::from_str(&player_raw).map_err(|e| format!("Parse: {}", e))?;
+    let player_json = client.glz().get_json(&player_path)?;
     let party_id = player_json["CurrentPartyID"].as_str().filter(|s| !s.is_empty())
         .ok_or("No party ID")?;
     let path = format!("/parties/v1/parties/{}/queue", party_id);
-    let body = format!("{{\"queueID\": \"{}\"}}", queue_id);
+    let body = serde_json::json!({ "queueID": queue_id });
     log_info(&format!("[Party] Changing queue to {}", queue_id));
-    glz_post_body(&region, &shard, &path, &body, &access_token, &entitlements, &client_version)
+    client.glz().post_json(&path, &body)
 }
 
-pub fn start_custom_game_match(state: &Mutex<ConnectionState>) -> Result<String, String> {
-    let (access_token, entitlements, puuid, region, shard, client_version) = get_glz_creds(state)?;
+pub fn start_custom_game_match(state: &Mutex<ConnectionState>) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let puuid = client.puuid();
     let player_path = format!("/parties/v1/players/{}", puuid);
-    let player_raw = glz_get(&region, &shard, &player_path, &access_token, &entitlements, &client_version)?;
-    let player_json: serde_json::Value = serde_json::from_str(&player_raw).map_err(|e| format!("Parse: {}", e))?;
+    let player_json = client.glz().get_json(&player_path)?;
     let party_id = player_json["CurrentPartyID"].as_str().filter(|s| !s.is_empty())
         .ok_or("No party ID")?;
     let path = format!("/parties/v1/parties/{}/startcustomgame", party_id);
     log_info("[Custom] Starting custom game");
-    glz_post(&region, &shard, &path, &access_token, &entitlements, &client_version)
+    client.glz().post(&path)
 }
 
-pub fn enter_queue(state: &Mutex<ConnectionState>) -> Result<String, String> {
-    let (access_token, entitlements, puuid, region, shard, client_version) = get_glz_creds(state)?;
+pub fn enter_queue(state: &Mutex<ConnectionState>) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let puuid = client.puuid();
     let player_path = format!("/parties/v1/players/{}", puuid);
-    let player_raw = glz_get(&region, &shard, &player_path, &access_token, &entitlements, &client_version)?;
-    let player_json: serde_json::Value = serde_json::from_str(&player_raw).map_err(|e| format!("Parse: {}", e))?;
+    let player_json = client.glz().get_json(&player_path)?;
     let party_id = player_json["CurrentPartyID"].as_str().filter(|s| !s.is_empty())
         .ok_or("No party ID")?;
     let path = format!("/parties/v1/parties/{}/matchmaking/join", party_id);
     log_info(&format!("[Queue] Entering queue for party {}", party_id));
-    glz_post(&region, &shard, &path, &access_token, &entitlements, &client_version)
+    client.glz().post(&path)
 }
 
-pub fn leave_queue(state: &Mutex<ConnectionState>) -> Result<String, String> {
-    let (access_token, entitlements, puuid, region, shard, client_version) = get_glz_creds(state)?;
+pub fn leave_queue(state: &Mutex<ConnectionState>) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let puuid = client.puuid();
     let player_path = format!("/parties/v1/players/{}", puuid);
-    let player_raw = glz_get(&region, &shard, &player_path, &access_token, &entitlements, &client_version)?;
-    let player_json: serde_json::Value = serde_json::from_str(&player_raw).map_err(|e| format!("Parse: {}", e))?;
+    let player_json = client.glz().get_json(&player_path)?;
     let party_id = player_json["CurrentPartyID"].as_str().filter(|s| !s.is_empty())
         .ok_or("No party ID")?;
     let path = format!("/parties/v1/parties/{}/matchmaking/leave", party_id);
     log_info(&format!("[Queue] Leaving queue for party {}", party_id));
-    glz_post(&region, &shard, &path, &access_token, &entitlements, &client_version)
+    client.glz().post(&path)
 }
 
-pub fn check_loadout(state: &Mutex<ConnectionState>) -> Result<String, String> {
-    let (access_token, entitlements, puuid, _region, shard, client_version) = get_glz_creds(state)?;
+pub fn check_loadout(state: &Mutex<ConnectionState>) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let puuid = client.puuid();
     let path = format!("/personalization/v2/players/{}/playerloadout", puuid);
-    pd_get(&shard, &path, &access_token, &entitlements, &client_version)?;
+    client.pd().get(&path)?;
     Ok("ok".to_string())
 }
 
@@ -653,31 +748,31 @@ fn extract_map_name(map_url: &str) -> String {
     map_url.rsplit('/').next().unwrap_or("Unknown").to_string()
 }
 
-pub fn get_home_stats(state: &Mutex<ConnectionState>, queue_filter: &str) -> Result<String, String> {
-    let (access_token, entitlements, puuid, _region, shard, client_version) = get_glz_creds(state)?;
+pub fn get_home_stats(state: &Mutex<ConnectionState>, queue_filter: &str) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let puuid = client.puuid();
 
     let mmr_path = format!("/mmr/v1/players/{}", puuid);
-    let mmr_raw = pd_get(&shard, &mmr_path, &access_token, &entitlements, &client_version)?;
-    let mmr: serde_json::Value = serde_json::from_str(&mmr_raw).map_err(|e| format!("parse mmr: {}", e))?;
+    let mmr: PlayerMmr = client.pd().get_as(&mmr_path)?;
 
-    let current_tier = mmr["LatestCompetitiveUpdate"]["TierAfterUpdate"].as_u64().unwrap_or(0);
-    let current_rr = mmr["LatestCompetitiveUpdate"]["RankedRatingAfterUpdate"].as_u64().unwrap_or(0);
+    let (current_tier, current_rr) = mmr.latest_competitive_update.as_ref()
+        .map(|u| (u.tier_after_update, u.ranked_rating_after_update))
+        .unwrap_or((0, 0));
 
     let mut peak_tier: u64 = 0;
     let mut comp_wins: u64 = 0;
     let mut comp_games: u64 = 0;
-    if let Some(seasons) = mmr["QueueSkills"]["competitive"]["SeasonalInfoBySeasonID"].as_object() {
-        for (_id, season) in seasons {
-            let tier = season["CompetitiveTier"].as_u64().unwrap_or(0);
-            if tier > peak_tier { peak_tier = tier; }
-            comp_wins += season["NumberOfWinsWithPlacements"].as_u64().unwrap_or(0);
-            comp_games += season["NumberOfGames"].as_u64().unwrap_or(0);
+    if let Some(seasons) = mmr.queue_skills.get("competitive").and_then(|q| q.seasonal_info_by_season_id.as_ref()) {
+        for season in seasons.values() {
+            peak_tier = peak_tier.max(season.competitive_tier);
+            comp_wins += season.number_of_wins_with_placements;
+            comp_games += season.number_of_games;
         }
     }
 
     let loadout_path = format!("/personalization/v2/players/{}/playerloadout", puuid);
     let mut card_id = String::new();
-    if let Ok(loadout_raw) = pd_get(&shard, &loadout_path, &access_token, &entitlements, &client_version) {
+    if let Ok(loadout_raw) = client.pd().get(&loadout_path) {
         if let Ok(loadout) = serde_json::from_str::<serde_json::Value>(&loadout_raw) {
             card_id = loadout["Identity"]["PlayerCardID"].as_str().unwrap_or("").to_string();
         }
@@ -685,13 +780,13 @@ pub fn get_home_stats(state: &Mutex<ConnectionState>, queue_filter: &str) -> Res
 
     let mut account_level: u64 = 0;
     let xp_path = format!("/account-xp/v1/players/{}", puuid);
-    if let Ok(xp_raw) = pd_get(&shard, &xp_path, &access_token, &entitlements, &client_version) {
+    if let Ok(xp_raw) = client.pd().get(&xp_path) {
         if let Ok(xp) = serde_json::from_str::<serde_json::Value>(&xp_raw) {
             account_level = xp["Progress"]["Level"].as_u64().unwrap_or(0);
         }
     }
 
-    Ok(serde_json::json!({
+    let stats = serde_json::json!({
         "level": account_level,
         "cardId": card_id,
         "currentTier": current_tier,
@@ -700,97 +795,110 @@ pub fn get_home_stats(state: &Mutex<ConnectionState>, queue_filter: &str) -> Res
         "wins": comp_wins,
         "losses": comp_games.saturating_sub(comp_wins),
         "totalGames": comp_games,
-    }).to_string())
+    });
+    profiles::record_home_stats(puuid, &stats);
+    Ok(stats.to_string())
 }
 
-pub fn get_match_page(state: &Mutex<ConnectionState>, page: u64, page_size: u64) -> Result<String, String> {
-    let (access_token, entitlements, puuid, _region, shard, client_version) = get_glz_creds(state)?;
+pub fn get_match_page(state: &Mutex<ConnectionState>, page: u64, page_size: u64, queue: Option<&str>) -> Result<String, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let puuid = client.puuid();
 
     let start = page * page_size;
     let end = start + page_size;
-    let history_path = format!("/match-history/v1/history/{}?startIndex={}&endIndex={}", puuid, start, end);
-    let history_raw = pd_get(&shard, &history_path, &access_token, &entitlements, &client_version)?;
-    let history: serde_json::Value = serde_json::from_str(&history_raw).map_err(|e| format!("parse history: {}", e))?;
-    let total = history["Total"].as_u64().unwrap_or(0);
-
-    let mut match_paths: Vec<String> = Vec::new();
-    if let Some(matches) = history["History"].as_array() {
-        for m in matches {
-            if let Some(id) = m["MatchID"].as_str() {
-                match_paths.push(format!("/match-details/v1/matches/{}", id));
-            }
-        }
-    }
+    let history_path = Query::new(format!("/match-history/v1/history/{}", puuid))
+        .param("startIndex", start)
+        .param("endIndex", end)
+        .param_opt("queue", queue.filter(|q| !q.is_empty()))
+        .build();
+    let history: MatchHistory = client.pd().get_as(&history_path)?;
+    let total = history.total;
+
+    let match_ids: Vec<String> = history.history.into_iter().map(|m| m.match_id).collect();
+    let match_paths: Vec<String> = match_ids.iter()
+        .map(|id| format!("/match-details/v1/matches/{}", id))
+        .collect();
 
     let mut matches: Vec<serde_json::Value> = Vec::new();
+    let mut failed: u64 = 0;
     if !match_paths.is_empty() {
-        let details = pd_batch_get(&shard, &match_paths, &access_token, &entitlements, &client_version)?;
-        for detail in &details {
-            if detail.is_null() { continue; }
-
-            let map_name = extract_map_name(detail["matchInfo"]["mapId"].as_str().unwrap_or(""));
-
-            let player_data = detail["players"].as_array().and_then(|players| {
-                players.iter().find(|p| p["subject"].as_str() == Some(puuid.as_str()))
+        let mut details: Vec<Option<Result<MatchDetails, RiotError>>> = match_ids.iter().zip(&match_paths)
+            .map(|(id, path)| cache::get_json(CacheKind::MatchDetails, id).map(|v| models::decode(path, &v)))
+            .collect();
+        let (missing_idx, missing_paths): (Vec<usize>, Vec<String>) = details.iter().enumerate()
+            .filter(|(_, d)| !matches!(d, Some(Ok(_))))
+            .map(|(i, _)| (i, match_paths[i].clone()))
+            .unzip();
+        let fetched = client.pd().batch_get(&missing_paths)?;
+        for (i, result) in missing_idx.into_iter().zip(fetched) {
+            let decoded = result.and_then(|raw| {
+                let detail = models::decode::<MatchDetails>(&match_paths[i], &raw)?;
+                cache::put(CacheKind::MatchDetails, &match_ids[i], &raw.to_string());
+                Ok(detail)
             });
+            details[i] = Some(decoded);
+        }
 
-            let (team_id, kills, deaths, assists, agent) = match player_data {
-                Some(p) => (
-                    p["teamId"].as_str().unwrap_or("").to_string(),
-                    p["stats"]["kills"].as_u64().unwrap_or(0),
-                    p["stats"]["deaths"].as_u64().unwrap_or(0),
-                    p["stats"]["assists"].as_u64().unwrap_or(0),
-                    p["characterId"].as_str().unwrap_or("").to_string(),
-                ),
-                None => continue,
+        let mut first_err = None;
+        for (path, result) in match_paths.iter().zip(details.into_iter().flatten()) {
+            let detail = match result {
+                Ok(d) => d,
+                Err(e) => {
+                    log_error(&format!("[History] {} failed: {}", path, e));
+                    failed += 1;
+                    first_err.get_or_insert(e);
+                    continue;
+                }
             };
 
+            let Some(me) = detail.players.iter().find(|p| p.subject == puuid) else { continue };
+            let stats = me.stats.clone().unwrap_or_default();
+
             let mut won = false;
             let mut rounds_won: u64 = 0;
             let mut rounds_lost: u64 = 0;
-
-            if let Some(teams) = detail["teams"].as_array() {
-                for team in teams {
-                    let tid = team["teamId"].as_str().unwrap_or("");
-                    let rw = team["roundsWon"].as_u64().unwrap_or(0);
-                    if tid == team_id {
-                        won = team["won"].as_bool().unwrap_or(false);
-                        rounds_won = rw;
-                    } else {
-                        rounds_lost = rw;
-                    }
+            for team in detail.teams.iter().flatten() {
+                if team.team_id == me.team_id {
+                    won = team.won;
+                    rounds_won = team.rounds_won;
+                } else {
+                    rounds_lost = team.rounds_won;
                 }
             }
 
-            let queue_id = detail["matchInfo"]["queueID"].as_str().unwrap_or("").to_string();
-
             matches.push(serde_json::json!({
-                "map": map_name,
+                "map": extract_map_name(&detail.match_info.map_id),
                 "won": won,
                 "roundsWon": rounds_won,
                 "roundsLost": rounds_lost,
-                "kills": kills,
-                "deaths": deaths,
-                "assists": assists,
-                "agent": agent,
-                "queueId": queue_id,
+                "kills": stats.kills,
+                "deaths": stats.deaths,
+                "assists": stats.assists,
+                "agent": me.character_id,
+                "queueId": detail.match_info.queue_id,
             }));
         }
+        if failed as usize == match_paths.len() {
+            if let Some(e) = first_err {
+                return Err(e);
+            }
+        }
     }
 
     Ok(serde_json::json!({
         "matches": matches,
+        "failed": failed,
         "total": total,
         "page": page,
         "pageSize": page_size,
     }).to_string())
 }
 
-pub fn get_owned_agents(state: &Mutex<ConnectionState>) -> Result<Vec<String>, String> {
-    let (access_token, entitlements, puuid, _, shard, client_version) = get_glz_creds(state)?;
+pub fn get_owned_agents(state: &Mutex<ConnectionState>) -> Result<Vec<String>, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let puuid = client.puuid();
     let path = format!("/store/v1/entitlements/{}/01bb38e1-da47-4e6a-9b3d-945fe4655707", puuid);
-    let raw = pd_get(&shard, &path, &access_token, &entitlements, &client_version)?;
-    let json: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+    let json = client.pd().get_json(&path)?;
     let items = json["Entitlements"].as_array().ok_or("No Entitlements array")?;
     let ids: Vec<String> = items.iter()
         .filter_map(|item| item["ItemID"].as_str().map(|s| s.to_lowercase()))
@@ -799,7 +907,7 @@ pub fn get_owned_agents(state: &Mutex<ConnectionState>) -> Result<Vec<String>, S
     Ok(ids)
 }
 
-pub fn get_chat_conversations(state: &Mutex<ConnectionState>) -> Result<String, String> {
+pub fn get_chat_conversations(state: &Mutex<ConnectionState>) -> Result<String, RiotError> {
     let (port, auth) = get_local_creds(state)?;
     let raw = local_get(port, &auth, "/chat/v6/conversations")?;
     let all: serde_json::Value = serde_json::from_str(&raw).unwrap_or(serde_json::json!({}));
@@ -811,11 +919,12 @@ pub fn get_chat_conversations(state: &Mutex<ConnectionState>) -> Result<String,
     };
 
     let mut muc_labels: std::collections::HashMap<String, (String, String)> = std::collections::HashMap::new();
-    if let Ok((at, ent, puuid, region, shard, cv)) = get_glz_creds(state) {
-        if let Ok(pr) = glz_get(&region, &shard, &format!("/parties/v1/players/{}", puuid), &at, &ent, &cv) {
+    if let Ok(client) = RiotClient::from_state(state) {
+        let puuid = client.puuid();
+        if let Ok(pr) = client.glz().get(&format!("/parties/v1/players/{}", puuid)) {
             if let Ok(pj) = serde_json::from_str::<serde_json::Value>(&pr) {
                 if let Some(pid) = pj["CurrentPartyID"].as_str().filter(|s| !s.is_empty()) {
-                    if let Ok(party_raw) = glz_get(&region, &shard, &format!("/parties/v1/parties/{}", pid), &at, &ent, &cv) {
+                    if let Ok(party_raw) = client.glz().get(&format!("/parties/v1/parties/{}", pid)) {
                         if let Ok(party) = serde_json::from_str::<serde_json::Value>(&party_raw) {
                             if let Some(muc) = party["MUCName"].as_str().filter(|s| !s.is_empty()) {
                                 muc_labels.insert(muc.to_string(), ("ares-parties".into(), "Party".into()));
@@ -829,10 +938,10 @@ pub fn get_chat_conversations(state: &Mutex<ConnectionState>) -> Result<String,
             (format!("/pregame/v1/players/{}", puuid), "/pregame/v1/matches/", "pregame"),
             (format!("/core-game/v1/players/{}", puuid), "/core-game/v1/matches/", "coregame"),
         ] {
-            if let Ok(pr) = glz_get(&region, &shard, player_path, &at, &ent, &cv) {
+            if let Ok(pr) = client.glz().get(player_path) {
                 if let Ok(pj) = serde_json::from_str::<serde_json::Value>(&pr) {
                     if let Some(mid) = pj["MatchID"].as_str().filter(|s| !s.is_empty()) {
-                        if let Ok(mr) = glz_get(&region, &shard, &format!("{}{}", match_prefix, mid), &at, &ent, &cv) {
+                        if let Ok(mr) = client.glz().get(&format!("{}{}", match_prefix, mid)) {
                             if let Ok(mj) = serde_json::from_str::<serde_json::Value>(&mr) {
                                 let ares = format!("ares-{}", phase);
                                 if let Some(m) = mj["MUCName"].as_str().filter(|s| !s.is_empty()) {
@@ -908,14 +1017,14 @@ pub fn get_chat_conversations(state: &Mutex<ConnectionState>) -> Result<String,
     Ok(serde_json::json!({ "conversations": result }).to_string())
 }
 
-pub fn get_chat_messages(state: &Mutex<ConnectionState>, cid: &str) -> Result<String, String> {
+pub fn get_chat_messages(state: &Mutex<ConnectionState>, cid: &str) -> Result<String, RiotError> {
     let (port, auth) = get_local_creds(state)?;
     let encoded_cid = cid.replace("@", "%40");
     let path = format!("/chat/v6/conversations/{}/messages", encoded_cid);
     local_get(port, &auth, &path)
 }
 
-pub fn send_chat_message(state: &Mutex<ConnectionState>, cid: &str, message: &str, msg_type: &str) -> Result<String, String> {
+pub fn send_chat_message(state: &Mutex<ConnectionState>, cid: &str, message: &str, msg_type: &str) -> Result<String, RiotError> {
     let (port, auth) = get_local_creds(state)?;
     let send_type = if msg_type.is_empty() { "chat" } else { msg_type };
 
@@ -937,23 +1046,20 @@ pub fn send_chat_message(state: &Mutex<ConnectionState>, cid: &str, message: &st
             "cid": try_cid,
             "message": message,
             "type": send_type
-        }).to_string();
+        });
         log_info(&format!("[Chat] Try sending {} to {}", send_type, try_cid));
-        if let Ok(raw) = local_post(port, &auth, "/chat/v6/messages", &body) {
-            let lines: Vec<&str> = raw.splitn(2, '\n').collect();
-            let status = lines.first().and_then(|s| s.parse::<u16>().ok()).unwrap_or(0);
-            let resp_body = lines.get(1).unwrap_or(&"").to_string();
-            if status < 400 {
+        match local_post(port, &auth, "/chat/v6/messages", &body) {
+            Ok(resp_body) => {
                 log_info(&format!("[Chat] Send OK with cid={}", try_cid));
                 return Ok(resp_body);
             }
-            log_info(&format!("[Chat] Failed ({}): {}", status, &resp_body[..resp_body.len().min(100)]));
+            Err(e) => log_info(&format!("[Chat] Failed: {}", e)),
         }
     }
-    Err(format!("Chat send failed: all CID formats tried for {}", cid))
+    Err(RiotError::Local(format!("Chat send failed: all CID formats tried for {}", cid)))
 }
 
-pub fn get_chat_participants(state: &Mutex<ConnectionState>, cid: &str) -> Result<String, String> {
+pub fn get_chat_participants(state: &Mutex<ConnectionState>, cid: &str) -> Result<String, RiotError> {
     let (port, auth) = get_local_creds(state)?;
     let encoded_cid = cid.replace("@", "%40");
     let path = format!("/chat/v6/conversations/{}/participants", encoded_cid);
@@ -1,361 +1,320 @@
-use std::process::Command;
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
-
-pub fn local_get(port: u16, auth: &str, path: &str) -> Result<String, String> {
-    let script = format!(
-        r#"const https=require('https');const r=https.request('https://127.0.0.1:{}{}',{{headers:{{Authorization:'{}'}},agent:new https.Agent({{rejectUnauthorized:false}})}},res=>{{let d='';res.on('data',c=>d+=c);res.on('end',()=>process.stdout.write(d))}});r.on('error',e=>{{process.stderr.write(e.message);process.exit(1)}});r.setTimeout(5000,()=>{{r.destroy();process.stderr.write('timeout');process.exit(1)}});r.end()"#,
-        port, path, auth
-    );
-
-    let mut cmd = Command::new("node");
-    cmd.args(["-e", &script]);
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(0x08000000);
-    let output = cmd.output().map_err(|e| format!("node failed: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("{}: {}", path, stderr.trim()));
-    }
-
-    let body = String::from_utf8_lossy(&output.stdout).to_string();
-    if body.is_empty() {
-        return Err(format!("Empty response from {}", path));
-    }
-    Ok(body)
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use reqwest::blocking::{Client, Request, RequestBuilder};
+use reqwest::header::RETRY_AFTER;
+
+use super::error::{RiotError, TransportCause};
+use super::logging::{log_error, log_info};
+use super::retry::{acquire_host, parse_retry_after, RetryPolicy};
+
+static LOCAL_CLIENT: OnceLock<Result<Client, String>> = OnceLock::new();
+static REMOTE_CLIENT: OnceLock<Result<Client, String>> = OnceLock::new();
+
+fn local_client() -> Result<&'static Client, RiotError> {
+    LOCAL_CLIENT
+        .get_or_init(|| {
+            Client::builder()
+                .danger_accept_invalid_certs(true)
+                .connect_timeout(Duration::from_secs(5))
+                .build()
+                .map_err(|e| format!("http client init: {}", e))
+        })
+        .as_ref()
+        .map_err(|e| RiotError::Local(e.clone()))
 }
 
-pub fn local_put(port: u16, auth: &str, path: &str, body: &str) -> Result<String, String> {
-    let escaped_body = body.replace('\\', "\\\\").replace('\'', "\\'").replace('\n', "\\n");
-    let script = format!(
-        r#"const https=require('https');const d='{body}';const r=https.request('https://127.0.0.1:{port}{path}',{{method:'PUT',headers:{{Authorization:'{auth}','Content-Type':'application/json','Content-Length':Buffer.byteLength(d)}},agent:new https.Agent({{rejectUnauthorized:false}})}},res=>{{let b='';res.on('data',c=>b+=c);res.on('end',()=>process.stdout.write(res.statusCode+'\n'+b))}});r.on('error',e=>{{process.stderr.write(e.message);process.exit(1)}});r.setTimeout(5000,()=>{{r.destroy();process.stderr.write('timeout');process.exit(1)}});r.write(d);r.end()"#,
-        port = port, path = path, auth = auth, body = escaped_body
-    );
-
-    let mut cmd = Command::new("node");
-    cmd.args(["-e", &script]);
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(0x08000000);
-    let output = cmd.output().map_err(|e| format!("node failed: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("PUT {}: {}", path, stderr.trim()));
-    }
-
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+fn remote_client() -> Result<&'static Client, RiotError> {
+    REMOTE_CLIENT
+        .get_or_init(|| {
+            Client::builder()
+                .connect_timeout(Duration::from_secs(10))
+                .pool_idle_timeout(Duration::from_secs(90))
+                .build()
+                .map_err(|e| format!("http client init: {}", e))
+        })
+        .as_ref()
+        .map_err(|e| RiotError::Local(e.clone()))
 }
 
-pub fn local_post(port: u16, auth: &str, path: &str, body: &str) -> Result<String, String> {
-    let escaped_body = body.replace('\\', "\\\\").replace('\'', "\\'").replace('\n', "\\n");
-    let script = format!(
-        r#"const https=require('https');const d='{body}';const r=https.request('https://127.0.0.1:{port}{path}',{{method:'POST',headers:{{Authorization:'{auth}','Content-Type':'application/json','Content-Length':Buffer.byteLength(d)}},agent:new https.Agent({{rejectUnauthorized:false}})}},res=>{{let b='';res.on('data',c=>b+=c);res.on('end',()=>process.stdout.write(res.statusCode+'\n'+b))}});r.on('error',e=>{{process.stderr.write(e.message);process.exit(1)}});r.setTimeout(5000,()=>{{r.destroy();process.stderr.write('timeout');process.exit(1)}});r.write(d);r.end()"#,
-        port = port, path = path, auth = auth, body = escaped_body
-    );
-
-    let mut cmd = Command::new("node");
-    cmd.args(["-e", &script]);
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(0x08000000);
-    let output = cmd.output().map_err(|e| format!("node failed: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("POST {}: {}", path, stderr.trim()));
+fn transport_error(endpoint: &str, e: &reqwest::Error) -> RiotError {
+    if e.is_timeout() {
+        return RiotError::Transport {
+            endpoint: endpoint.to_string(),
+            message: "timeout".to_string(),
+            cause: TransportCause::Timeout,
+        };
+    }
+    let mut msg = e.to_string();
+    let mut source = std::error::Error::source(e);
+    while let Some(s) = source {
+        msg = format!("{}: {}", msg, s);
+        source = s.source();
+    }
+    if e.is_connect() {
+        return RiotError::Transport {
+            endpoint: endpoint.to_string(),
+            message: format!("connection refused ({})", msg),
+            cause: TransportCause::Connect,
+        };
+    }
+    RiotError::Transport {
+        endpoint: endpoint.to_string(),
+        message: msg,
+        cause: TransportCause::Other,
     }
-
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
-pub fn https_get(url: &str) -> Result<String, String> {
-    let script = format!(
-        r#"const https=require('https');https.get('{}',res=>{{let d='';res.on('data',c=>d+=c);res.on('end',()=>process.stdout.write(d))}}).on('error',e=>{{process.stderr.write(e.message);process.exit(1)}})"#,
-        url
-    );
+struct Reply {
+    status: u16,
+    retry_after: Option<u64>,
+    body: String,
+}
 
-    let mut cmd = Command::new("node");
-    cmd.args(["-e", &script]);
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(0x08000000);
-    let output = cmd.output().map_err(|e| format!("node failed: {}", e))?;
+fn execute(client: &Client, request: Request, endpoint: &str) -> Result<Reply, RiotError> {
+    let resp = client.execute(request).map_err(|e| transport_error(endpoint, &e))?;
+    let status = resp.status().as_u16();
+    let retry_after = resp.headers()
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_retry_after);
+    let body = resp.text().map_err(|e| transport_error(endpoint, &e))?;
+    Ok(Reply { status, retry_after, body })
+}
 
-    if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+fn send(req: RequestBuilder, endpoint: &str, timeout_secs: u64, policy: RetryPolicy) -> Result<Reply, RiotError> {
+    let (client, request) = req.timeout(Duration::from_secs(timeout_secs)).build_split();
+    let request = request.map_err(|e| transport_error(endpoint, &e))?;
+    let host = request.url().host_str().unwrap_or_default().to_string();
+
+    let mut request = Some(request);
+    let mut attempt = 1;
+    loop {
+        // The last attempt, or a body that can't be cloned, sends the original request.
+        let copy = request.as_ref().filter(|_| attempt < policy.max_attempts).and_then(|r| r.try_clone());
+        let is_final = copy.is_none();
+        let Some(current) = copy.or_else(|| request.take()) else {
+            return Err(RiotError::Local(format!("Request for {} already sent", endpoint)));
+        };
+        let result = {
+            let _permit = acquire_host(&host);
+            execute(&client, current, endpoint)
+        };
+        let (status, retry_after, err) = match &result {
+            Ok(reply) => (Some(reply.status), reply.retry_after, None),
+            Err(e) => (None, None, Some(e)),
+        };
+        let delay = if is_final { None } else { policy.delay_for(attempt, status, retry_after, err) };
+        let Some(delay) = delay else {
+            if attempt > 1 {
+                match &result {
+                    Ok(reply) if reply.status < 400 => log_info(&format!("[HTTP] {} succeeded on attempt {}", endpoint, attempt)),
+                    Ok(reply) => log_error(&format!("[HTTP] {} gave up after {} attempts: HTTP {}", endpoint, attempt, reply.status)),
+                    Err(e) => log_error(&format!("[HTTP] {} gave up after {} attempts: {}", endpoint, attempt, e)),
+                }
+            }
+            return result;
+        };
+        let reason = match (status, err) {
+            (Some(429), _) => match retry_after {
+                Some(secs) => format!("rate limited (429, Retry-After {}s)", secs),
+                None => "rate limited (429)".to_string(),
+            },
+            (Some(s), _) => format!("server error (HTTP {})", s),
+            (_, Some(e)) => format!("{} ({})", e.kind(), e),
+            _ => "unknown".to_string(),
+        };
+        log_info(&format!(
+            "[HTTP] {} {}, retry {}/{} in {}ms",
+            endpoint, reason, attempt, policy.max_attempts - 1, delay.as_millis()
+        ));
+        std::thread::sleep(delay);
+        attempt += 1;
     }
-
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
-pub fn authed_get(url: &str, access_token: &str) -> Result<String, String> {
-    let script = format!(
-        r#"const https=require('https');const u=new URL('{}');const r=https.request({{hostname:u.hostname,path:u.pathname,headers:{{'Authorization':'Bearer {}'}}}},res=>{{let d='';res.on('data',c=>d+=c);res.on('end',()=>process.stdout.write(d))}});r.on('error',e=>{{process.stderr.write(e.message);process.exit(1)}});r.setTimeout(5000,()=>{{r.destroy();process.stderr.write('timeout');process.exit(1)}});r.end()"#,
-        url, access_token
-    );
+fn send_checked(req: RequestBuilder, endpoint: &str, timeout_secs: u64, policy: RetryPolicy) -> Result<String, RiotError> {
+    let reply = send(req, endpoint, timeout_secs, policy)?;
+    if reply.status >= 400 {
+        return Err(RiotError::from_response(endpoint, reply.status, &reply.body).with_retry_after(reply.retry_after));
+    }
+    Ok(reply.body)
+}
 
-    let mut cmd = Command::new("node");
-    cmd.args(["-e", &script]);
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(0x08000000);
-    let output = cmd.output().map_err(|e| format!("node failed: {}", e))?;
+fn encode_body(endpoint: &str, body: &serde_json::Value) -> Result<Vec<u8>, RiotError> {
+    serde_json::to_vec(body).map_err(|e| RiotError::Local(format!("Encode body for {}: {}", endpoint, e)))
+}
 
-    if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+fn non_empty(endpoint: &str, body: String) -> Result<String, RiotError> {
+    if body.is_empty() {
+        return Err(RiotError::Local(format!("Empty response from {}", endpoint)));
     }
+    Ok(body)
+}
 
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+fn with_riot_headers(req: RequestBuilder, access_token: &str, entitlements: &str, client_version: &str) -> RequestBuilder {
+    req.header("Authorization", format!("Bearer {}", access_token))
+        .header("X-Riot-Entitlements-JWT", entitlements)
+        .header("X-Riot-ClientPlatform", PLATFORM)
+        .header("X-Riot-ClientVersion", client_version)
 }
 
-pub fn authed_get_with_entitlements(url: &str, access_token: &str, entitlements: &str) -> Result<String, String> {
-    let script = format!(
-        r#"const https=require('https');const u=new URL('{}');const r=https.request({{hostname:u.hostname,path:u.pathname+u.search,headers:{{'Authorization':'Bearer {}','X-Riot-Entitlements-JWT':'{}','User-Agent':''}}}},res=>{{let d='';res.on('data',c=>d+=c);res.on('end',()=>process.stdout.write(d))}});r.on('error',e=>{{process.stderr.write(e.message);process.exit(1)}});r.setTimeout(10000,()=>{{r.destroy();process.stderr.write('timeout');process.exit(1)}});r.end()"#,
-        url, access_token, entitlements
-    );
+pub fn local_get(port: u16, auth: &str, path: &str) -> Result<String, RiotError> {
+    let url = format!("https://127.0.0.1:{}{}", port, path);
+    let req = local_client()?.get(&url).header("Authorization", auth);
+    non_empty(path, send_checked(req, path, 5, RetryPolicy::NONE)?)
+}
 
-    let mut cmd = Command::new("node");
-    cmd.args(["-e", &script]);
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(0x08000000);
-    let output = cmd.output().map_err(|e| format!("node failed: {}", e))?;
+pub fn local_put(port: u16, auth: &str, path: &str, body: &serde_json::Value) -> Result<String, RiotError> {
+    let url = format!("https://127.0.0.1:{}{}", port, path);
+    let req = local_client()?
+        .put(&url)
+        .header("Authorization", auth)
+        .header("Content-Type", "application/json")
+        .body(encode_body(path, body)?);
+    send_checked(req, &format!("PUT {}", path), 5, RetryPolicy::NONE)
+}
 
-    if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
-    }
+pub fn local_post(port: u16, auth: &str, path: &str, body: &serde_json::Value) -> Result<String, RiotError> {
+    let url = format!("https://127.0.0.1:{}{}", port, path);
+    let req = local_client()?
+        .post(&url)
+        .header("Authorization", auth)
+        .header("Content-Type", "application/json")
+        .body(encode_body(path, body)?);
+    send_checked(req, &format!("POST {}", path), 5, RetryPolicy::NONE)
+}
 
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+pub fn https_get(url: &str) -> Result<String, RiotError> {
+    send_checked(remote_client()?.get(url), url, 10, RetryPolicy::NONE)
 }
 
-pub fn splooshima_api_post(path: &str, body: &str, api_key: &str) -> Result<String, String> {
-    let url = format!("https://api.splooshima.com{}", path);
-    let escaped_body = body.replace('\\', "\\\\").replace('\'', "\\'");
-    let script = format!(
-        r#"const https=require('https');const u=new URL('{}');const b='{}';const r=https.request({{hostname:u.hostname,path:u.pathname,method:'POST',headers:{{'X-API-Key':'{}','Content-Type':'application/json','Content-Length':Buffer.byteLength(b)}}}},res=>{{let d='';res.on('data',c=>d+=c);res.on('end',()=>process.stdout.write(res.statusCode+'\n'+d))}});r.on('error',e=>{{process.stderr.write(e.message);process.exit(1)}});r.setTimeout(15000,()=>{{r.destroy();process.stderr.write('timeout');process.exit(1)}});r.write(b);r.end()"#,
-        url, escaped_body, api_key
-    );
-
-    let mut cmd = Command::new("node");
-    cmd.args(["-e", &script]);
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(0x08000000);
-    let output = cmd.output().map_err(|e| format!("node failed: {}", e))?;
-
-    if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
-    }
+pub fn authed_get(url: &str, access_token: &str) -> Result<String, RiotError> {
+    let req = remote_client()?
+        .get(url)
+        .header("Authorization", format!("Bearer {}", access_token));
+    send_checked(req, url, 5, RetryPolicy::NONE)
+}
 
-    let raw = String::from_utf8_lossy(&output.stdout).to_string();
-    let lines: Vec<&str> = raw.splitn(2, '\n').collect();
-    let status = lines.first().and_then(|s| s.parse::<u16>().ok()).unwrap_or(0);
-    let resp_body = lines.get(1).unwrap_or(&"").to_string();
+pub fn authed_get_with_entitlements(url: &str, access_token: &str, entitlements: &str) -> Result<String, RiotError> {
+    let req = remote_client()?
+        .get(url)
+        .header("Authorization", format!("Bearer {}", access_token))
+        .header("X-Riot-Entitlements-JWT", entitlements)
+        .header("User-Agent", "");
+    send_checked(req, url, 10, RetryPolicy::NONE)
+}
 
-    if status >= 400 {
-        return Err(format!("Splooshima API {} (HTTP {})", resp_body.chars().take(200).collect::<String>(), status));
-    }
+pub fn github_api_get(path: &str) -> Result<String, RiotError> {
+    let url = format!("https://api.github.com{}", path);
+    let req = remote_client()?.get(&url).header("User-Agent", "ValorantThing");
+    send_checked(req, &url, 10, RetryPolicy::NONE)
+}
 
-    Ok(resp_body)
+pub fn splooshima_api_post(path: &str, body: &serde_json::Value, api_key: &str) -> Result<String, RiotError> {
+    let url = format!("https://api.splooshima.com{}", path);
+    let req = remote_client()?
+        .post(&url)
+        .header("X-API-Key", api_key)
+        .header("Content-Type", "application/json")
+        .body(encode_body(path, body)?);
+    send_checked(req, &url, 15, RetryPolicy::NONE)
 }
 
-pub fn henrik_api_get(path: &str, api_key: &str) -> Result<String, String> {
+pub fn henrik_api_get(path: &str, api_key: &str) -> Result<String, RiotError> {
     let url = format!("https://api.henrikdev.xyz{}", path);
-    let script = format!(
-        r#"const https=require('https');const u=new URL('{}');const r=https.request({{hostname:u.hostname,path:u.pathname,headers:{{'Authorization':'{}'}}}},res=>{{let d='';res.on('data',c=>d+=c);res.on('end',()=>process.stdout.write(d))}});r.on('error',e=>{{process.stderr.write(e.message);process.exit(1)}});r.setTimeout(10000,()=>{{r.destroy();process.stderr.write('timeout');process.exit(1)}});r.end()"#,
-        url, api_key
-    );
-
-    let mut cmd = Command::new("node");
-    cmd.args(["-e", &script]);
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(0x08000000);
-    let output = cmd.output().map_err(|e| format!("node failed: {}", e))?;
-
-    if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
-    }
-
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    let req = remote_client()?.get(&url).header("Authorization", api_key);
+    Ok(send(req, &url, 10, RetryPolicy::NONE)?.body)
 }
 
 const PLATFORM: &str = "ew0KCSJwbGF0Zm9ybVR5cGUiOiAiUEMiLA0KCSJwbGF0Zm9ybU9TIjogIldpbmRvd3MiLA0KCSJwbGF0Zm9ybU9TVmVyc2lvbiI6ICIxMC4wLjE5MDQyLjEuMjU2LjY0Yml0IiwNCgkicGxhdGZvcm1DaGlwc2V0IjogIlVua25vd24iDQp9";
 
-pub fn pd_get(shard: &str, path: &str, access_token: &str, entitlements: &str, client_version: &str) -> Result<String, String> {
+pub fn pd_get(shard: &str, path: &str, access_token: &str, entitlements: &str, client_version: &str) -> Result<String, RiotError> {
     let url = format!("https://pd.{}.a.pvp.net{}", shard, path);
-    let script = format!(
-        r#"const https=require('https');const zlib=require('zlib');const u=new URL('{}');const r=https.request({{hostname:u.hostname,path:u.pathname,headers:{{'Authorization':'Bearer {}','X-Riot-Entitlements-JWT':'{}','X-Riot-ClientPlatform':'{}','X-Riot-ClientVersion':'{}'}}}},res=>{{const chunks=[];res.on('data',c=>chunks.push(c));res.on('end',()=>{{let buf=Buffer.concat(chunks);const enc=res.headers['content-encoding'];process.stderr.write('HTTP '+res.statusCode+' enc='+(enc||'none')+' raw='+buf.length+' ');if(enc==='gzip'){{try{{buf=zlib.gunzipSync(buf)}}catch(e){{process.stderr.write('gunzip err:'+e.message+' ')}}}}else if(enc==='deflate'){{try{{buf=zlib.inflateSync(buf)}}catch(e){{}}}}const out=buf.toString();process.stderr.write('len='+out.length);process.stdout.write(out)}})}});r.on('error',e=>{{process.stderr.write('err:'+e.message);process.exit(1)}});r.setTimeout(15000,()=>{{r.destroy();process.stderr.write('timeout');process.exit(1)}});r.end()"#,
-        url, access_token, entitlements, PLATFORM, client_version
-    );
-
-    let mut cmd = Command::new("node");
-    cmd.args(["-e", &script]);
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(0x08000000);
-    let output = cmd.output().map_err(|e| format!("node failed: {}", e))?;
-
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-
-    if !output.status.success() {
-        return Err(format!("{}: {}", path, stderr.trim()));
-    }
-
-    let body = String::from_utf8_lossy(&output.stdout).to_string();
-    if body.is_empty() {
-        return Err(format!("Empty response from {} (debug: {})", path, stderr.trim()));
-    }
-    Ok(body)
+    let req = with_riot_headers(remote_client()?.get(&url), access_token, entitlements, client_version);
+    non_empty(path, send_checked(req, path, 15, RetryPolicy::READ)?)
 }
 
-pub fn pd_put(shard: &str, path: &str, body: &str, access_token: &str, entitlements: &str, client_version: &str) -> Result<String, String> {
+pub fn pd_put(shard: &str, path: &str, body: &serde_json::Value, access_token: &str, entitlements: &str, client_version: &str) -> Result<String, RiotError> {
     let url = format!("https://pd.{}.a.pvp.net{}", shard, path);
-    let escaped_body = body.replace('\\', "\\\\").replace('\'', "\\'");
-    let script = format!(
-        r#"const https=require('https');const u=new URL('{}');const b='{}';const r=https.request({{hostname:u.hostname,path:u.pathname,method:'PUT',headers:{{'Authorization':'Bearer {}','X-Riot-Entitlements-JWT':'{}','X-Riot-ClientPlatform':'{}','X-Riot-ClientVersion':'{}','Content-Type':'application/json','Content-Length':Buffer.byteLength(b)}}}},res=>{{let d='';res.on('data',c=>d+=c);res.on('end',()=>process.stdout.write(d))}});r.on('error',e=>{{process.stderr.write(e.message);process.exit(1)}});r.setTimeout(5000,()=>{{r.destroy();process.stderr.write('timeout');process.exit(1)}});r.end(b)"#,
-        url, escaped_body, access_token, entitlements, PLATFORM, client_version
-    );
-
-    let mut cmd = Command::new("node");
-    cmd.args(["-e", &script]);
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(0x08000000);
-    let output = cmd.output().map_err(|e| format!("node failed: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("{}: {}", path, stderr.trim()));
-    }
-
-    let body_out = String::from_utf8_lossy(&output.stdout).to_string();
-    if body_out.is_empty() {
-        return Err(format!("Empty response from {}", path));
-    }
-    Ok(body_out)
+    let req = with_riot_headers(remote_client()?.put(&url), access_token, entitlements, client_version)
+        .header("Content-Type", "application/json")
+        .body(encode_body(path, body)?);
+    non_empty(path, send_checked(req, path, 5, RetryPolicy::READ)?)
 }
 
-pub fn glz_get(region: &str, shard: &str, path: &str, access_token: &str, entitlements: &str, client_version: &str) -> Result<String, String> {
+pub fn glz_get(region: &str, shard: &str, path: &str, access_token: &str, entitlements: &str, client_version: &str) -> Result<String, RiotError> {
     let url = format!("https://glz-{}-1.{}.a.pvp.net{}", region, shard, path);
-    let script = format!(
-        r#"const https=require('https');const u=new URL('{}');const r=https.request({{hostname:u.hostname,path:u.pathname,headers:{{'Authorization':'Bearer {}','X-Riot-Entitlements-JWT':'{}','X-Riot-ClientPlatform':'{}','X-Riot-ClientVersion':'{}'}}}},res=>{{let d='';res.on('data',c=>d+=c);res.on('end',()=>process.stdout.write(d))}});r.on('error',e=>{{process.stderr.write(e.message);process.exit(1)}});r.setTimeout(5000,()=>{{r.destroy();process.stderr.write('timeout');process.exit(1)}});r.end()"#,
-        url, access_token, entitlements, PLATFORM, client_version
-    );
-
-    let mut cmd = Command::new("node");
-    cmd.args(["-e", &script]);
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(0x08000000);
-    let output = cmd.output().map_err(|e| format!("node failed: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("{}: {}", path, stderr.trim()));
-    }
-
-    let body = String::from_utf8_lossy(&output.stdout).to_string();
-    if body.is_empty() {
-        return Err(format!("Empty response from {}", path));
-    }
-    Ok(body)
+    let req = with_riot_headers(remote_client()?.get(&url), access_token, entitlements, client_version);
+    non_empty(path, send_checked(req, path, 5, RetryPolicy::READ)?)
 }
 
-pub fn glz_delete(region: &str, shard: &str, path: &str, access_token: &str, entitlements: &str, client_version: &str) -> Result<String, String> {
+pub fn glz_delete(region: &str, shard: &str, path: &str, access_token: &str, entitlements: &str, client_version: &str) -> Result<String, RiotError> {
     let url = format!("https://glz-{}-1.{}.a.pvp.net{}", region, shard, path);
-    let script = format!(
-        r#"const https=require('https');const u=new URL('{}');const r=https.request({{hostname:u.hostname,path:u.pathname,method:'DELETE',headers:{{'Authorization':'Bearer {}','X-Riot-Entitlements-JWT':'{}','X-Riot-ClientPlatform':'{}','X-Riot-ClientVersion':'{}'}}}},res=>{{let d='';res.on('data',c=>d+=c);res.on('end',()=>process.stdout.write(d))}});r.on('error',e=>{{process.stderr.write(e.message);process.exit(1)}});r.setTimeout(5000,()=>{{r.destroy();process.stderr.write('timeout');process.exit(1)}});r.end()"#,
-        url, access_token, entitlements, PLATFORM, client_version
-    );
-
-    let mut cmd = Command::new("node");
-    cmd.args(["-e", &script]);
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(0x08000000);
-    let output = cmd.output().map_err(|e| format!("node failed: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("{}: {}", path, stderr.trim()));
-    }
-
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    let req = with_riot_headers(remote_client()?.delete(&url), access_token, entitlements, client_version);
+    send_checked(req, path, 5, RetryPolicy::WRITE)
 }
 
-pub fn glz_post_body(region: &str, shard: &str, path: &str, body: &str, access_token: &str, entitlements: &str, client_version: &str) -> Result<String, String> {
+pub fn glz_post_body(region: &str, shard: &str, path: &str, body: &serde_json::Value, access_token: &str, entitlements: &str, client_version: &str) -> Result<String, RiotError> {
     let url = format!("https://glz-{}-1.{}.a.pvp.net{}", region, shard, path);
-    let escaped_body = body.replace('\\', "\\\\").replace('\'', "\\'");
-    let script = format!(
-        r#"const https=require('https');const u=new URL('{}');const b='{}';const r=https.request({{hostname:u.hostname,path:u.pathname,method:'POST',headers:{{'Authorization':'Bearer {}','X-Riot-Entitlements-JWT':'{}','X-Riot-ClientPlatform':'{}','X-Riot-ClientVersion':'{}','Content-Type':'application/json','Content-Length':Buffer.byteLength(b)}}}},res=>{{let d='';res.on('data',c=>d+=c);res.on('end',()=>{{process.stderr.write('HTTP '+res.statusCode+' ');process.stdout.write(d)}})}});r.on('error',e=>{{process.stderr.write(e.message);process.exit(1)}});r.setTimeout(5000,()=>{{r.destroy();process.stderr.write('timeout');process.exit(1)}});r.end(b)"#,
-        url, escaped_body, access_token, entitlements, PLATFORM, client_version
-    );
-
-    let mut cmd = Command::new("node");
-    cmd.args(["-e", &script]);
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(0x08000000);
-    let output = cmd.output().map_err(|e| format!("node failed: {}", e))?;
-
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-    let body_out = String::from_utf8_lossy(&output.stdout).to_string();
-    if !output.status.success() {
-        return Err(format!("{}: {}", path, stderr.trim()));
-    }
-
-    if let Some(code_str) = stderr.trim().strip_prefix("HTTP ") {
-        if let Ok(code) = code_str.trim().parse::<u16>() {
-            if code >= 400 {
-                return Err(format!("{}: HTTP {} - {}", path, code, &body_out[..body_out.len().min(300)]));
-            }
-        }
-    }
-
-    Ok(body_out)
+    let req = with_riot_headers(remote_client()?.post(&url), access_token, entitlements, client_version)
+        .header("Content-Type", "application/json")
+        .body(encode_body(path, body)?);
+    send_checked(req, path, 5, RetryPolicy::WRITE)
 }
 
-pub fn pd_batch_get(shard: &str, paths: &[String], access_token: &str, entitlements: &str, client_version: &str) -> Result<Vec<serde_json::Value>, String> {
+pub fn pd_batch_get(shard: &str, paths: &[String], access_token: &str, entitlements: &str, client_version: &str) -> Result<Vec<Result<serde_json::Value, RiotError>>, RiotError> {
     if paths.is_empty() {
         return Ok(vec![]);
     }
-    let base_url = format!("https://pd.{}.a.pvp.net", shard);
-    let paths_json = serde_json::to_string(paths).map_err(|e| format!("json: {}", e))?;
-
-    let script = format!(
-        r#"const https=require('https');const zlib=require('zlib');const b='{}';const ps={};const h={{'Authorization':'Bearer {}','X-Riot-Entitlements-JWT':'{}','X-Riot-ClientPlatform':'{}','X-Riot-ClientVersion':'{}'}};function f(p){{return new Promise((ok,no)=>{{const u=new URL(b+p);const r=https.request({{hostname:u.hostname,path:u.pathname+u.search,headers:h}},res=>{{const c=[];res.on('data',d=>c.push(d));res.on('end',()=>{{let buf=Buffer.concat(c);const e=res.headers['content-encoding'];if(e==='gzip')try{{buf=zlib.gunzipSync(buf)}}catch(_){{}}else if(e==='deflate')try{{buf=zlib.inflateSync(buf)}}catch(_){{}};try{{ok(JSON.parse(buf.toString()))}}catch(_){{ok(null)}}}});}});r.on('error',()=>ok(null));r.setTimeout(15000,()=>{{r.destroy();ok(null)}});r.end()}})}};Promise.all(ps.map(p=>f(p))).then(r=>process.stdout.write(JSON.stringify(r)))"#,
-        base_url, paths_json, access_token, entitlements, PLATFORM, client_version
-    );
-
-    let mut cmd = Command::new("node");
-    cmd.args(["-e", &script]);
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(0x08000000);
-    let output = cmd.output().map_err(|e| format!("batch node failed: {}", e))?;
-
-    if !output.status.success() {
-        return Err(format!("batch fetch failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
-    }
-
-    let body = String::from_utf8_lossy(&output.stdout).to_string();
-    serde_json::from_str(&body).map_err(|e| format!("parse batch: {}", e))
+    let client = remote_client()?;
+
+    let results = std::thread::scope(|scope| {
+        let handles: Vec<_> = paths.iter().map(|path| {
+            scope.spawn(move || {
+                let url = format!("https://pd.{}.a.pvp.net{}", shard, path);
+                let req = with_riot_headers(client.get(&url), access_token, entitlements, client_version);
+                let body = send_checked(req, path, 15, RetryPolicy::READ)?;
+                serde_json::from_str::<serde_json::Value>(&body).map_err(|e| RiotError::decode(path, e))
+            })
+        }).collect();
+        handles.into_iter()
+            .map(|h| h.join().unwrap_or_else(|_| Err(RiotError::Local("batch worker panicked".to_string()))))
+            .collect()
+    });
+
+    Ok(results)
 }
 
-pub fn glz_post(region: &str, shard: &str, path: &str, access_token: &str, entitlements: &str, client_version: &str) -> Result<String, String> {
+pub fn glz_post(region: &str, shard: &str, path: &str, access_token: &str, entitlements: &str, client_version: &str) -> Result<String, RiotError> {
     let url = format!("https://glz-{}-1.{}.a.pvp.net{}", region, shard, path);
-    let script = format!(
-        r#"const https=require('https');const u=new URL('{}');const r=https.request({{hostname:u.hostname,path:u.pathname+u.search,method:'POST',headers:{{'Authorization':'Bearer {}','X-Riot-Entitlements-JWT':'{}','X-Riot-ClientPlatform':'{}','X-Riot-ClientVersion':'{}','Content-Length':'0'}}}},res=>{{let d='';res.on('data',c=>d+=c);res.on('end',()=>{{process.stderr.write('HTTP '+res.statusCode+' ');process.stdout.write(d)}})}});r.on('error',e=>{{process.stderr.write(e.message);process.exit(1)}});r.setTimeout(5000,()=>{{r.destroy();process.stderr.write('timeout');process.exit(1)}});r.end()"#,
-        url, access_token, entitlements, PLATFORM, client_version
-    );
-
-    let mut cmd = Command::new("node");
-    cmd.args(["-e", &script]);
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(0x08000000);
-    let output = cmd.output().map_err(|e| format!("node failed: {}", e))?;
-
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-    let body = String::from_utf8_lossy(&output.stdout).to_string();
-    if !output.status.success() {
-        return Err(format!("{}: {}", path, stderr.trim()));
-    }
+    let req = with_riot_headers(remote_client()?.post(&url), access_token, entitlements, client_version)
+        .body("");
+    send_checked(req, path, 5, RetryPolicy::WRITE)
+}
 
-    if let Some(code) = stderr.trim().strip_prefix("HTTP ").and_then(|s| s.split_whitespace().next()).and_then(|s| s.parse::<u16>().ok()) {
-        if code >= 400 {
-            return Err(format!("{}: HTTP {} {}", path, code, body.trim()));
+#[cfg(test)]
+mod tests {
+    use super::encode_body;
+
+    #[test]
+    fn chat_bodies_round_trip() {
+        let messages = [
+            r#"she said "gg" and left"#,
+            r"C:\Riot Games\VALORANT\live",
+            "line one\nline two",
+            "windows\r\nline endings\r\n",
+            "\"}, \"type\": \"groupchat",
+            "👍🏽 family 👨‍👩‍👧‍👦 flag 🇧🇷",
+            "\t\u{0}\u{1f}",
+        ];
+        for message in messages {
+            let body = serde_json::json!({ "cid": "abc@ares-parties.na1.pvp.net", "message": message, "type": "groupchat" });
+            let bytes = encode_body("/chat/v6/messages", &body).unwrap();
+            let decoded: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+            assert_eq!(decoded, body);
+            assert_eq!(decoded["message"].as_str(), Some(message));
+            assert_eq!(decoded["type"].as_str(), Some("groupchat"));
         }
     }
-
-    Ok(body)
 }
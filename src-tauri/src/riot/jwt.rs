@@ -0,0 +1,39 @@
+use base64::Engine;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TokenClaims {
+    pub issued_at: Option<u64>,
+    pub expires_at: Option<u64>,
+    pub scopes: Vec<String>,
+}
+
+pub fn decode_payload(token: &str) -> Result<serde_json::Value, String> {
+    let parts: Vec<&str> = token.trim().split('.').collect();
+    if parts.len() < 2 {
+        return Err("Invalid JWT format".to_string());
+    }
+
+    let payload = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(parts[1])
+        .or_else(|_| base64::engine::general_purpose::STANDARD_NO_PAD.decode(parts[1]))
+        .or_else(|_| base64::engine::general_purpose::STANDARD.decode(parts[1]))
+        .map_err(|e| format!("base64 decode JWT payload: {}", e))?;
+
+    serde_json::from_slice(&payload).map_err(|e| format!("parse JWT payload: {}", e))
+}
+
+// Access tokens carry `scp` as an array; some Riot tokens use a space-separated `scope`.
+pub fn claims(token: &str) -> Result<TokenClaims, String> {
+    let payload = decode_payload(token)?;
+    let scopes = match (&payload["scp"], &payload["scope"]) {
+        (serde_json::Value::Array(list), _) => list.iter().filter_map(|s| s.as_str()).map(|s| s.to_string()).collect(),
+        (_, serde_json::Value::String(s)) => s.split_whitespace().map(|s| s.to_string()).collect(),
+        _ => Vec::new(),
+    };
+    Ok(TokenClaims {
+        issued_at: payload["iat"].as_u64(),
+        expires_at: payload["exp"].as_u64(),
+        scopes,
+    })
+}
@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use super::client::RiotClient;
+use super::content::{self, ContentIndex, ContentItem};
+use super::error::RiotError;
+use super::logging::log_error;
+use super::roster::{self, Seat};
+use super::types::ConnectionState;
+
+const SOCKET_SKIN: &str = "bcef87d6-209b-46c6-8b19-fbe40bd95abc";
+const SOCKET_SKIN_LEVEL: &str = "e7c63390-eda7-46e0-bb7a-a6abdacd2433";
+const SOCKET_CHROMA: &str = "3ad1b2b2-acdb-4524-852f-954a76ddae0a";
+const SOCKET_BUDDY: &str = "77258665-71d1-4623-bc72-44db9bd5b3b3";
+const SOCKET_BUDDY_LEVEL: &str = "dd3bf334-87f3-40bd-b043-682a57a8dc3a";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemRef {
+    pub id: String,
+    pub name: Option<String>,
+    pub icon: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WeaponLoadout {
+    pub weapon: ItemRef,
+    pub skin: Option<ItemRef>,
+    pub chroma: Option<ItemRef>,
+    pub buddy: Option<ItemRef>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayerLoadout {
+    pub puuid: String,
+    pub character_id: String,
+    pub player_card: Option<ItemRef>,
+    pub weapons: Vec<WeaponLoadout>,
+    pub sprays: Vec<ItemRef>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchLoadouts {
+    pub match_id: String,
+    pub phase: String,
+    // Whether names and icons were resolved; false when the content index could not be built.
+    pub resolved: bool,
+    pub players: Vec<PlayerLoadout>,
+}
+
+fn resolve(table: Option<&HashMap<String, ContentItem>>, id: &str) -> Option<ItemRef> {
+    let id = id.to_lowercase();
+    if id.is_empty() {
+        return None;
+    }
+    let item = table.and_then(|t| t.get(&id));
+    Some(ItemRef {
+        name: item.map(|i| i.name.clone()),
+        icon: item.and_then(|i| i.icon.clone()),
+        id,
+    })
+}
+
+fn socket<'a>(sockets: &'a serde_json::Value, socket_id: &str) -> Option<&'a str> {
+    sockets[socket_id]["Item"]["ID"].as_str()
+}
+
+fn weapons(items: &serde_json::Value, index: Option<&ContentIndex>) -> Vec<WeaponLoadout> {
+    let Some(items) = items.as_object() else { return Vec::new() };
+    let mut weapons: Vec<WeaponLoadout> = items.iter()
+        .map(|(weapon_id, item)| {
+            let sockets = &item["Sockets"];
+            let skin = socket(sockets, SOCKET_SKIN).or_else(|| socket(sockets, SOCKET_SKIN_LEVEL));
+            let buddy = socket(sockets, SOCKET_BUDDY).or_else(|| socket(sockets, SOCKET_BUDDY_LEVEL));
+            WeaponLoadout {
+                weapon: resolve(index.map(|i| &i.weapons), weapon_id)
+                    .unwrap_or(ItemRef { id: weapon_id.clone(), name: None, icon: None }),
+                skin: skin.and_then(|id| resolve(index.map(|i| &i.skins), id)),
+                chroma: socket(sockets, SOCKET_CHROMA).and_then(|id| resolve(index.map(|i| &i.chromas), id)),
+                buddy: buddy.and_then(|id| resolve(index.map(|i| &i.buddies), id)),
+            }
+        })
+        .collect();
+    weapons.sort_by(|a, b| a.weapon.name.cmp(&b.weapon.name));
+    weapons
+}
+
+fn sprays(sprays: &serde_json::Value, index: Option<&ContentIndex>) -> Vec<ItemRef> {
+    sprays["SpraySelections"].as_array().into_iter().flatten()
+        .filter_map(|s| s["SprayID"].as_str())
+        .filter_map(|id| resolve(index.map(|i| &i.sprays), id))
+        .collect()
+}
+
+fn players(raw: &serde_json::Value, seats: &[Seat], index: Option<&ContentIndex>) -> Vec<PlayerLoadout> {
+    let entries = raw["Loadouts"].as_array().cloned().unwrap_or_default();
+    entries.iter().enumerate()
+        .map(|(i, entry)| {
+            // Core-game wraps each loadout with its CharacterID; pregame lists them bare, in team order.
+            let loadout = if entry["Loadout"].is_object() { &entry["Loadout"] } else { entry };
+            let puuid = loadout["Subject"].as_str().map(|s| s.to_string())
+                .or_else(|| seats.get(i).map(|p| p.puuid.clone()))
+                .unwrap_or_default();
+            let known = seats.iter().find(|p| p.puuid == puuid);
+            let character_id = entry["CharacterID"].as_str().map(|s| s.to_string())
+                .or_else(|| known.map(|p| p.character_id.clone()))
+                .unwrap_or_default()
+                .to_lowercase();
+            PlayerLoadout {
+                player_card: known.and_then(|p| resolve(index.map(|i| &i.cards), &p.identity.player_card_id)),
+                weapons: weapons(&loadout["Items"], index),
+                sprays: sprays(&loadout["Sprays"], index),
+                puuid,
+                character_id,
+            }
+        })
+        .collect()
+}
+
+pub fn get_match_loadouts(state: &Mutex<ConnectionState>, match_id: &str) -> Result<MatchLoadouts, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let (phase, seats) = roster::seats(&client, match_id)?;
+    let path = match phase {
+        "pregame" => format!("/pregame/v1/matches/{}/loadouts", match_id),
+        _ => format!("/core-game/v1/matches/{}/loadouts", match_id),
+    };
+    let raw = client.glz().get_json(&path)?;
+
+    let index = match content::index() {
+        Ok(index) => Some(index),
+        Err(e) => {
+            log_error(&format!("[Content] Item index unavailable, returning raw IDs: {}", e));
+            None
+        }
+    };
+
+    Ok(MatchLoadouts {
+        match_id: match_id.to_string(),
+        phase: phase.to_string(),
+        resolved: index.is_some(),
+        players: players(&raw, &seats, index.as_deref()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::riot::models;
+
+    const JETT: &str = "add6443a-41bd-e414-f6ad-e58d267f4e95";
+    const OMEN: &str = "8e253930-4c05-31dd-1b6c-968525494517";
+    const VANDAL: &str = "9c82e19d-4575-0200-1a81-3eacf00cf872";
+
+    fn index() -> ContentIndex {
+        let v: serde_json::Value = serde_json::from_str(include_str!("../../fixtures/valorant_api_content.json")).unwrap();
+        let list = |key: &str| v[key].as_array().cloned().unwrap();
+        content::index_from(&list("weapons"), &list("buddies"), &list("sprays"), &list("playercards"))
+    }
+
+    fn pregame() -> (serde_json::Value, Vec<Seat>) {
+        let raw = serde_json::from_str(include_str!("../../fixtures/pregame_loadouts.json")).unwrap();
+        let seats = roster::pregame_seats(models::decode_str("/pregame/v1/matches", include_str!("../../fixtures/pregame_match.json")).unwrap());
+        (raw, seats)
+    }
+
+    fn coregame() -> (serde_json::Value, Vec<Seat>) {
+        let raw = serde_json::from_str(include_str!("../../fixtures/coregame_loadouts.json")).unwrap();
+        let seats = roster::coregame_seats(models::decode_str("/core-game/v1/matches", include_str!("../../fixtures/coregame_match.json")).unwrap());
+        (raw, seats)
+    }
+
+    fn name(item: &Option<ItemRef>) -> Option<&str> {
+        item.as_ref().and_then(|i| i.name.as_deref())
+    }
+
+    #[test]
+    fn pregame_loadouts_follow_team_order() {
+        let (raw, seats) = pregame();
+        let players = players(&raw, &seats, Some(&index()));
+        assert_eq!(players.len(), 2);
+        assert_eq!(players[0].puuid, "00000000-0000-0000-0000-000000000001");
+        assert_eq!(players[0].character_id, JETT);
+        assert_eq!(name(&players[0].player_card), Some("Valorant Card"));
+        assert_eq!(players[1].puuid, "00000000-0000-0000-0000-000000000002");
+        assert_eq!(players[1].character_id, "");
+        assert!(players[1].weapons.is_empty() && players[1].sprays.is_empty());
+    }
+
+    #[test]
+    fn coregame_loadouts_carry_their_character() {
+        let (raw, seats) = coregame();
+        let players = players(&raw, &seats, Some(&index()));
+        assert_eq!(players.len(), 2);
+        assert_eq!(players[0].puuid, "00000000-0000-0000-0000-000000000003");
+        assert_eq!(players[0].character_id, OMEN);
+        assert_eq!(name(&players[0].player_card), Some("Valorant Card"));
+        assert_eq!(players[1].puuid, "00000000-0000-0000-0000-000000000001");
+        assert_eq!(players[1].character_id, JETT);
+
+        let vandal = &players[0].weapons[0];
+        assert_eq!(vandal.weapon.id, VANDAL);
+        assert_eq!(name(&vandal.skin), Some("Standard Vandal"));
+        assert!(vandal.chroma.is_none());
+        assert_eq!(name(&vandal.buddy), Some("Spike Buddy"));
+        assert_eq!(players[0].sprays.iter().map(|s| s.name.as_deref()).collect::<Vec<_>>(), [Some("Plain Spray")]);
+    }
+
+    #[test]
+    fn weapons_resolve_through_level_sockets() {
+        let (raw, _) = pregame();
+        let weapons = weapons(&raw["Loadouts"][0]["Items"], Some(&index()));
+        // Unknown weapons sort ahead of the named ones.
+        let names: Vec<_> = weapons.iter().map(|w| w.weapon.name.as_deref()).collect();
+        assert_eq!(names, [None, Some("Classic"), Some("Vandal")]);
+
+        let classic = &weapons[1];
+        assert_eq!(name(&classic.skin), Some("Standard Classic"));
+        assert!(classic.chroma.is_none() && classic.buddy.is_none());
+
+        // Only the skin level and buddy level sockets are filled here.
+        let vandal = &weapons[2];
+        assert_eq!(name(&vandal.skin), Some("Reaver Vandal"));
+        assert_eq!(name(&vandal.chroma), Some("Reaver Vandal Variant 1 Red"));
+        assert!(vandal.chroma.as_ref().unwrap().icon.as_deref().unwrap().ends_with("displayicon.png"));
+        assert_eq!(name(&vandal.buddy), Some("Spike Buddy"));
+    }
+
+    #[test]
+    fn unknown_ids_keep_their_id_without_a_name() {
+        let (raw, _) = pregame();
+        let loadout = &raw["Loadouts"][0];
+        let index = index();
+
+        let weapons = weapons(&loadout["Items"], Some(&index));
+        let unknown = &weapons[0];
+        assert_eq!(unknown.weapon.id, "63e6c2b6-4a8e-869c-3d4c-e38355226584");
+        assert!(unknown.weapon.name.is_none() && unknown.weapon.icon.is_none());
+        let skin = unknown.skin.as_ref().unwrap();
+        assert_eq!(skin.id, "e9f0a1b2-c3d4-4e5f-8a6b-7c8d9e0f1a21");
+        assert!(skin.name.is_none() && skin.icon.is_none());
+
+        let sprays = sprays(&loadout["Sprays"], Some(&index));
+        assert_eq!(sprays.len(), 2);
+        assert_eq!(sprays[0].name.as_deref(), Some("Nice to Zap You Spray"));
+        assert!(sprays[0].icon.as_deref().unwrap().ends_with("fulltransparenticon.png"));
+        assert_eq!(sprays[1].id, "d0e1f2a3-b4c5-4d6e-9f7a-8b9c0d1e2f31");
+        assert!(sprays[1].name.is_none());
+
+        assert!(resolve(Some(&index.sprays), "").is_none());
+    }
+
+    #[test]
+    fn without_an_index_only_ids_are_returned() {
+        let (raw, seats) = pregame();
+        let players = players(&raw, &seats, None);
+        let jett = &players[0];
+        assert_eq!(jett.player_card.as_ref().unwrap().id, "9fb348bc-41a0-91ad-8a3e-818035c4e561");
+        assert!(jett.player_card.as_ref().unwrap().name.is_none());
+        assert_eq!(jett.weapons.len(), 3);
+        assert!(jett.weapons.iter().all(|w| w.weapon.name.is_none() && w.skin.as_ref().is_some_and(|s| s.name.is_none())));
+        assert_eq!(jett.sprays.len(), 2);
+        assert!(jett.sprays.iter().all(|s| s.name.is_none()));
+    }
+}
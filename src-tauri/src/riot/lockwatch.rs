@@ -0,0 +1,471 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use super::connection::{connect_and_store, disconnect, get_cached_player, is_reconnecting, refresh_due_in, refresh_tokens, session_puuid, suspend};
+use super::error::RiotError;
+use super::logging::{log_error, log_info};
+use super::paths;
+use super::process::{is_valorant_running, parse_lockfile};
+use super::procinfo::is_pid_alive;
+use super::types::{ConnectionState, PlayerInfo};
+use super::xmpp::{xmpp_connect, xmpp_disconnect, XmppState};
+
+// notify misses events on some Wine setups, so the lockfile is also re-read
+// on this interval.
+const RECHECK_INTERVAL: Duration = Duration::from_secs(5);
+const CONNECT_RETRY: Duration = Duration::from_secs(15);
+// Signing in to another account keeps the same lockfile, so the session owner
+// is checked separately.
+const SESSION_CHECK: Duration = Duration::from_secs(30);
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+type Lockfile = (u32, u16, String);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum SessionState {
+    Connected,
+    Reconnecting,
+    Disconnected,
+}
+
+#[derive(Clone, Serialize)]
+struct ConnectionChanged {
+    connected: bool,
+    state: SessionState,
+    reason: String,
+    player: Option<PlayerInfo>,
+}
+
+type StateHook<T> = Box<dyn Fn(&Mutex<ConnectionState>) -> T + Send>;
+
+// Everything the watcher reads or drives outside its own state. `live` wires these to the
+// resolved Riot Client paths, connection.rs and the frontend; tests substitute their own.
+struct Hooks {
+    lockfile_path: Box<dyn Fn() -> Option<PathBuf> + Send>,
+    game_running: Box<dyn Fn() -> bool + Send>,
+    connect: StateHook<Result<(), RiotError>>,
+    refresh: StateHook<Result<(), RiotError>>,
+    suspend: StateHook<()>,
+    emit: Box<dyn Fn(ConnectionChanged) + Send>,
+}
+
+impl Hooks {
+    fn live(app: AppHandle) -> Self {
+        Hooks {
+            lockfile_path: Box::new(|| paths::resolve().ok().map(|p| p.lockfile())),
+            game_running: Box::new(is_valorant_running),
+            connect: Box::new(|state| connect_and_store(state).map(|_| ())),
+            refresh: Box::new(refresh_tokens),
+            suspend: Box::new(suspend),
+            emit: Box::new(move |change| { let _ = app.emit("connection-changed", change); }),
+        }
+    }
+}
+
+struct LockWatch {
+    hooks: Hooks,
+    state: Arc<Mutex<ConnectionState>>,
+    xmpp: Arc<Mutex<XmppState>>,
+    // XMPP was up when the Riot Client went away; bring it back after reconnecting.
+    restore_xmpp: bool,
+    last: Option<Lockfile>,
+    last_attempt: Option<Instant>,
+    last_session_check: Option<Instant>,
+}
+
+impl LockWatch {
+    fn emit(&self, state: SessionState, reason: &str) {
+        let player = if state == SessionState::Disconnected { None } else { get_cached_player(&self.state) };
+        (self.hooks.emit)(ConnectionChanged {
+            connected: state == SessionState::Connected,
+            state,
+            reason: reason.to_string(),
+            player,
+        });
+    }
+
+    // Keeps identity and caches; only the tokens and the XMPP stream go.
+    fn suspend(&mut self, reason: &str) {
+        let xmpp_up = self.xmpp.lock().map(|x| x.connected).unwrap_or(false);
+        if xmpp_up {
+            self.restore_xmpp = true;
+            let _ = xmpp_disconnect(&self.xmpp);
+        }
+        (self.hooks.suspend)(&self.state);
+        self.emit(SessionState::Reconnecting, reason);
+    }
+
+    fn resume_xmpp(&mut self) {
+        if !std::mem::take(&mut self.restore_xmpp) {
+            return;
+        }
+        let already_up = self.xmpp.lock().map(|x| x.connected).unwrap_or(false);
+        if already_up {
+            return;
+        }
+        match xmpp_connect(&self.xmpp, &self.state) {
+            Ok(_) => log_info("[Lockfile] XMPP session restored"),
+            Err(e) => log_error(&format!("[Lockfile] XMPP reconnect failed: {}", e)),
+        }
+    }
+
+    fn is_connected(&self) -> bool {
+        self.state.lock().map(|s| s.connected).unwrap_or(false)
+    }
+
+    fn connected_port(&self) -> Option<u16> {
+        self.state.lock().ok().filter(|s| s.connected).and_then(|s| s.port)
+    }
+
+    fn connected_puuid(&self) -> Option<String> {
+        self.state.lock().ok().filter(|s| s.connected).and_then(|s| s.puuid.clone())
+    }
+
+    fn reconnect(&mut self, reason: &str) {
+        self.suspend(reason);
+        self.last_attempt = None;
+        self.try_connect(reason);
+    }
+
+    fn check_session(&mut self) {
+        if self.last_session_check.is_some_and(|t| t.elapsed() < SESSION_CHECK) {
+            return;
+        }
+        self.last_session_check = Some(Instant::now());
+        let Some(current) = self.connected_puuid() else { return };
+        if session_puuid(&self.state).is_some_and(|p| p != current) {
+            log_info("[Lockfile] Riot Client switched accounts, reconnecting");
+            // Another account's identity and XMPP session must not carry over.
+            if self.xmpp.lock().map(|x| x.connected).unwrap_or(false) {
+                let _ = xmpp_disconnect(&self.xmpp);
+            }
+            self.restore_xmpp = false;
+            disconnect(&self.state);
+            self.emit(SessionState::Disconnected, "account switched");
+            self.last_attempt = None;
+            self.try_connect("account switched");
+        }
+    }
+
+    // Refreshes the tokens once they are within the refresh margin of expiring. The
+    // watch loop sleeps no longer than `refresh_due_in`, so this runs on time.
+    fn refresh_if_due(&mut self) {
+        if !refresh_due_in(&self.state).is_some_and(|d| d.is_zero()) {
+            return;
+        }
+        log_info("[Lockfile] Tokens close to expiry, refreshing");
+        if let Err(e) = (self.hooks.refresh)(&self.state) {
+            log_error(&format!("[Lockfile] Scheduled token refresh failed: {}, reconnecting", e));
+            self.reconnect("token refresh failed");
+        }
+    }
+
+    fn try_connect(&mut self, reason: &str) {
+        if self.last_attempt.is_some_and(|t| t.elapsed() < CONNECT_RETRY) {
+            return;
+        }
+        if !(self.hooks.game_running)() {
+            return;
+        }
+        self.last_attempt = Some(Instant::now());
+        let resuming = is_reconnecting(&self.state);
+        match (self.hooks.connect)(&self.state) {
+            Ok(_) => {
+                self.last_attempt = None;
+                self.resume_xmpp();
+                self.emit(SessionState::Connected, if resuming { "reconnected" } else { reason });
+            }
+            Err(e) => log_error(&format!("[Lockfile] Auto-connect failed: {}", e)),
+        }
+    }
+
+    fn read_lockfile(&self) -> Option<Lockfile> {
+        let contents = std::fs::read_to_string((self.hooks.lockfile_path)()?).ok()?;
+        parse_lockfile(&contents).ok().filter(|(pid, _, _)| is_pid_alive(*pid))
+    }
+
+    fn check(&mut self) {
+        let current = self.read_lockfile();
+        let changed = current != self.last;
+        let previous = std::mem::replace(&mut self.last, current.clone());
+
+        let Some((pid, port, _)) = current else {
+            if previous.is_some() {
+                log_info("[Lockfile] Lockfile removed, Riot Client closed");
+                self.last_attempt = None;
+                // health_check may have suspended the session already.
+                if self.is_connected() || is_reconnecting(&self.state) {
+                    self.suspend("riot client closed");
+                }
+            }
+            return;
+        };
+
+        if !self.is_connected() {
+            if changed {
+                log_info(&format!("[Lockfile] Lockfile found (port={}, pid={})", port, pid));
+                self.last_attempt = None;
+                if is_reconnecting(&self.state) {
+                    self.emit(SessionState::Reconnecting, "lockfile found");
+                }
+            }
+            self.try_connect("lockfile found");
+            return;
+        }
+
+        // A manual connect can beat the watcher to a restarted client.
+        self.resume_xmpp();
+
+        if changed && previous.is_some() {
+            log_info(&format!("[Lockfile] Lockfile changed (port={}, pid={}), refreshing credentials", port, pid));
+            match (self.hooks.refresh)(&self.state) {
+                Ok(()) => self.emit(SessionState::Connected, "lockfile changed"),
+                Err(e) => {
+                    log_error(&format!("[Lockfile] Refresh after change failed: {}, reconnecting", e));
+                    self.reconnect("lockfile changed");
+                }
+            }
+        } else if self.connected_port() != Some(port) {
+            log_info("[Lockfile] Connected on a stale port, refreshing credentials");
+            match (self.hooks.refresh)(&self.state) {
+                Ok(()) => self.emit(SessionState::Connected, "port changed"),
+                Err(_) => self.reconnect("port changed"),
+            }
+        } else {
+            self.check_session();
+            if self.is_connected() {
+                self.refresh_if_due();
+            }
+        }
+    }
+}
+
+pub fn spawn(app: AppHandle, state: Arc<Mutex<ConnectionState>>, xmpp: Arc<Mutex<XmppState>>) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(move || {
+        let (tx, rx) = channel();
+        let mut fs_watcher: Option<RecommendedWatcher> = match notify::recommended_watcher(tx) {
+            Ok(w) => Some(w),
+            Err(e) => {
+                log_error(&format!("[Lockfile] File watcher unavailable, polling only: {}", e));
+                None
+            }
+        };
+        let mut watched: Option<PathBuf> = None;
+        let mut lock = LockWatch {
+            hooks: Hooks::live(app),
+            state,
+            xmpp,
+            restore_xmpp: false,
+            last: None,
+            last_attempt: None,
+            last_session_check: None,
+        };
+
+        loop {
+            let dir = (lock.hooks.lockfile_path)()
+                .and_then(|p| p.parent().map(|d| d.to_path_buf()))
+                .filter(|d| d.is_dir());
+            if dir != watched {
+                if let Some(w) = fs_watcher.as_mut() {
+                    if let Some(old) = &watched {
+                        let _ = w.unwatch(old);
+                    }
+                    if let Some(new) = &dir {
+                        if let Err(e) = w.watch(new, RecursiveMode::NonRecursive) {
+                            log_error(&format!("[Lockfile] watch {}: {}", new.display(), e));
+                        }
+                    }
+                }
+                watched = dir;
+            }
+
+            lock.check();
+
+            let wait = refresh_due_in(&lock.state).map_or(RECHECK_INTERVAL, |d| d.min(RECHECK_INTERVAL));
+            match rx.recv_timeout(wait) {
+                Ok(_) => while rx.try_recv().is_ok() {},
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => std::thread::sleep(RECHECK_INTERVAL),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    fn record(log: &Log, entry: String) {
+        log.lock().unwrap().push(entry);
+    }
+
+    // Stands in for connection.rs: connecting takes the port from the lockfile, refreshing
+    // fails when `refresh_ok` is cleared, and every call and emitted change is logged.
+    fn watch(path: PathBuf, log: &Log, refresh_ok: &Arc<AtomicBool>) -> LockWatch {
+        let port_of = |path: &PathBuf| {
+            std::fs::read_to_string(path).ok().and_then(|c| parse_lockfile(&c).ok()).map(|(_, port, _)| port)
+        };
+        let (connect_log, refresh_log, suspend_log, emit_log) = (log.clone(), log.clone(), log.clone(), log.clone());
+        let (connect_path, refresh_path, lockfile) = (path.clone(), path.clone(), path);
+        let refresh_ok = refresh_ok.clone();
+        LockWatch {
+            hooks: Hooks {
+                lockfile_path: Box::new(move || Some(lockfile.clone())),
+                game_running: Box::new(|| true),
+                connect: Box::new(move |state| {
+                    let mut s = state.lock().unwrap();
+                    s.connected = true;
+                    s.reconnecting = false;
+                    s.port = port_of(&connect_path);
+                    s.token_fetched_at = Some(Instant::now());
+                    record(&connect_log, format!("connect {:?}", s.port));
+                    Ok(())
+                }),
+                refresh: Box::new(move |state| {
+                    if !refresh_ok.load(Ordering::SeqCst) {
+                        record(&refresh_log, "refresh failed".to_string());
+                        return Err(RiotError::Local("token fetch failed".to_string()));
+                    }
+                    let mut s = state.lock().unwrap();
+                    s.port = port_of(&refresh_path);
+                    s.token_fetched_at = Some(Instant::now());
+                    record(&refresh_log, format!("refresh {:?}", s.port));
+                    Ok(())
+                }),
+                suspend: Box::new(move |state| {
+                    let mut s = state.lock().unwrap();
+                    s.connected = false;
+                    s.reconnecting = true;
+                    s.port = None;
+                    record(&suspend_log, "suspend".to_string());
+                }),
+                emit: Box::new(move |change| {
+                    record(&emit_log, format!("{:?} {}", change.state, change.reason));
+                }),
+            },
+            state: Arc::new(Mutex::new(ConnectionState::default())),
+            xmpp: Arc::new(Mutex::new(XmppState::default())),
+            restore_xmpp: false,
+            last: None,
+            last_attempt: None,
+            last_session_check: None,
+        }
+    }
+
+    fn write_lockfile(path: &PathBuf, pid: u32, port: u16) {
+        std::fs::write(path, format!("Riot Client:{}:{}:sanitized:https", pid, port)).unwrap();
+    }
+
+    fn drain(log: &Log) -> Vec<String> {
+        std::mem::take(&mut *log.lock().unwrap())
+    }
+
+    #[test]
+    fn follows_lockfile_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lockfile");
+        let log: Log = Arc::default();
+        let refresh_ok = Arc::new(AtomicBool::new(true));
+        let mut lock = watch(path.clone(), &log, &refresh_ok);
+        let pid = std::process::id();
+
+        lock.check();
+        assert!(drain(&log).is_empty(), "no lockfile, nothing to do");
+
+        write_lockfile(&path, pid, 50001);
+        lock.check();
+        assert_eq!(drain(&log), ["connect Some(50001)", "Connected lockfile found"]);
+
+        lock.check();
+        assert!(drain(&log).is_empty(), "an unchanged lockfile keeps the session");
+
+        write_lockfile(&path, pid, 50002);
+        lock.check();
+        assert_eq!(drain(&log), ["refresh Some(50002)", "Connected lockfile changed"]);
+
+        refresh_ok.store(false, Ordering::SeqCst);
+        write_lockfile(&path, pid, 50003);
+        lock.check();
+        assert_eq!(drain(&log), [
+            "refresh failed",
+            "suspend",
+            "Reconnecting lockfile changed",
+            "connect Some(50003)",
+            "Connected reconnected",
+        ]);
+
+        std::fs::remove_file(&path).unwrap();
+        lock.check();
+        assert_eq!(drain(&log), ["suspend", "Reconnecting riot client closed"]);
+        assert!(is_reconnecting(&lock.state));
+
+        write_lockfile(&path, pid, 50004);
+        lock.check();
+        assert_eq!(drain(&log), ["Reconnecting lockfile found", "connect Some(50004)", "Connected reconnected"]);
+    }
+
+    #[test]
+    fn refreshes_tokens_when_due() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lockfile");
+        let log: Log = Arc::default();
+        let refresh_ok = Arc::new(AtomicBool::new(true));
+        let mut lock = watch(path.clone(), &log, &refresh_ok);
+        write_lockfile(&path, std::process::id(), 50001);
+        lock.check();
+        drain(&log);
+
+        // The test tokens carry no `exp`, so they are due FALLBACK_TOKEN_LIFETIME_SECS after the fetch.
+        let due = refresh_due_in(&lock.state).unwrap();
+        assert!(due > Duration::from_secs(500) && due <= Duration::from_secs(600));
+        lock.check();
+        assert!(drain(&log).is_empty());
+
+        let stale = Instant::now().checked_sub(Duration::from_secs(601)).unwrap();
+        lock.state.lock().unwrap().token_fetched_at = Some(stale);
+        assert_eq!(refresh_due_in(&lock.state), Some(Duration::ZERO));
+        lock.check();
+        assert_eq!(drain(&log), ["refresh Some(50001)"]);
+        assert!(refresh_due_in(&lock.state).unwrap() > Duration::from_secs(500));
+
+        refresh_ok.store(false, Ordering::SeqCst);
+        lock.state.lock().unwrap().token_fetched_at = Some(stale);
+        lock.check();
+        assert_eq!(drain(&log), [
+            "refresh failed",
+            "suspend",
+            "Reconnecting token refresh failed",
+            "connect Some(50001)",
+            "Connected reconnected",
+        ]);
+    }
+
+    #[test]
+    fn ignores_unreadable_and_stale_lockfiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lockfile");
+        let log: Log = Arc::default();
+        let mut lock = watch(path.clone(), &log, &Arc::new(AtomicBool::new(true)));
+
+        std::fs::write(&path, "Riot Client:garbage").unwrap();
+        lock.check();
+        write_lockfile(&path, u32::MAX - 1, 50001);
+        lock.check();
+        assert!(drain(&log).is_empty());
+        assert!(!lock.is_connected());
+    }
+}
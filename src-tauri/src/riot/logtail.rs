@@ -0,0 +1,246 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use regex::Regex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use super::logging::log_info;
+use super::paths;
+use super::watcher;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+static TAIL: Mutex<Option<LogTail>> = Mutex::new(None);
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LogEvent {
+    RegionShard { region: String, shard: String },
+    ClientVersion { version: String },
+    MapLoaded { map: String },
+    MatchId { match_id: String },
+    Disconnected { reason: String },
+    Crashed { message: String },
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LogSnapshot {
+    pub region: Option<String>,
+    pub shard: Option<String>,
+    pub client_version: Option<String>,
+    pub map: Option<String>,
+    pub match_id: Option<String>,
+}
+
+struct LogTail {
+    path: PathBuf,
+    offset: u64,
+    partial: Vec<u8>,
+    snapshot: LogSnapshot,
+}
+
+struct Patterns {
+    glz: Regex,
+    version: Regex,
+    map: Regex,
+    match_id: Regex,
+    disconnect: Regex,
+    crash: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| Patterns {
+        glz: Regex::new(r"https://glz-(.+?)-1\.(.+?)\.a\.pvp\.net").unwrap(),
+        version: Regex::new(r"CI server version: (\S+)").unwrap(),
+        map: Regex::new(r"LoadMap: (/Game/Maps/[^\s?]+)").unwrap(),
+        match_id: Regex::new(r"/(?:pregame|core-game)/v1/matches/([0-9a-fA-F-]{36})").unwrap(),
+        disconnect: Regex::new(r"(?:NetworkFailure|TravelFailure|ConnectionLost|LostConnection)\S*:?\s*(.*)").unwrap(),
+        crash: Regex::new(r"(?:=== Critical error: ===|Fatal error|Unhandled Exception)(.*)").unwrap(),
+    })
+}
+
+impl LogTail {
+    fn new(path: PathBuf) -> Self {
+        LogTail { path, offset: 0, partial: Vec::new(), snapshot: LogSnapshot::default() }
+    }
+
+    fn read_new(&mut self) -> Vec<String> {
+        let Ok(mut file) = File::open(&self.path) else { return Vec::new() };
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        // The game starts a fresh log on launch, so a shorter file means a new session.
+        if len < self.offset {
+            self.offset = 0;
+            self.partial.clear();
+            self.snapshot = LogSnapshot::default();
+        }
+        if len == self.offset || file.seek(SeekFrom::Start(self.offset)).is_err() {
+            return Vec::new();
+        }
+        let mut buf = Vec::new();
+        let Ok(read) = file.read_to_end(&mut buf) else { return Vec::new() };
+        self.offset += read as u64;
+
+        self.partial.extend_from_slice(&buf);
+        let Some(last_newline) = self.partial.iter().rposition(|&b| b == b'\n') else { return Vec::new() };
+        let rest = self.partial.split_off(last_newline + 1);
+        let complete = std::mem::replace(&mut self.partial, rest);
+        String::from_utf8_lossy(&complete).lines().map(|l| l.to_string()).collect()
+    }
+
+    fn parse_line(&mut self, line: &str, events: &mut Vec<LogEvent>) {
+        let p = patterns();
+        let snap = &mut self.snapshot;
+
+        if let Some(c) = p.glz.captures(line) {
+            let (region, shard) = (c[1].to_string(), c[2].to_string());
+            if snap.region.as_deref() != Some(&region) || snap.shard.as_deref() != Some(&shard) {
+                snap.region = Some(region.clone());
+                snap.shard = Some(shard.clone());
+                events.push(LogEvent::RegionShard { region, shard });
+            }
+        }
+        if let Some(c) = p.version.captures(line) {
+            let version = c[1].to_string();
+            if snap.client_version.as_deref() != Some(&version) {
+                snap.client_version = Some(version.clone());
+                events.push(LogEvent::ClientVersion { version });
+            }
+        }
+        if let Some(c) = p.map.captures(line) {
+            let map = c[1].to_string();
+            if !map.contains("/Menu") && snap.map.as_deref() != Some(&map) {
+                snap.map = Some(map.clone());
+                events.push(LogEvent::MapLoaded { map });
+            }
+        }
+        if let Some(c) = p.match_id.captures(line) {
+            let match_id = c[1].to_lowercase();
+            if snap.match_id.as_deref() != Some(&match_id) {
+                snap.match_id = Some(match_id.clone());
+                events.push(LogEvent::MatchId { match_id });
+            }
+        }
+        if let Some(c) = p.disconnect.captures(line) {
+            events.push(LogEvent::Disconnected { reason: c[1].trim().to_string() });
+        }
+        if let Some(c) = p.crash.captures(line) {
+            events.push(LogEvent::Crashed { message: c[0].trim().to_string() });
+        }
+    }
+}
+
+pub fn poll() -> Result<(LogSnapshot, Vec<LogEvent>), String> {
+    let path = paths::resolve()?.shooter_game_log();
+    let mut guard = TAIL.lock().unwrap_or_else(|e| e.into_inner());
+    if guard.as_ref().map(|t| &t.path) != Some(&path) {
+        *guard = Some(LogTail::new(path));
+    }
+    let tail = guard.as_mut().ok_or("log tail unavailable")?;
+    if !tail.path.is_file() {
+        return Err("Could not read ShooterGame.log. Is Valorant installed?".to_string());
+    }
+
+    let mut events = Vec::new();
+    for line in tail.read_new() {
+        tail.parse_line(&line, &mut events);
+    }
+    Ok((tail.snapshot.clone(), events))
+}
+
+pub fn snapshot() -> Result<LogSnapshot, String> {
+    poll().map(|(snap, _)| snap)
+}
+
+pub fn spawn(app: AppHandle) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(move || loop {
+        if let Ok((_, events)) = poll() {
+            for event in events {
+                match &event {
+                    LogEvent::Disconnected { reason } => {
+                        log_info(&format!("[GameLog] Network failure: {}", reason));
+                        watcher::wake();
+                    }
+                    LogEvent::Crashed { message } => {
+                        log_info(&format!("[GameLog] Game crash: {}", message));
+                        watcher::wake();
+                    }
+                    _ => {}
+                }
+                let _ = app.emit("game-log", &event);
+            }
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../../fixtures/shooter_game.log");
+
+    fn parse(tail: &mut LogTail, text: &str) -> Vec<LogEvent> {
+        let mut events = Vec::new();
+        for line in text.lines() {
+            tail.parse_line(line, &mut events);
+        }
+        events
+    }
+
+    #[test]
+    fn parses_region_and_session_from_log() {
+        let mut tail = LogTail::new(PathBuf::new());
+        let events = parse(&mut tail, FIXTURE);
+        let match_id = "8b1c2f4e-3a6d-4e2b-9f10-5c7d8e9a0b1c".to_string();
+        assert_eq!(events, vec![
+            LogEvent::ClientVersion { version: "release-09.07-shipping-11-2790213".to_string() },
+            LogEvent::RegionShard { region: "eu".to_string(), shard: "eu".to_string() },
+            LogEvent::MatchId { match_id: match_id.clone() },
+            LogEvent::MapLoaded { map: "/Game/Maps/Ascent/Ascent".to_string() },
+        ]);
+        assert_eq!(tail.snapshot.region.as_deref(), Some("eu"));
+        assert_eq!(tail.snapshot.shard.as_deref(), Some("eu"));
+        assert_eq!(tail.snapshot.match_id, Some(match_id));
+    }
+
+    #[test]
+    fn no_region_without_glz_traffic() {
+        let mut tail = LogTail::new(PathBuf::new());
+        let events = parse(&mut tail, "LogInit: Build: ++Ares-Core+release-09.07\n\u{fffd}\u{fffd} garbage https://glz-\nhttps://shared.na.a.pvp.net/v1/config/na\n");
+        assert!(events.is_empty());
+        assert!(tail.snapshot.region.is_none() && tail.snapshot.shard.is_none());
+    }
+
+    #[test]
+    fn holds_partial_lines_and_resets_on_new_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ShooterGame.log");
+        let mut tail = LogTail::new(path.clone());
+
+        std::fs::write(&path, &FIXTURE[..FIXTURE.find("glz-eu-1").unwrap()]).unwrap();
+        let lines = tail.read_new();
+        parse(&mut tail, &lines.join("\n"));
+        assert!(tail.snapshot.region.is_none(), "a cut-off line must not be parsed yet");
+
+        std::fs::write(&path, FIXTURE).unwrap();
+        let lines = tail.read_new();
+        parse(&mut tail, &lines.join("\n"));
+        assert_eq!(tail.snapshot.region.as_deref(), Some("eu"));
+
+        std::fs::write(&path, "[2026.10.17-19.00.00:000][  0]URL [GET https://glz-na-1.na.a.pvp.net/session/v1/sessions]\n").unwrap();
+        let lines = tail.read_new();
+        let events = parse(&mut tail, &lines.join("\n"));
+        assert_eq!(events, vec![LogEvent::RegionShard { region: "na".to_string(), shard: "na".to_string() }]);
+        assert!(tail.snapshot.match_id.is_none());
+    }
+}
@@ -1,13 +1,38 @@
 mod types;
+mod error;
+mod jwt;
 mod http;
+mod query;
+mod retry;
+mod client;
 mod process;
+mod procinfo;
+mod version;
 mod connection;
 mod game;
+pub mod agentselect;
+pub mod cache;
+pub mod content;
+pub mod diagnostics;
+pub mod dodge;
+pub mod loadouts;
+pub mod paths;
+pub mod watcher;
+pub mod lockwatch;
+pub mod logtail;
+pub mod profiles;
+pub mod region;
+pub mod roles;
+pub mod roster;
+pub mod websocket;
+pub mod models;
 pub mod logging;
 pub mod xmpp;
 
 pub use types::{ConnectionState, PlayerInfo};
-pub use http::{henrik_api_get, splooshima_api_post};
+pub use error::RiotError;
+pub use http::{henrik_api_get, splooshima_api_post, github_api_get};
 pub use process::{is_valorant_running, find_valorant_path};
-pub use connection::{connect_and_store, disconnect, health_check, get_status, get_cached_player, get_token_age_secs};
+pub use procinfo::{process_status, ProcessStatus};
+pub use connection::{connect_and_store, disconnect, health_check, get_status, get_cached_player, get_token_age_secs, get_token_info, TokenInfo};
 pub use game::{check_current_game, select_agent, lock_agent, pregame_quit, coregame_quit, get_owned_agents, get_party, get_friends, kick_from_party, invite_to_party, request_to_join_party, generate_party_code, join_party_by_code, get_custom_configs, set_custom_settings, change_queue, start_custom_game_match, enter_queue, leave_queue, set_party_accessibility, disable_party_code, get_player_mmr, resolve_player_names, get_home_stats, get_match_page, check_loadout, get_chat_conversations, get_chat_messages, send_chat_message, get_chat_participants};
@@ -0,0 +1,424 @@
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use super::error::RiotError;
+
+pub fn decode<T: DeserializeOwned>(endpoint: &str, value: &serde_json::Value) -> Result<T, RiotError> {
+    T::deserialize(value).map_err(|e| RiotError::decode(endpoint, e))
+}
+
+pub fn decode_str<T: DeserializeOwned>(endpoint: &str, raw: &str) -> Result<T, RiotError> {
+    serde_json::from_str(raw).map_err(|e| RiotError::decode(endpoint, e))
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PlayerIdentity {
+    pub subject: String,
+    #[serde(rename = "PlayerCardID")]
+    pub player_card_id: String,
+    #[serde(rename = "PlayerTitleID")]
+    pub player_title_id: String,
+    pub account_level: u64,
+    pub incognito: bool,
+    pub hide_account_level: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MatchRef {
+    #[serde(rename = "MatchID")]
+    pub match_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PregameMatch {
+    #[serde(rename = "ID")]
+    pub id: String,
+    #[serde(rename = "MapID")]
+    pub map_id: String,
+    pub mode: String,
+    #[serde(rename = "QueueID")]
+    pub queue_id: String,
+    pub pregame_state: String,
+    pub ally_team: Option<PregameTeam>,
+    #[serde(rename = "PhaseTimeRemainingNS")]
+    pub phase_time_remaining_ns: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PregameTeam {
+    #[serde(rename = "TeamID")]
+    pub team_id: String,
+    pub players: Vec<PregamePlayer>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PregamePlayer {
+    pub subject: String,
+    #[serde(rename = "CharacterID")]
+    pub character_id: String,
+    pub character_selection_state: String,
+    pub competitive_tier: u64,
+    pub player_identity: PlayerIdentity,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CoreGameMatch {
+    #[serde(rename = "MatchID")]
+    pub match_id: String,
+    #[serde(rename = "MapID")]
+    pub map_id: String,
+    #[serde(rename = "ModeID")]
+    pub mode_id: String,
+    pub state: String,
+    pub players: Vec<CoreGamePlayer>,
+    pub matchmaking_data: Option<CoreGameMatchmaking>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CoreGamePlayer {
+    pub subject: String,
+    #[serde(rename = "TeamID")]
+    pub team_id: String,
+    #[serde(rename = "CharacterID")]
+    pub character_id: String,
+    pub player_identity: PlayerIdentity,
+    pub is_coach: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CoreGameMatchmaking {
+    #[serde(rename = "QueueID")]
+    pub queue_id: String,
+    pub is_ranked: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PartyPlayer {
+    #[serde(rename = "CurrentPartyID")]
+    pub current_party_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Party {
+    #[serde(rename = "ID")]
+    pub id: String,
+    #[serde(rename = "MUCName")]
+    pub muc_name: String,
+    pub members: Vec<PartyMember>,
+    pub state: String,
+    pub accessibility: String,
+    #[serde(default)]
+    pub invite_code: Option<String>,
+    pub matchmaking_data: PartyMatchmaking,
+    pub custom_game_data: Option<CustomGameData>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PartyMember {
+    pub subject: String,
+    pub competitive_tier: u64,
+    pub player_identity: PlayerIdentity,
+    #[serde(default)]
+    pub is_owner: bool,
+    pub is_ready: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PartyMatchmaking {
+    #[serde(rename = "QueueID")]
+    pub queue_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CustomGameData {
+    pub settings: CustomGameSettings,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CustomGameSettings {
+    pub map: String,
+    pub mode: String,
+    pub game_pod: String,
+    #[serde(default)]
+    pub game_rules: Option<HashMap<String, String>>,
+}
+
+impl CustomGameSettings {
+    pub fn rule(&self, key: &str, default: bool) -> bool {
+        self.game_rules.as_ref()
+            .and_then(|rules| rules.get(key))
+            .map(|v| v == "true")
+            .unwrap_or(default)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PlayerMmr {
+    pub subject: String,
+    pub queue_skills: HashMap<String, QueueSkill>,
+    pub latest_competitive_update: Option<CompetitiveUpdate>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct QueueSkill {
+    #[serde(rename = "SeasonalInfoBySeasonID", default)]
+    pub seasonal_info_by_season_id: Option<HashMap<String, SeasonalInfo>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SeasonalInfo {
+    #[serde(rename = "SeasonID")]
+    pub season_id: String,
+    pub number_of_wins: u64,
+    pub number_of_wins_with_placements: u64,
+    pub number_of_games: u64,
+    pub competitive_tier: u64,
+    pub ranked_rating: u64,
+    #[serde(default)]
+    pub wins_by_tier: Option<HashMap<String, u64>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CompetitiveUpdate {
+    #[serde(rename = "MatchID")]
+    pub match_id: String,
+    #[serde(rename = "MapID")]
+    pub map_id: String,
+    #[serde(rename = "SeasonID")]
+    pub season_id: String,
+    pub match_start_time: u64,
+    pub tier_after_update: u64,
+    pub tier_before_update: u64,
+    pub ranked_rating_after_update: u64,
+    pub ranked_rating_before_update: u64,
+    pub ranked_rating_earned: i64,
+    pub competitive_movement: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CompetitiveUpdates {
+    pub subject: String,
+    pub matches: Vec<CompetitiveUpdate>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct MatchHistory {
+    pub total: u64,
+    pub history: Vec<MatchHistoryEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct MatchHistoryEntry {
+    #[serde(rename = "MatchID")]
+    pub match_id: String,
+    pub game_start_time: u64,
+    #[serde(rename = "QueueID")]
+    pub queue_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchDetails {
+    pub match_info: MatchInfo,
+    pub players: Vec<MatchPlayer>,
+    #[serde(default)]
+    pub teams: Option<Vec<MatchTeam>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchInfo {
+    pub match_id: String,
+    pub map_id: String,
+    #[serde(rename = "queueID")]
+    pub queue_id: String,
+    pub game_mode: String,
+    pub game_start_millis: u64,
+    #[serde(default)]
+    pub game_length_millis: Option<u64>,
+    pub is_completed: bool,
+    pub is_ranked: bool,
+    pub season_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchPlayer {
+    pub subject: String,
+    pub game_name: String,
+    pub tag_line: String,
+    pub team_id: String,
+    pub party_id: String,
+    pub character_id: String,
+    #[serde(default)]
+    pub stats: Option<MatchPlayerStats>,
+    pub competitive_tier: u64,
+    pub player_card: String,
+    pub account_level: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchPlayerStats {
+    pub score: u64,
+    pub rounds_played: u64,
+    pub kills: u64,
+    pub deaths: u64,
+    pub assists: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchTeam {
+    pub team_id: String,
+    pub won: bool,
+    pub rounds_played: u64,
+    pub rounds_won: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Sanitized captures of live responses; PUUIDs and chat/voice IDs are replaced.
+    macro_rules! fixture {
+        ($name:literal) => {
+            serde_json::from_str::<serde_json::Value>(include_str!(concat!("../../fixtures/", $name))).unwrap()
+        };
+    }
+
+    #[test]
+    fn pregame_match() {
+        let m: PregameMatch = decode("/pregame/v1/matches", &fixture!("pregame_match.json")).unwrap();
+        assert_eq!(m.pregame_state, "character_select_active");
+        assert_eq!(m.queue_id, "competitive");
+        let ally = m.ally_team.unwrap();
+        assert_eq!(ally.team_id, "Blue");
+        assert_eq!(ally.players.len(), 2);
+        assert_eq!(ally.players[0].character_selection_state, "locked");
+        assert_eq!(ally.players[0].player_identity.account_level, 143);
+        assert!(ally.players[1].player_identity.incognito);
+    }
+
+    #[test]
+    fn coregame_match() {
+        let m: CoreGameMatch = decode("/core-game/v1/matches", &fixture!("coregame_match.json")).unwrap();
+        assert_eq!(m.players.len(), 2);
+        assert_eq!(m.players[1].team_id, "Red");
+        assert_eq!(m.matchmaking_data.unwrap().queue_id, "competitive");
+    }
+
+    #[test]
+    fn party() {
+        let p: Party = decode("/parties/v1/parties", &fixture!("party.json")).unwrap();
+        assert_eq!(p.state, "DEFAULT");
+        assert_eq!(p.matchmaking_data.queue_id, "competitive");
+        assert!(p.members[0].is_owner);
+        let settings = p.custom_game_data.unwrap().settings;
+        assert!(!settings.rule("AllowGameModifiers", true));
+        assert!(settings.rule("PlayOutAllRounds", true));
+    }
+
+    #[test]
+    fn player_mmr() {
+        let m: PlayerMmr = decode("/mmr/v1/players", &fixture!("mmr.json")).unwrap();
+        let update = m.latest_competitive_update.unwrap();
+        assert_eq!((update.tier_after_update, update.ranked_rating_after_update), (15, 47));
+        let seasons = m.queue_skills["competitive"].seasonal_info_by_season_id.as_ref().unwrap();
+        let act = &seasons["52ca6698-41c1-e7de-4008-8994d2221209"];
+        assert_eq!((act.number_of_wins_with_placements, act.number_of_games), (23, 41));
+        assert_eq!(act.wins_by_tier.as_ref().unwrap()["16"], 2);
+        assert!(m.queue_skills["deathmatch"].seasonal_info_by_season_id.is_none());
+    }
+
+    #[test]
+    fn competitive_updates() {
+        let u: CompetitiveUpdates = decode("/mmr/v1/players/competitiveupdates", &fixture!("competitive_updates.json")).unwrap();
+        assert_eq!(u.matches.len(), 2);
+        assert_eq!(u.matches[1].competitive_movement, "PROMOTED");
+        assert_eq!(u.matches[1].ranked_rating_earned, 21);
+    }
+
+    #[test]
+    fn match_history() {
+        let h: MatchHistory = decode("/match-history/v1/history", &fixture!("match_history.json")).unwrap();
+        assert_eq!(h.total, 137);
+        assert_eq!(h.history[1].queue_id, "");
+    }
+
+    #[test]
+    fn match_details() {
+        let d: MatchDetails = decode("/match-details/v1/matches", &fixture!("match_details.json")).unwrap();
+        assert_eq!(d.match_info.queue_id, "competitive");
+        assert_eq!(d.match_info.game_length_millis, Some(2150000));
+        assert_eq!(d.players[0].stats.as_ref().unwrap().kills, 22);
+        assert!(d.players[1].stats.is_none());
+        assert_eq!(d.teams.unwrap()[0].rounds_won, 13);
+    }
+
+    // Removes `key` from the object at `parent` (a JSON pointer) and expects the decode to fail.
+    fn decode_without<T: DeserializeOwned + std::fmt::Debug>(mut raw: serde_json::Value, parent: &str, key: &str) {
+        raw.pointer_mut(parent).and_then(|v| v.as_object_mut()).unwrap().remove(key).unwrap();
+        let err = decode::<T>("/test", &raw).unwrap_err();
+        assert!(err.to_string().contains(key), "{} should name {}", err, key);
+    }
+
+    #[test]
+    fn missing_character_id_fails() {
+        decode_without::<PregameMatch>(fixture!("pregame_match.json"), "/AllyTeam/Players/0", "CharacterID");
+        decode_without::<CoreGameMatch>(fixture!("coregame_match.json"), "/Players/1", "CharacterID");
+        decode_without::<MatchDetails>(fixture!("match_details.json"), "/players/0", "characterId");
+    }
+
+    #[test]
+    fn missing_map_id_fails() {
+        decode_without::<PregameMatch>(fixture!("pregame_match.json"), "", "MapID");
+        decode_without::<CoreGameMatch>(fixture!("coregame_match.json"), "", "MapID");
+        decode_without::<CompetitiveUpdates>(fixture!("competitive_updates.json"), "/Matches/0", "MapID");
+    }
+
+    #[test]
+    fn missing_queue_id_fails() {
+        decode_without::<PregameMatch>(fixture!("pregame_match.json"), "", "QueueID");
+        decode_without::<CoreGameMatch>(fixture!("coregame_match.json"), "/MatchmakingData", "QueueID");
+        decode_without::<Party>(fixture!("party.json"), "/MatchmakingData", "QueueID");
+        decode_without::<MatchHistory>(fixture!("match_history.json"), "/History/0", "QueueID");
+        decode_without::<MatchDetails>(fixture!("match_details.json"), "/matchInfo", "queueID");
+    }
+
+    #[test]
+    fn missing_stats_fail() {
+        decode_without::<MatchDetails>(fixture!("match_details.json"), "/players/0/stats", "kills");
+        decode_without::<MatchDetails>(fixture!("match_details.json"), "/players/0/stats", "deaths");
+    }
+
+    #[test]
+    fn missing_required_field_names_the_endpoint() {
+        let mut raw = fixture!("pregame_match.json");
+        raw.as_object_mut().unwrap().remove("ID");
+        let err = decode::<PregameMatch>("/pregame/v1/matches", &raw).unwrap_err();
+        assert!(matches!(err, RiotError::Decode { .. }));
+        assert!(err.to_string().contains("/pregame/v1/matches"));
+    }
+}
@@ -0,0 +1,253 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use super::logging::{log_error, log_info};
+
+pub const BASE_DIR_ENV: &str = "VALORANT_THING_RIOT_DIR";
+
+static CONFIGURED_BASE: OnceLock<Mutex<Option<PathBuf>>> = OnceLock::new();
+static BASE_DIR_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedBaseDir {
+    base_dir: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RiotPaths {
+    pub local_app_data: PathBuf,
+    pub program_data: PathBuf,
+    pub source: String,
+}
+
+fn configured_slot() -> &'static Mutex<Option<PathBuf>> {
+    CONFIGURED_BASE.get_or_init(|| Mutex::new(None))
+}
+
+fn load(path: &Path) -> Option<PathBuf> {
+    let raw = fs::read_to_string(path).ok()?;
+    match serde_json::from_str::<SavedBaseDir>(&raw) {
+        Ok(saved) => Some(saved.base_dir).filter(|d| !d.as_os_str().is_empty()),
+        Err(e) => {
+            log_error(&format!("[Paths] {} is corrupt, ignoring it: {}", path.display(), e));
+            None
+        }
+    }
+}
+
+fn save(dir: Option<&Path>) {
+    let Some(path) = BASE_DIR_PATH.get() else { return };
+    let Some(dir) = dir else {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => log_error(&format!("[Paths] remove {}: {}", path.display(), e)),
+            _ => {}
+        }
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let Ok(body) = serde_json::to_string_pretty(&SavedBaseDir { base_dir: dir.to_path_buf() }) else { return };
+    let tmp = path.with_extension("tmp");
+    if let Err(e) = fs::write(&tmp, body).and_then(|_| fs::rename(&tmp, path)) {
+        log_error(&format!("[Paths] write {}: {}", path.display(), e));
+        let _ = fs::remove_file(&tmp);
+    }
+}
+
+// Loads the saved base directory; call before anything resolves a Riot path.
+pub fn init(path: PathBuf) {
+    let saved = load(&path);
+    if let Some(dir) = &saved {
+        log_info(&format!("[Paths] Loaded Riot base dir {}", dir.display()));
+    }
+    *configured_slot().lock().unwrap_or_else(|e| e.into_inner()) = saved;
+    let _ = BASE_DIR_PATH.set(path);
+}
+
+pub fn set_base_dir(dir: Option<PathBuf>) {
+    let dir = dir.filter(|d| !d.as_os_str().is_empty());
+    save(dir.as_deref());
+    *configured_slot().lock().unwrap_or_else(|e| e.into_inner()) = dir;
+}
+
+pub fn get_base_dir() -> Option<PathBuf> {
+    configured_slot().lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn env_path(key: &str) -> Option<PathBuf> {
+    std::env::var_os(key).filter(|v| !v.is_empty()).map(PathBuf::from)
+}
+
+// A base directory is either a Wine/Proton prefix (has `drive_c`) or the
+// directory that plays the role of %LOCALAPPDATA%.
+fn from_base(base: &Path, source: &str) -> RiotPaths {
+    let drive_c = base.join("drive_c");
+    if drive_c.is_dir() {
+        return RiotPaths {
+            local_app_data: wine_user_dir(&drive_c).join("AppData").join("Local"),
+            program_data: drive_c.join("ProgramData"),
+            source: format!("{} (wine prefix {})", source, base.display()),
+        };
+    }
+    RiotPaths {
+        local_app_data: base.to_path_buf(),
+        program_data: default_program_data().unwrap_or_else(|| base.to_path_buf()),
+        source: format!("{} ({})", source, base.display()),
+    }
+}
+
+fn wine_user_dir(drive_c: &Path) -> PathBuf {
+    let users = drive_c.join("users");
+    let steam = users.join("steamuser");
+    if steam.is_dir() {
+        return steam;
+    }
+    let user = std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_default();
+    users.join(user)
+}
+
+#[cfg(target_os = "windows")]
+fn default_program_data() -> Option<PathBuf> {
+    Some(env_path("ALLUSERSPROFILE").unwrap_or_else(|| PathBuf::from(r"C:\ProgramData")))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn default_program_data() -> Option<PathBuf> {
+    wine_prefix().map(|p| p.join("drive_c").join("ProgramData"))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn wine_prefix() -> Option<PathBuf> {
+    env_path("WINEPREFIX").or_else(|| env_path("HOME").map(|h| h.join(".wine")))
+}
+
+#[cfg(target_os = "windows")]
+fn platform_default() -> Result<RiotPaths, String> {
+    let local = env_path("LOCALAPPDATA").ok_or("LOCALAPPDATA not found")?;
+    Ok(RiotPaths {
+        local_app_data: local,
+        program_data: default_program_data().unwrap_or_default(),
+        source: "LOCALAPPDATA".to_string(),
+    })
+}
+
+#[cfg(not(target_os = "windows"))]
+fn platform_default() -> Result<RiotPaths, String> {
+    let prefix = wine_prefix().ok_or("No WINEPREFIX or HOME to locate the Riot Client")?;
+    Ok(from_base(&prefix, "WINEPREFIX"))
+}
+
+pub fn resolve() -> Result<RiotPaths, String> {
+    let configured = configured_slot().lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(base) = configured {
+        return Ok(from_base(&base, "settings"));
+    }
+    if let Some(base) = env_path(BASE_DIR_ENV) {
+        return Ok(from_base(&base, BASE_DIR_ENV));
+    }
+    platform_default()
+}
+
+impl RiotPaths {
+    pub fn riot_client_config_dir(&self) -> PathBuf {
+        self.local_app_data.join("Riot Games").join("Riot Client").join("Config")
+    }
+
+    pub fn lockfile(&self) -> PathBuf {
+        self.riot_client_config_dir().join("lockfile")
+    }
+
+    pub fn valorant_logs_dir(&self) -> PathBuf {
+        self.local_app_data.join("VALORANT").join("Saved").join("Logs")
+    }
+
+    pub fn shooter_game_log(&self) -> PathBuf {
+        self.valorant_logs_dir().join("ShooterGame.log")
+    }
+
+    pub fn product_settings(&self) -> PathBuf {
+        self.program_data
+            .join("Riot Games")
+            .join("Metadata")
+            .join("valorant.live")
+            .join("valorant.live.product_settings.yaml")
+    }
+
+    // Riot's metadata stores Windows paths (`C:/Riot Games/VALORANT/live`);
+    // under Wine those live in the prefix's `dosdevices`.
+    pub fn host_path(&self, windows_path: &str) -> PathBuf {
+        let path = PathBuf::from(windows_path);
+        if cfg!(target_os = "windows") || path.exists() {
+            return path;
+        }
+        let Some((drive, rest)) = windows_path.split_once(':').filter(|(d, _)| d.len() == 1) else {
+            return path;
+        };
+        let rest = rest.trim_start_matches(['/', '\\']).replace('\\', "/");
+        let Some(drive_c) = self.program_data.parent() else { return path };
+        let mapped = drive_c.parent()
+            .map(|prefix| prefix.join("dosdevices").join(format!("{}:", drive.to_lowercase())))
+            .filter(|d| d.exists());
+        match mapped {
+            Some(root) => root.join(rest),
+            None if drive.eq_ignore_ascii_case("c") => drive_c.join(rest),
+            None => path,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::riot::process::parse_lockfile;
+
+    #[test]
+    fn plain_base_is_local_app_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = from_base(dir.path(), "settings");
+        assert_eq!(paths.local_app_data, dir.path());
+        assert_eq!(paths.lockfile(), dir.path().join("Riot Games/Riot Client/Config/lockfile"));
+    }
+
+    #[test]
+    fn saved_base_dir_loads() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("riot-dir.json");
+        assert_eq!(load(&path), None);
+
+        std::fs::write(&path, r#"{ "base_dir": "/games/riot" }"#).unwrap();
+        assert_eq!(load(&path), Some(PathBuf::from("/games/riot")));
+        std::fs::write(&path, r#"{ "base_dir": "" }"#).unwrap();
+        assert_eq!(load(&path), None);
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(load(&path), None);
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn wine_prefix_layout() {
+        let prefix = tempfile::tempdir().unwrap();
+        let drive_c = prefix.path().join("drive_c");
+        std::fs::create_dir_all(drive_c.join("users/steamuser")).unwrap();
+        std::fs::create_dir_all(prefix.path().join("dosdevices/d:")).unwrap();
+
+        let paths = from_base(prefix.path(), "settings");
+        assert!(paths.source.contains("wine prefix"));
+        let lockfile = paths.lockfile();
+        assert_eq!(lockfile, drive_c.join("users/steamuser/AppData/Local/Riot Games/Riot Client/Config/lockfile"));
+        assert_eq!(paths.product_settings(), drive_c.join("ProgramData/Riot Games/Metadata/valorant.live/valorant.live.product_settings.yaml"));
+
+        std::fs::create_dir_all(lockfile.parent().unwrap()).unwrap();
+        std::fs::write(&lockfile, include_str!("../../fixtures/lockfile_wine")).unwrap();
+        let contents = std::fs::read_to_string(&lockfile).unwrap();
+        assert_eq!(parse_lockfile(&contents).unwrap().1, 61023);
+
+        assert_eq!(paths.host_path("C:/Riot Games/VALORANT/live"), drive_c.join("Riot Games/VALORANT/live"));
+        assert_eq!(paths.host_path(r"D:\Games\VALORANT\live"), prefix.path().join("dosdevices/d:/Games/VALORANT/live"));
+        assert_eq!(paths.host_path("E:/Games"), PathBuf::from("E:/Games"));
+    }
+}
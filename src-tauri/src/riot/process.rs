@@ -1,17 +1,16 @@
-use std::process::Command;
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
 use super::logging::log_info;
+use super::logtail;
+use super::paths;
+use super::procinfo::{is_pid_alive, is_valorant_game_running};
 
 pub fn read_lockfile() -> Result<(u32, u16, String), String> {
-    let local_app_data =
-        std::env::var("LOCALAPPDATA").map_err(|_| "LOCALAPPDATA not found".to_string())?;
-    let path = format!(
-        "{}\\Riot Games\\Riot Client\\Config\\lockfile",
-        local_app_data
-    );
+    let path = paths::resolve()?.lockfile();
     let contents = std::fs::read_to_string(&path)
         .map_err(|_| "Could not read lockfile. Is Riot Client running?".to_string())?;
+    parse_lockfile(&contents)
+}
+
+pub fn parse_lockfile(contents: &str) -> Result<(u32, u16, String), String> {
     let parts: Vec<&str> = contents.trim().split(':').collect();
     if parts.len() < 5 {
         return Err("Invalid lockfile format".to_string());
@@ -22,17 +21,6 @@ pub fn read_lockfile() -> Result<(u32, u16, String), String> {
     Ok((pid, port, password))
 }
 
-pub fn is_pid_alive(pid: u32) -> bool {
-    let mut cmd = Command::new("tasklist");
-    cmd.args(["/FI", &format!("PID eq {}", pid), "/NH"]);
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(0x08000000);
-    match cmd.output() {
-        Ok(o) => String::from_utf8_lossy(&o.stdout).contains(&pid.to_string()),
-        Err(_) => false,
-    }
-}
-
 pub fn is_riot_client_running() -> bool {
     match read_lockfile() {
         Ok((pid, _, _)) => is_pid_alive(pid),
@@ -40,25 +28,18 @@ pub fn is_riot_client_running() -> bool {
     }
 }
 
-fn is_valorant_game_running() -> bool {
-    let mut cmd = Command::new("tasklist");
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(0x08000000);
-    match cmd.output() {
-        Ok(o) => String::from_utf8_lossy(&o.stdout).contains("VALORANT-Win64-Shi"),
-        Err(_) => false,
-    }
-}
-
 pub fn is_valorant_running() -> bool {
     is_riot_client_running() && is_valorant_game_running()
 }
 
 pub fn find_valorant_path() -> Result<String, String> {
-    let programdata = std::env::var("ALLUSERSPROFILE").unwrap_or_else(|_| "C:\\ProgramData".to_string());
-    let settings_path = format!("{}\\Riot Games\\Metadata\\valorant.live\\valorant.live.product_settings.yaml", programdata);
+    let settings_path = paths::resolve()?.product_settings();
     let contents = std::fs::read_to_string(&settings_path)
         .map_err(|_| "Could not read Valorant product settings. Is Valorant installed?".to_string())?;
+    parse_install_path(&contents)
+}
+
+pub fn parse_install_path(contents: &str) -> Result<String, String> {
     for line in contents.lines() {
         let trimmed = line.trim();
         if trimmed.starts_with("product_install_full_path:") {
@@ -72,20 +53,57 @@ pub fn find_valorant_path() -> Result<String, String> {
 }
 
 pub fn parse_region_shard() -> Result<(String, String), String> {
-    let local_app_data =
-        std::env::var("LOCALAPPDATA").map_err(|_| "LOCALAPPDATA not found".to_string())?;
-    let path = format!(
-        "{}\\VALORANT\\Saved\\Logs\\ShooterGame.log",
-        local_app_data
-    );
-    let log = std::fs::read_to_string(&path)
-        .map_err(|_| "Could not read ShooterGame.log. Is Valorant installed?".to_string())?;
-    let re = regex::Regex::new(r"https://glz-(.+?)-1\.(.+?)\.a\.pvp\.net")
-        .map_err(|e| e.to_string())?;
-    let last = re.captures_iter(&log).last()
+    let snap = logtail::snapshot()?;
+    let (region, shard) = snap.region.zip(snap.shard)
         .ok_or("Could not find region/shard in ShooterGame.log")?;
-    let region = last[1].to_string();
-    let shard = last[2].to_string();
     log_info(&format!("[Connect] Parsed region={} shard={} from ShooterGame.log", region, shard));
     Ok((region, shard))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_lockfile() {
+        let (pid, port, password) = parse_lockfile(include_str!("../../fixtures/lockfile_windows")).unwrap();
+        assert_eq!((pid, port), (14872, 52143));
+        assert_eq!(password, "Zm9vYmFyYmF6cXV4c2FuaXRpemVk");
+    }
+
+    #[test]
+    fn wine_lockfile_with_crlf() {
+        let (pid, port, password) = parse_lockfile(include_str!("../../fixtures/lockfile_wine")).unwrap();
+        assert_eq!((pid, port), (32, 61023));
+        assert_eq!(password, "c2FuaXRpemVkLXdpbmUtcGFzcw");
+    }
+
+    #[test]
+    fn malformed_lockfiles() {
+        assert_eq!(parse_lockfile("").unwrap_err(), "Invalid lockfile format");
+        assert_eq!(parse_lockfile("Riot Client:14872:52143").unwrap_err(), "Invalid lockfile format");
+        assert_eq!(parse_lockfile("Riot Client:pid:52143:pw:https").unwrap_err(), "Invalid PID");
+        assert_eq!(parse_lockfile("Riot Client:14872:70000:pw:https").unwrap_err(), "Invalid port");
+        assert_eq!(parse_lockfile("Riot Client:14872::pw:https").unwrap_err(), "Invalid port");
+    }
+
+    #[test]
+    fn windows_install_path() {
+        let path = parse_install_path(include_str!("../../fixtures/product_settings_windows.yaml")).unwrap();
+        assert_eq!(path, "D:/Games/Riot Games/VALORANT/live");
+    }
+
+    #[test]
+    fn wine_install_path() {
+        let path = parse_install_path(include_str!("../../fixtures/product_settings_wine.yaml")).unwrap();
+        assert_eq!(path, "C:/Riot Games/VALORANT/live");
+    }
+
+    #[test]
+    fn malformed_product_settings() {
+        assert!(parse_install_path("").is_err());
+        assert!(parse_install_path("product_install_root: \"C:/Riot Games\"\n").is_err());
+        assert!(parse_install_path("product_install_full_path: \"\"\n").is_err());
+        assert_eq!(parse_install_path("product_install_full_path: C:/Riot Games/VALORANT/live\r\n").unwrap(), "C:/Riot Games/VALORANT/live");
+    }
+}
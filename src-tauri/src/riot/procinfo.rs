@@ -0,0 +1,84 @@
+use std::sync::{Mutex, OnceLock};
+
+use serde::Serialize;
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessesToUpdate, System};
+
+use super::process::read_lockfile;
+
+// Prefixes, since Linux/Wine truncates process names to 15 characters.
+const RIOT_CLIENT_NAMES: &[&str] = &["riotclientservi", "riot client", "riotclientux"];
+const VALORANT_NAMES: &[&str] = &["valorant-win64-"];
+const ANTI_CHEAT_NAMES: &[&str] = &["vgc"];
+
+static SYSTEM: OnceLock<Mutex<System>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    pub start_time: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessStatus {
+    pub riot_client: Option<ProcessInfo>,
+    pub valorant: Option<ProcessInfo>,
+    pub anti_cheat: Option<ProcessInfo>,
+    pub lockfile_pid: Option<u32>,
+}
+
+fn with_system<T>(refresh: ProcessesToUpdate<'_>, f: impl FnOnce(&System) -> T) -> T {
+    let mut sys = SYSTEM.get_or_init(|| Mutex::new(System::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    sys.refresh_processes_specifics(refresh, true, ProcessRefreshKind::nothing());
+    f(&sys)
+}
+
+fn base_name(process: &Process) -> String {
+    let name = process.name().to_string_lossy().to_lowercase();
+    name.strip_suffix(".exe").map(|s| s.to_string()).unwrap_or(name)
+}
+
+fn info(process: &Process) -> ProcessInfo {
+    ProcessInfo {
+        pid: process.pid().as_u32(),
+        name: process.name().to_string_lossy().to_string(),
+        start_time: process.start_time(),
+    }
+}
+
+fn find(sys: &System, names: &[&str]) -> Option<ProcessInfo> {
+    sys.processes().values()
+        .filter(|p| {
+            let name = base_name(p);
+            names.iter().any(|n| name.starts_with(n))
+        })
+        .min_by_key(|p| p.start_time())
+        .map(info)
+}
+
+pub fn is_pid_alive(pid: u32) -> bool {
+    let pid = Pid::from_u32(pid);
+    with_system(ProcessesToUpdate::Some(&[pid]), |sys| sys.process(pid).is_some())
+}
+
+pub fn is_valorant_game_running() -> bool {
+    with_system(ProcessesToUpdate::All, |sys| find(sys, VALORANT_NAMES).is_some())
+}
+
+pub fn process_status() -> ProcessStatus {
+    let lockfile_pid = read_lockfile().ok().map(|(pid, _, _)| pid);
+    with_system(ProcessesToUpdate::All, |sys| {
+        let riot_client = lockfile_pid
+            .and_then(|pid| sys.process(Pid::from_u32(pid)))
+            .map(info)
+            .or_else(|| find(sys, RIOT_CLIENT_NAMES));
+        ProcessStatus {
+            riot_client,
+            valorant: find(sys, VALORANT_NAMES),
+            anti_cheat: find(sys, ANTI_CHEAT_NAMES),
+            lockfile_pid,
+        }
+    })
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::logging::{log_error, log_info};
+use super::types::PlayerInfo;
+
+static PROFILES_PATH: OnceLock<PathBuf> = OnceLock::new();
+static REGISTRY: Mutex<Option<Registry>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankSnapshot {
+    pub tier: u64,
+    pub rr: u64,
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub puuid: String,
+    pub game_name: String,
+    pub game_tag: String,
+    pub region: String,
+    pub shard: String,
+    pub player_card_url: Option<String>,
+    pub last_rank: Option<RankSnapshot>,
+    #[serde(default)]
+    pub home_stats: Option<serde_json::Value>,
+    pub last_seen: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Registry {
+    pub active: Option<String>,
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn load() -> Registry {
+    let Some(path) = PROFILES_PATH.get() else { return Registry::default() };
+    let Ok(raw) = fs::read_to_string(path) else { return Registry::default() };
+    serde_json::from_str(&raw).unwrap_or_else(|e| {
+        log_error(&format!("[Profiles] {} is corrupt, starting fresh: {}", path.display(), e));
+        Registry::default()
+    })
+}
+
+fn save(registry: &Registry) {
+    let Some(path) = PROFILES_PATH.get() else { return };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let Ok(body) = serde_json::to_string_pretty(registry) else { return };
+    let tmp = path.with_extension("tmp");
+    if let Err(e) = fs::write(&tmp, body).and_then(|_| fs::rename(&tmp, path)) {
+        log_error(&format!("[Profiles] write {}: {}", path.display(), e));
+        let _ = fs::remove_file(&tmp);
+    }
+}
+
+fn with_registry<T>(f: impl FnOnce(&mut Registry) -> T) -> T {
+    let mut guard = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    f(guard.get_or_insert_with(load))
+}
+
+pub fn init(path: PathBuf) {
+    let _ = PROFILES_PATH.set(path);
+}
+
+// Called on every successful connect; switching accounts in the Riot Client
+// lands here with a different PUUID and the previous profile stays on disk.
+pub fn activate(info: &PlayerInfo) {
+    with_registry(|reg| {
+        if reg.active.as_deref() != Some(info.puuid.as_str()) {
+            log_info(&format!("[Profiles] Active account: {}#{}", info.game_name, info.game_tag));
+        }
+        let profile = reg.profiles.entry(info.puuid.clone()).or_insert_with(|| Profile {
+            puuid: info.puuid.clone(),
+            game_name: String::new(),
+            game_tag: String::new(),
+            region: String::new(),
+            shard: String::new(),
+            player_card_url: None,
+            last_rank: None,
+            home_stats: None,
+            last_seen: 0,
+        });
+        profile.game_name = info.game_name.clone();
+        profile.game_tag = info.game_tag.clone();
+        profile.region = info.region.clone();
+        profile.shard = info.shard.clone();
+        if info.player_card_url.is_some() {
+            profile.player_card_url = info.player_card_url.clone();
+        }
+        profile.last_seen = now_secs();
+        reg.active = Some(info.puuid.clone());
+        save(reg);
+    })
+}
+
+pub fn record_home_stats(puuid: &str, stats: &serde_json::Value) {
+    with_registry(|reg| {
+        let Some(profile) = reg.profiles.get_mut(puuid) else { return };
+        let now = now_secs();
+        profile.last_rank = Some(RankSnapshot {
+            tier: stats["currentTier"].as_u64().unwrap_or(0),
+            rr: stats["currentRR"].as_u64().unwrap_or(0),
+            updated_at: now,
+        });
+        profile.home_stats = Some(stats.clone());
+        profile.last_seen = now;
+        save(reg);
+    })
+}
+
+pub fn active() -> Option<Profile> {
+    with_registry(|reg| reg.active.as_ref().and_then(|p| reg.profiles.get(p)).cloned())
+}
+
+pub fn get(puuid: &str) -> Option<Profile> {
+    with_registry(|reg| reg.profiles.get(puuid).cloned())
+}
+
+pub fn list() -> Registry {
+    with_registry(|reg| reg.clone())
+}
+
+pub fn remove(puuid: &str) -> Result<(), String> {
+    with_registry(|reg| {
+        if reg.active.as_deref() == Some(puuid) {
+            return Err("Cannot remove the active account".to_string());
+        }
+        reg.profiles.remove(puuid).ok_or("Unknown account")?;
+        save(reg);
+        Ok(())
+    })
+}
@@ -0,0 +1,86 @@
+use std::fmt;
+
+use url::form_urlencoded::byte_serialize;
+
+#[derive(Clone)]
+pub struct Query {
+    path: String,
+    pairs: Vec<(String, String)>,
+}
+
+impl Query {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into(), pairs: Vec::new() }
+    }
+
+    pub fn param(mut self, key: &str, value: impl ToString) -> Self {
+        self.pairs.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn param_opt<T: ToString>(self, key: &str, value: Option<T>) -> Self {
+        match value {
+            Some(v) => self.param(key, v),
+            None => self,
+        }
+    }
+
+    pub fn build(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path)?;
+        let mut sep = if self.path.contains('?') { '&' } else { '?' };
+        for (key, value) in &self.pairs {
+            let k: String = byte_serialize(key.as_bytes()).collect();
+            let v: String = byte_serialize(value.as_bytes()).collect();
+            write!(f, "{}{}={}", sep, k, v)?;
+            sep = '&';
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn params_are_appended_in_order() {
+        assert_eq!(Query::new("/match-history/v1/history/abc").build(), "/match-history/v1/history/abc");
+        let q = Query::new("/match-history/v1/history/abc")
+            .param("startIndex", 0)
+            .param("endIndex", 20)
+            .param("queue", "competitive");
+        assert_eq!(q.build(), "/match-history/v1/history/abc?startIndex=0&endIndex=20&queue=competitive");
+        assert_eq!(q.to_string(), q.build());
+    }
+
+    #[test]
+    fn existing_query_strings_are_extended() {
+        let q = Query::new("/mmr/v1/players/abc/competitiveupdates?queue=competitive").param("startIndex", 0);
+        assert_eq!(q.build(), "/mmr/v1/players/abc/competitiveupdates?queue=competitive&startIndex=0");
+    }
+
+    #[test]
+    fn optional_params_are_skipped_when_missing() {
+        let q = Query::new("/history")
+            .param_opt("queue", None::<&str>)
+            .param_opt("startIndex", Some(5))
+            .param_opt("endIndex", None::<u32>);
+        assert_eq!(q.build(), "/history?startIndex=5");
+    }
+
+    #[test]
+    fn keys_and_values_are_form_encoded() {
+        let q = Query::new("/search")
+            .param("name", "Tenz #NA1")
+            .param("a&b=c", "x/y?z")
+            .param("emoji", "gg 👍")
+            .param("safe", "A-z_0.9*");
+        assert_eq!(q.build(), "/search?name=Tenz+%23NA1&a%26b%3Dc=x%2Fy%3Fz&emoji=gg+%F0%9F%91%8D&safe=A-z_0.9*");
+    }
+}
@@ -0,0 +1,232 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use super::http::local_get;
+use super::logging::{log_error, log_info};
+use super::process::parse_region_shard;
+use super::xmpp::{decode_affinity, fetch_pas_token};
+
+// GLZ region → PD shard. LATAM and BR play on the NA shard.
+const REGION_SHARDS: &[(&str, &str)] = &[
+    ("na", "na"),
+    ("latam", "na"),
+    ("br", "na"),
+    ("eu", "eu"),
+    ("ap", "ap"),
+    ("kr", "kr"),
+    ("pbe", "pbe"),
+];
+
+static OVERRIDE_PATH: OnceLock<PathBuf> = OnceLock::new();
+static OVERRIDE: Mutex<Option<RegionShard>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegionShard {
+    pub region: String,
+    pub shard: String,
+    pub source: String,
+}
+
+fn shard_for(region: &str) -> Option<&'static str> {
+    REGION_SHARDS.iter().find(|(r, _)| *r == region).map(|(_, s)| *s)
+}
+
+fn known(region: &str, shard: &str, source: &str) -> RegionShard {
+    RegionShard { region: region.to_string(), shard: shard.to_string(), source: source.to_string() }
+}
+
+pub fn validate(region: &str, shard: Option<&str>) -> Result<RegionShard, String> {
+    let region = region.trim().to_lowercase();
+    let expected = shard_for(&region).ok_or_else(|| {
+        let names: Vec<&str> = REGION_SHARDS.iter().map(|(r, _)| *r).collect();
+        format!("Unknown region '{}' (expected one of {})", region, names.join(", "))
+    })?;
+    match shard.map(|s| s.trim().to_lowercase()).filter(|s| !s.is_empty()) {
+        Some(s) if s != expected => Err(format!("Region '{}' uses shard '{}', not '{}'", region, expected, s)),
+        _ => Ok(known(&region, expected, "override")),
+    }
+}
+
+fn load(path: &Path) -> Option<RegionShard> {
+    let raw = fs::read_to_string(path).ok()?;
+    let saved: RegionShard = match serde_json::from_str(&raw) {
+        Ok(saved) => saved,
+        Err(e) => {
+            log_error(&format!("[Region] {} is corrupt, ignoring the override: {}", path.display(), e));
+            return None;
+        }
+    };
+    validate(&saved.region, Some(&saved.shard))
+        .map_err(|e| log_error(&format!("[Region] Saved override rejected: {}", e)))
+        .ok()
+}
+
+fn save(value: Option<&RegionShard>) {
+    let Some(path) = OVERRIDE_PATH.get() else { return };
+    let Some(value) = value else {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => log_error(&format!("[Region] remove {}: {}", path.display(), e)),
+            _ => {}
+        }
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let Ok(body) = serde_json::to_string_pretty(value) else { return };
+    let tmp = path.with_extension("tmp");
+    if let Err(e) = fs::write(&tmp, body).and_then(|_| fs::rename(&tmp, path)) {
+        log_error(&format!("[Region] write {}: {}", path.display(), e));
+        let _ = fs::remove_file(&tmp);
+    }
+}
+
+// Loads the saved override; call before anything resolves a region.
+pub fn init(path: PathBuf) {
+    let saved = load(&path);
+    if let Some(o) = &saved {
+        log_info(&format!("[Region] Loaded override region={} shard={}", o.region, o.shard));
+    }
+    *OVERRIDE.lock().unwrap_or_else(|e| e.into_inner()) = saved;
+    let _ = OVERRIDE_PATH.set(path);
+}
+
+pub fn set_override(region: Option<&str>, shard: Option<&str>) -> Result<Option<RegionShard>, String> {
+    let value = match region.map(str::trim).filter(|r| !r.is_empty()) {
+        Some(r) => Some(validate(r, shard)?),
+        None => None,
+    };
+    save(value.as_ref());
+    *OVERRIDE.lock().unwrap_or_else(|e| e.into_inner()) = value.clone();
+    Ok(value)
+}
+
+pub fn get_override() -> Option<RegionShard> {
+    OVERRIDE.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+// Maps Riot's region labels ("NA", "EUW", "LA1", "jp1", "eu3", ...) onto GLZ regions.
+fn region_from_label(label: &str) -> Option<&'static str> {
+    let label = label.trim().to_lowercase();
+    let prefixes: &[(&str, &str)] = &[
+        ("pbe", "pbe"),
+        ("la", "latam"),
+        ("br", "br"),
+        ("na", "na"),
+        ("eu", "eu"),
+        ("tr", "eu"),
+        ("ru", "eu"),
+        ("me", "eu"),
+        ("kr", "kr"),
+        ("ap", "ap"),
+        ("jp", "ap"),
+        ("oc", "ap"),
+        ("sg", "ap"),
+        ("ph", "ap"),
+        ("tw", "ap"),
+        ("th", "ap"),
+        ("vn", "ap"),
+    ];
+    prefixes.iter().find(|(p, _)| label.starts_with(p)).map(|(_, r)| *r)
+}
+
+fn from_label(label: &str, source: &str) -> Option<RegionShard> {
+    let region = region_from_label(label)?;
+    Some(known(region, shard_for(region)?, source))
+}
+
+fn from_region_locale(port: u16, auth: &str) -> Option<RegionShard> {
+    let raw = local_get(port, auth, "/riotclient/region-locale").ok()?;
+    let v: serde_json::Value = serde_json::from_str(&raw).ok()?;
+    from_label(v["region"].as_str()?, "region-locale")
+}
+
+fn from_pas_affinity(access_token: &str) -> Option<RegionShard> {
+    let affinity = fetch_pas_token(access_token).and_then(|t| decode_affinity(&t)).ok()?;
+    from_label(&affinity, "pas affinity")
+}
+
+// Order: user override, ShooterGame.log, the Riot Client's region-locale, PAS affinity.
+pub fn resolve(port: u16, auth: &str, access_token: &str) -> Result<RegionShard, String> {
+    if let Some(o) = get_override() {
+        log_info(&format!("[Connect] Using region override region={} shard={}", o.region, o.shard));
+        return Ok(o);
+    }
+    let log_err = match parse_region_shard() {
+        Ok((region, shard)) => return Ok(RegionShard { region, shard, source: "ShooterGame.log".to_string() }),
+        Err(e) => e,
+    };
+    log_error(&format!("[Connect] {}, trying Riot Client region", log_err));
+    let found = from_region_locale(port, auth).or_else(|| from_pas_affinity(access_token));
+    match found {
+        Some(r) => {
+            log_info(&format!("[Connect] region={} shard={} from {}", r.region, r.shard, r.source));
+            Ok(r)
+        }
+        None => Err(format!("{}; region-locale and PAS affinity gave no region either. Set a region override in Settings.", log_err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_map_to_glz_regions() {
+        let cases = [
+            ("NA", Some("na")),
+            ("LA1", Some("latam")),
+            ("LA2", Some("latam")),
+            ("BR1", Some("br")),
+            ("EUW", Some("eu")),
+            ("EUNE", Some("eu")),
+            ("eu3", Some("eu")),
+            ("tr", Some("eu")),
+            ("RU", Some("eu")),
+            ("KR", Some("kr")),
+            ("jp1", Some("ap")),
+            ("OC1", Some("ap")),
+            ("PBE1", Some("pbe")),
+            (" na ", Some("na")),
+            ("xx", None),
+            ("", None),
+        ];
+        for (label, region) in cases {
+            assert_eq!(region_from_label(label), region, "{:?}", label);
+        }
+        assert_eq!(from_label("LA1", "test"), Some(known("latam", "na", "test")));
+        assert_eq!(from_label("EUW", "test"), Some(known("eu", "eu", "test")));
+    }
+
+    #[test]
+    fn validate_checks_the_shard() {
+        assert_eq!(validate(" BR ", None), Ok(known("br", "na", "override")));
+        assert_eq!(validate("latam", Some(" NA ")), Ok(known("latam", "na", "override")));
+        assert_eq!(validate("eu", Some("")), Ok(known("eu", "eu", "override")));
+        assert_eq!(validate("eu", Some("na")), Err("Region 'eu' uses shard 'eu', not 'na'".to_string()));
+        assert!(validate("latam", Some("latam")).is_err());
+        assert!(validate("euw", None).unwrap_err().starts_with("Unknown region 'euw'"));
+    }
+
+    #[test]
+    fn saved_override_loads_only_when_valid() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("region.json");
+        assert_eq!(load(&path), None);
+
+        fs::write(&path, r#"{ "region": "latam", "shard": "na", "source": "override" }"#).unwrap();
+        assert_eq!(load(&path), Some(known("latam", "na", "override")));
+
+        fs::write(&path, r#"{ "region": "eu", "shard": "na", "source": "override" }"#).unwrap();
+        assert_eq!(load(&path), None);
+        fs::write(&path, r#"{ "region": "euw", "shard": "eu", "source": "override" }"#).unwrap();
+        assert_eq!(load(&path), None);
+        fs::write(&path, r#"{ "region": "na" }"#).unwrap();
+        assert_eq!(load(&path), None);
+        fs::write(&path, "{ not json").unwrap();
+        assert_eq!(load(&path), None);
+    }
+}
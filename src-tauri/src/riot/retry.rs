@@ -0,0 +1,251 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::error::{RiotError, TransportCause};
+
+const MAX_CONCURRENT_PER_HOST: usize = 6;
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub max_retry_after: Duration,
+    pub idempotent: bool,
+}
+
+impl RetryPolicy {
+    pub const NONE: RetryPolicy = RetryPolicy {
+        max_attempts: 1,
+        base_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+        max_retry_after: Duration::ZERO,
+        idempotent: false,
+    };
+
+    pub const READ: RetryPolicy = RetryPolicy {
+        max_attempts: 4,
+        base_delay: Duration::from_millis(300),
+        max_delay: Duration::from_secs(5),
+        max_retry_after: Duration::from_secs(20),
+        idempotent: true,
+    };
+
+    // POST/DELETE to GLZ change game state, so they are only retried when the
+    // server definitely did not act on them: 429s and refused connections.
+    pub const WRITE: RetryPolicy = RetryPolicy {
+        max_attempts: 3,
+        base_delay: Duration::from_millis(250),
+        max_delay: Duration::from_secs(2),
+        max_retry_after: Duration::from_secs(5),
+        idempotent: false,
+    };
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self.base_delay.saturating_mul(1u32 << (attempt - 1).min(16));
+        let capped = exp.min(self.max_delay);
+        let half = capped / 2;
+        half + jitter(half)
+    }
+
+    pub fn delay_for(&self, attempt: u32, status: Option<u16>, retry_after: Option<u64>, err: Option<&RiotError>) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        match (status, err) {
+            (Some(429), _) => match retry_after {
+                Some(secs) if Duration::from_secs(secs) > self.max_retry_after => None,
+                Some(secs) => Some(Duration::from_secs(secs) + jitter(self.base_delay)),
+                None => Some(self.backoff(attempt)),
+            },
+            (Some(s), _) if s >= 500 && self.idempotent => Some(self.backoff(attempt)),
+            (_, Some(RiotError::Transport { cause: TransportCause::Connect, .. })) => Some(self.backoff(attempt)),
+            (_, Some(RiotError::Transport { .. })) if self.idempotent => Some(self.backoff(attempt)),
+            _ => None,
+        }
+    }
+}
+
+// Only the delta-seconds form; an HTTP-date gives None and the request backs off as usual.
+pub fn parse_retry_after(value: &str) -> Option<u64> {
+    value.trim().parse().ok()
+}
+
+fn jitter(max: Duration) -> Duration {
+    let max_ms = max.as_millis() as u64;
+    if max_ms == 0 {
+        return Duration::ZERO;
+    }
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0));
+    Duration::from_millis(hasher.finish() % (max_ms + 1))
+}
+
+struct HostLimiter {
+    active: Mutex<HashMap<String, usize>>,
+    freed: Condvar,
+}
+
+static HOST_LIMITER: OnceLock<HostLimiter> = OnceLock::new();
+
+fn host_limiter() -> &'static HostLimiter {
+    HOST_LIMITER.get_or_init(|| HostLimiter {
+        active: Mutex::new(HashMap::new()),
+        freed: Condvar::new(),
+    })
+}
+
+pub struct HostPermit {
+    host: String,
+}
+
+pub fn acquire_host(host: &str) -> HostPermit {
+    let limiter = host_limiter();
+    let mut active = limiter.active.lock().unwrap_or_else(|e| e.into_inner());
+    while active.get(host).copied().unwrap_or(0) >= MAX_CONCURRENT_PER_HOST {
+        active = limiter.freed.wait(active).unwrap_or_else(|e| e.into_inner());
+    }
+    *active.entry(host.to_string()).or_insert(0) += 1;
+    HostPermit { host: host.to_string() }
+}
+
+impl Drop for HostPermit {
+    fn drop(&mut self) {
+        let limiter = host_limiter();
+        let mut active = limiter.active.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(n) = active.get_mut(&self.host) {
+            *n = n.saturating_sub(1);
+            if *n == 0 {
+                active.remove(&self.host);
+            }
+        }
+        limiter.freed.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn transport(cause: TransportCause) -> RiotError {
+        RiotError::Transport { endpoint: "/test".to_string(), message: "failed".to_string(), cause }
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap_with_half_jitter() {
+        let policy = RetryPolicy::READ;
+        for (attempt, capped) in [(1, 300), (2, 600), (3, 1200), (4, 2400), (5, 4800), (6, 5000), (40, 5000)] {
+            let capped = Duration::from_millis(capped);
+            for _ in 0..50 {
+                let delay = policy.backoff(attempt);
+                assert!(delay >= capped / 2 && delay <= capped, "attempt {}: {:?}", attempt, delay);
+            }
+        }
+    }
+
+    #[test]
+    fn jitter_stays_within_its_bound() {
+        assert_eq!(jitter(Duration::ZERO), Duration::ZERO);
+        for _ in 0..200 {
+            assert!(jitter(Duration::from_millis(100)) <= Duration::from_millis(100));
+        }
+    }
+
+    #[test]
+    fn retry_after_is_delta_seconds() {
+        assert_eq!(parse_retry_after("3"), Some(3));
+        assert_eq!(parse_retry_after(" 120 "), Some(120));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2026 07:28:00 GMT"), None);
+        assert_eq!(parse_retry_after("-1"), None);
+        assert_eq!(parse_retry_after(""), None);
+    }
+
+    #[test]
+    fn rate_limits_wait_for_retry_after() {
+        let policy = RetryPolicy::READ;
+        let delay = policy.delay_for(1, Some(429), Some(3), None).unwrap();
+        assert!(delay >= Duration::from_secs(3) && delay <= Duration::from_secs(3) + policy.base_delay);
+        // Longer than the policy will wait: give up instead.
+        assert_eq!(policy.delay_for(1, Some(429), Some(21), None), None);
+        assert!(policy.delay_for(1, Some(429), None, None).unwrap() <= policy.base_delay);
+        // Writes are retried on 429 too, since the server did not act on them.
+        assert!(RetryPolicy::WRITE.delay_for(1, Some(429), Some(2), None).is_some());
+        assert_eq!(RetryPolicy::WRITE.delay_for(1, Some(429), Some(6), None), None);
+    }
+
+    #[test]
+    fn only_idempotent_requests_retry_server_and_transport_errors() {
+        let (read, write) = (RetryPolicy::READ, RetryPolicy::WRITE);
+        assert!(read.delay_for(1, Some(503), None, None).is_some());
+        assert_eq!(write.delay_for(1, Some(503), None, None), None);
+        assert!(read.delay_for(1, None, None, Some(&transport(TransportCause::Timeout))).is_some());
+        assert_eq!(write.delay_for(1, None, None, Some(&transport(TransportCause::Timeout))), None);
+        assert!(write.delay_for(1, None, None, Some(&transport(TransportCause::Connect))).is_some());
+        assert_eq!(read.delay_for(1, Some(404), None, None), None);
+        assert_eq!(read.delay_for(1, None, None, Some(&RiotError::decode("/test", "bad"))), None);
+    }
+
+    #[test]
+    fn attempts_stop_at_the_limit() {
+        let policy = RetryPolicy::READ;
+        assert!(policy.delay_for(3, Some(503), None, None).is_some());
+        assert_eq!(policy.delay_for(4, Some(503), None, None), None);
+        assert_eq!(RetryPolicy::NONE.delay_for(1, Some(429), Some(1), None), None);
+    }
+
+    #[test]
+    fn host_limiter_caps_requests_per_host() {
+        let host = "limit.test";
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let handles: Vec<_> = (0..MAX_CONCURRENT_PER_HOST * 2)
+            .map(|_| {
+                let (active, peak) = (active.clone(), peak.clone());
+                std::thread::spawn(move || {
+                    let _permit = acquire_host(host);
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(30));
+                    active.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(peak.load(Ordering::SeqCst) <= MAX_CONCURRENT_PER_HOST);
+        assert!(!host_limiter().active.lock().unwrap().contains_key(host));
+    }
+
+    #[test]
+    fn a_full_host_waits_for_a_free_permit() {
+        let mut held: Vec<_> = (0..MAX_CONCURRENT_PER_HOST).map(|_| acquire_host("wait.test")).collect();
+        let acquired = Arc::new(AtomicUsize::new(0));
+        let waiter = {
+            let acquired = acquired.clone();
+            std::thread::spawn(move || {
+                let _permit = acquire_host("wait.test");
+                acquired.store(1, Ordering::SeqCst);
+            })
+        };
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(acquired.load(Ordering::SeqCst), 0, "a seventh request must wait");
+        held.pop();
+        waiter.join().unwrap();
+        assert_eq!(acquired.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn a_full_host_does_not_block_others() {
+        let held: Vec<_> = (0..MAX_CONCURRENT_PER_HOST).map(|_| acquire_host("full.test")).collect();
+        drop(acquire_host("other.test"));
+        drop(held);
+        drop(acquire_host("full.test"));
+    }
+}
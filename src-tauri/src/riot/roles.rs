@@ -0,0 +1,128 @@
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use super::cache::{self, CacheKind};
+use super::error::RiotError;
+use super::http::https_get;
+use super::logging::log_info;
+
+const CACHE_KEY: &str = "agent-roles";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Duelist,
+    Initiator,
+    Controller,
+    Sentinel,
+}
+
+impl Role {
+    pub fn label(self) -> &'static str {
+        match self {
+            Role::Duelist => "duelist",
+            Role::Initiator => "initiator",
+            Role::Controller => "controller",
+            Role::Sentinel => "sentinel",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Role> {
+        let name = name.to_lowercase();
+        [Role::Duelist, Role::Initiator, Role::Controller, Role::Sentinel].into_iter().find(|r| r.label() == name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentRole {
+    pub uuid: String,
+    pub name: String,
+    pub role: Role,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoleTable {
+    pub source: String,
+    pub agents: Vec<AgentRole>,
+}
+
+// Shipped with the app so role goals work offline; `refresh` replaces it with valorant-api.com data.
+const BUNDLED: &[(&str, &str, Role)] = &[
+    ("41fb69c1-4189-7b37-f117-bcaf1e96f1bf", "Astra", Role::Controller),
+    ("5f8d3a7f-467b-97f3-062c-13acf203c006", "Breach", Role::Initiator),
+    ("9f0d8ba9-4140-b941-57d3-a7ad57c6b417", "Brimstone", Role::Controller),
+    ("22697a3d-45bf-8dd7-4fec-84a9e28c69d7", "Chamber", Role::Sentinel),
+    ("1dbf2edd-4729-0984-3115-daa5eed44993", "Clove", Role::Controller),
+    ("117ed9e3-49f3-6512-3ccf-0cada7e3823b", "Cypher", Role::Sentinel),
+    ("cc8b64c8-4b25-4ff9-6e7f-37b4da43d235", "Deadlock", Role::Sentinel),
+    ("dade69b4-4f5a-8528-247b-219e5a1facd6", "Fade", Role::Initiator),
+    ("e370fa57-4757-3604-3648-499e1f642d3f", "Gekko", Role::Initiator),
+    ("95b78ed7-4637-86d9-7e41-71ba8c293152", "Harbor", Role::Controller),
+    ("0e38b510-41a8-5780-5e8f-568b2a4f2d6c", "Iso", Role::Duelist),
+    ("add6443a-41bd-e414-f6ad-e58d267f4e95", "Jett", Role::Duelist),
+    ("601dbbe7-43ce-be57-2a40-4abd24953621", "KAY/O", Role::Initiator),
+    ("1e58de9c-4950-5125-93e9-a0aee9f98746", "Killjoy", Role::Sentinel),
+    ("bb2a4828-46eb-8cd1-e765-15848195d751", "Neon", Role::Duelist),
+    ("8e253930-4c05-31dd-1b6c-968525494517", "Omen", Role::Controller),
+    ("eb93336a-449b-9c1b-0a54-a891f7921d69", "Phoenix", Role::Duelist),
+    ("f94c3b30-42be-e959-889c-5aa313dba261", "Raze", Role::Duelist),
+    ("a3bfb853-43b2-7238-a4f1-ad90e9e46bcc", "Reyna", Role::Duelist),
+    ("569fdd95-4d10-43ab-ca70-79becc718b46", "Sage", Role::Sentinel),
+    ("6f2a04ca-43e0-be17-7f36-b3908627744d", "Skye", Role::Initiator),
+    ("320b2a48-4d9b-a075-30f1-1f93a9b638fa", "Sova", Role::Initiator),
+    ("b444168c-4e35-8076-db47-ef9bf368f384", "Tejo", Role::Initiator),
+    ("707eab51-4836-f488-046a-cda6bf494859", "Viper", Role::Controller),
+    ("efba5359-4016-a1e5-7626-b1ae76895940", "Vyse", Role::Sentinel),
+    ("df1cb487-4902-002e-5c17-d28e83e78588", "Waylay", Role::Duelist),
+    ("7f94d92c-4234-0a36-9646-3a87eb8b5c89", "Yoru", Role::Duelist),
+];
+
+static REFRESHED: Mutex<Option<Vec<AgentRole>>> = Mutex::new(None);
+
+fn bundled() -> Vec<AgentRole> {
+    BUNDLED.iter()
+        .map(|(uuid, name, role)| AgentRole { uuid: uuid.to_string(), name: name.to_string(), role: *role })
+        .collect()
+}
+
+// This session's refresh, then the content cache, then the bundled table.
+pub fn table() -> RoleTable {
+    if let Some(agents) = REFRESHED.lock().unwrap_or_else(|e| e.into_inner()).clone() {
+        return RoleTable { source: "valorant-api".to_string(), agents };
+    }
+    let cached = cache::get_json(CacheKind::Content, CACHE_KEY)
+        .and_then(|v| serde_json::from_value::<Vec<AgentRole>>(v).ok())
+        .filter(|agents| !agents.is_empty());
+    match cached {
+        Some(agents) => RoleTable { source: "cache".to_string(), agents },
+        None => RoleTable { source: "bundled".to_string(), agents: bundled() },
+    }
+}
+
+pub fn role_of(agents: &[AgentRole], uuid: &str) -> Option<Role> {
+    agents.iter().find(|a| a.uuid.eq_ignore_ascii_case(uuid)).map(|a| a.role)
+}
+
+pub fn refresh() -> Result<RoleTable, RiotError> {
+    let body = https_get("https://valorant-api.com/v1/agents?isPlayableCharacter=true")?;
+    let v: serde_json::Value = serde_json::from_str(&body).map_err(|e| format!("parse agents: {}", e))?;
+    let mut agents: Vec<AgentRole> = v["data"].as_array().ok_or("No agent data")?
+        .iter()
+        .filter_map(|a| Some(AgentRole {
+            uuid: a["uuid"].as_str()?.to_lowercase(),
+            name: a["displayName"].as_str()?.to_string(),
+            role: Role::from_name(a["role"]["displayName"].as_str()?)?,
+        }))
+        .collect();
+    if agents.is_empty() {
+        return Err(RiotError::Local("valorant-api.com returned no agents with a role".to_string()));
+    }
+    agents.sort_by(|a, b| a.name.cmp(&b.name));
+    log_info(&format!("[Roles] Refreshed agent roles: {} agents", agents.len()));
+    if let Ok(json) = serde_json::to_string(&agents) {
+        cache::put(CacheKind::Content, CACHE_KEY, &json);
+    }
+    *REFRESHED.lock().unwrap_or_else(|e| e.into_inner()) = Some(agents.clone());
+    Ok(RoleTable { source: "valorant-api".to_string(), agents })
+}
@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use super::client::RiotClient;
+use super::error::RiotError;
+use super::game::{coregame_match, lookup_names, player_mmr, pregame_match, presence_party_ids};
+use super::logging::{log_error, log_info};
+use super::models::{self, CoreGameMatch, PlayerIdentity, PlayerMmr, PregameMatch};
+use super::types::ConnectionState;
+
+// MMR lookups in flight at once, so a ten-player match doesn't hit PD in one burst.
+const MAX_CONCURRENT: usize = 4;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RosterPlayer {
+    pub puuid: String,
+    pub name: Option<String>,
+    pub tag: Option<String>,
+    pub team_id: String,
+    pub character_id: String,
+    pub current_tier: u64,
+    pub current_rr: u64,
+    pub peak_tier: u64,
+    pub peak_season_id: Option<String>,
+    pub account_level: u64,
+    pub act_wins: u64,
+    pub act_games: u64,
+    pub win_rate: Option<f64>,
+    pub incognito: bool,
+    pub hide_account_level: bool,
+    // Shared by players in the same party; `None` for players without a partied teammate in the match.
+    pub party_group: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EnrichedRoster {
+    pub match_id: String,
+    pub phase: String,
+    // The newest competitive update's season among the players, used for the act win rate.
+    pub act_id: Option<String>,
+    pub players: Vec<RosterPlayer>,
+}
+
+pub struct Seat {
+    pub puuid: String,
+    pub team_id: String,
+    pub character_id: String,
+    pub identity: PlayerIdentity,
+}
+
+// Players of the match, from the pregame match while agent select is open and from the
+// core-game match once it has loaded in (pregame answers 404 then). Pregame only lists the
+// player's own team.
+pub fn seats(client: &RiotClient, match_id: &str) -> Result<(&'static str, Vec<Seat>), RiotError> {
+    match pregame_match(client, match_id) {
+        Ok(raw) => Ok(("pregame", pregame_seats(models::decode("/pregame/v1/matches", &raw)?))),
+        Err(RiotError::Http { status: 404, .. }) => {
+            let raw = coregame_match(client, match_id)?;
+            Ok(("ingame", coregame_seats(models::decode("/core-game/v1/matches", &raw)?)))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub fn pregame_seats(pregame: PregameMatch) -> Vec<Seat> {
+    pregame.ally_team.into_iter()
+        .flat_map(|t| {
+            let team_id = t.team_id;
+            t.players.into_iter().map(move |p| Seat {
+                puuid: p.subject,
+                team_id: team_id.clone(),
+                character_id: p.character_id,
+                identity: p.player_identity,
+            })
+        })
+        .collect()
+}
+
+pub fn coregame_seats(core: CoreGameMatch) -> Vec<Seat> {
+    core.players.into_iter()
+        .map(|p| Seat { puuid: p.subject, team_id: p.team_id, character_id: p.character_id, identity: p.player_identity })
+        .collect()
+}
+
+// MMR for each player, fetched MAX_CONCURRENT at a time; players whose lookup fails are left out.
+pub fn fetch_mmrs(client: &RiotClient, puuids: &[String]) -> HashMap<String, PlayerMmr> {
+    let mut mmrs = HashMap::new();
+    for chunk in puuids.chunks(MAX_CONCURRENT) {
+        let results: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = chunk.iter()
+                .map(|puuid| scope.spawn(move || (puuid, player_mmr(client, puuid))))
+                .collect();
+            handles.into_iter().filter_map(|h| h.join().ok()).collect()
+        });
+        for (puuid, result) in results {
+            match result.and_then(|json| models::decode::<PlayerMmr>("/mmr/v1/players", &json)) {
+                Ok(mmr) => { mmrs.insert(puuid.clone(), mmr); }
+                Err(e) => log_error(&format!("[Roster] MMR for {} failed: {}", puuid, e)),
+            }
+        }
+    }
+    mmrs
+}
+
+// Highest tier reached in any act: the act's final tier or the highest tier it has a win at.
+fn peak(mmr: &PlayerMmr) -> Option<(u64, String)> {
+    let seasons = mmr.queue_skills.get("competitive")?.seasonal_info_by_season_id.as_ref()?;
+    seasons.values()
+        .map(|s| {
+            let best_win = s.wins_by_tier.iter().flatten()
+                .filter_map(|(tier, _)| tier.parse::<u64>().ok())
+                .max()
+                .unwrap_or(0);
+            (s.competitive_tier.max(best_win), s.season_id.clone())
+        })
+        .filter(|(tier, _)| *tier > 0)
+        .max_by_key(|(tier, _)| *tier)
+}
+
+// Player IDs that share a party with at least one other player in the match → group number.
+fn party_groups(seats: &[Seat], parties: &HashMap<String, String>) -> HashMap<String, u32> {
+    let mut sizes: HashMap<&str, usize> = HashMap::new();
+    for seat in seats {
+        if let Some(party) = parties.get(&seat.puuid) {
+            *sizes.entry(party.as_str()).or_default() += 1;
+        }
+    }
+    let mut numbers: HashMap<&str, u32> = HashMap::new();
+    let mut groups = HashMap::new();
+    for seat in seats {
+        let Some(party) = parties.get(&seat.puuid) else { continue };
+        if sizes.get(party.as_str()).copied().unwrap_or(0) < 2 {
+            continue;
+        }
+        let next = numbers.len() as u32 + 1;
+        let number = *numbers.entry(party.as_str()).or_insert(next);
+        groups.insert(seat.puuid.clone(), number);
+    }
+    groups
+}
+
+pub fn get_enriched_roster(state: &Mutex<ConnectionState>, match_id: &str) -> Result<EnrichedRoster, RiotError> {
+    let client = RiotClient::from_state(state)?;
+    let (phase, seats) = seats(&client, match_id)?;
+    let puuids: Vec<String> = seats.iter().map(|s| s.puuid.clone()).collect();
+
+    // Riot's name service returns names for incognito players too.
+    let names: HashMap<String, (String, String)> = match lookup_names(&client, &puuids) {
+        Ok(list) => list.into_iter()
+            .filter_map(|n| Some((
+                n["Subject"].as_str()?.to_string(),
+                (n["GameName"].as_str().unwrap_or_default().to_string(), n["TagLine"].as_str().unwrap_or_default().to_string()),
+            )))
+            .collect(),
+        Err(e) => {
+            log_error(&format!("[Roster] Name lookup failed: {}", e));
+            HashMap::new()
+        }
+    };
+    let mmrs = fetch_mmrs(&client, &puuids);
+    let parties = presence_party_ids(state).unwrap_or_else(|e| {
+        log_error(&format!("[Roster] Presences unavailable, no party groups: {}", e));
+        HashMap::new()
+    });
+    let groups = party_groups(&seats, &parties);
+
+    let act_id = mmrs.values()
+        .filter_map(|m| m.latest_competitive_update.as_ref())
+        .filter(|u| !u.season_id.is_empty())
+        .max_by_key(|u| u.match_start_time)
+        .map(|u| u.season_id.clone());
+
+    let players: Vec<RosterPlayer> = seats.into_iter()
+        .map(|seat| {
+            let mmr = mmrs.get(&seat.puuid);
+            let (current_tier, current_rr) = mmr.and_then(|m| m.latest_competitive_update.as_ref())
+                .map(|u| (u.tier_after_update, u.ranked_rating_after_update))
+                .unwrap_or((0, 0));
+            let (peak_tier, peak_season_id) = mmr.and_then(peak)
+                .map(|(tier, season)| (tier, Some(season)))
+                .unwrap_or((0, None));
+            let act = mmr.zip(act_id.as_ref()).and_then(|(m, act)| {
+                m.queue_skills.get("competitive")?.seasonal_info_by_season_id.as_ref()?.get(act)
+            });
+            let act_wins = act.map(|s| s.number_of_wins_with_placements).unwrap_or(0);
+            let act_games = act.map(|s| s.number_of_games).unwrap_or(0);
+            let (name, tag) = match names.get(&seat.puuid) {
+                Some((name, tag)) if !name.is_empty() => (Some(name.clone()), Some(tag.clone())),
+                _ => (None, None),
+            };
+            RosterPlayer {
+                name,
+                tag,
+                team_id: seat.team_id,
+                character_id: seat.character_id.to_lowercase(),
+                current_tier,
+                current_rr,
+                peak_tier,
+                peak_season_id,
+                account_level: seat.identity.account_level,
+                act_wins,
+                act_games,
+                win_rate: (act_games > 0).then(|| act_wins as f64 / act_games as f64),
+                incognito: seat.identity.incognito,
+                hide_account_level: seat.identity.hide_account_level,
+                party_group: groups.get(&seat.puuid).copied(),
+                puuid: seat.puuid,
+            }
+        })
+        .collect();
+
+    log_info(&format!(
+        "[Roster] {} players in {} ({}): {} names, {} ranks, {} in parties",
+        players.len(), match_id, phase,
+        players.iter().filter(|p| p.name.is_some()).count(),
+        mmrs.len(),
+        groups.len()
+    ));
+    Ok(EnrichedRoster {
+        match_id: match_id.to_string(),
+        phase: phase.to_string(),
+        act_id,
+        players,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CURRENT_ACT: &str = "0981a882-4e7d-371a-70c4-c3b4f46c504a";
+    const OLDER_ACT: &str = "52ca6698-41c1-e7de-4008-8994d2221209";
+
+    fn act(season_id: &str, competitive_tier: u64, wins_by_tier: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "SeasonID": season_id,
+            "NumberOfWins": 10, "NumberOfWinsWithPlacements": 10, "NumberOfGames": 20,
+            "CompetitiveTier": competitive_tier, "RankedRating": 50,
+            "WinsByTier": wins_by_tier,
+        })
+    }
+
+    fn mmr(acts: &[serde_json::Value]) -> PlayerMmr {
+        let seasons: serde_json::Map<_, _> = acts.iter().map(|a| (a["SeasonID"].as_str().unwrap().to_string(), a.clone())).collect();
+        serde_json::from_value(serde_json::json!({
+            "Subject": "player",
+            "QueueSkills": { "competitive": { "SeasonalInfoBySeasonID": seasons } },
+            "LatestCompetitiveUpdate": null,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn peak_is_the_best_tier_of_any_act() {
+        // The fixture's final tier is 15, but it has wins at 16.
+        let fixture: PlayerMmr = models::decode_str("/mmr/v1/players", include_str!("../../fixtures/mmr.json")).unwrap();
+        assert_eq!(peak(&fixture), Some((16, OLDER_ACT.to_string())));
+
+        let older_peak = mmr(&[
+            act(CURRENT_ACT, 14, serde_json::json!({ "14": 3 })),
+            act(OLDER_ACT, 20, serde_json::json!({ "19": 2, "21": 1 })),
+        ]);
+        assert_eq!(peak(&older_peak), Some((21, OLDER_ACT.to_string())));
+
+        let final_tier_only = mmr(&[act(CURRENT_ACT, 12, serde_json::Value::Null), act(OLDER_ACT, 9, serde_json::json!({}))]);
+        assert_eq!(peak(&final_tier_only), Some((12, CURRENT_ACT.to_string())));
+
+        assert_eq!(peak(&mmr(&[act(CURRENT_ACT, 0, serde_json::Value::Null)])), None, "unranked");
+        assert_eq!(peak(&mmr(&[])), None);
+    }
+
+    fn seat(puuid: &str) -> Seat {
+        let identity = serde_json::from_value(serde_json::json!({
+            "Subject": puuid, "PlayerCardID": "", "PlayerTitleID": "",
+            "AccountLevel": 1, "Incognito": false, "HideAccountLevel": false,
+        }))
+        .unwrap();
+        Seat { puuid: puuid.to_string(), team_id: "Blue".to_string(), character_id: String::new(), identity }
+    }
+
+    #[test]
+    fn party_groups_number_shared_parties() {
+        let seats: Vec<Seat> = ["a", "b", "c", "d", "e", "f"].into_iter().map(seat).collect();
+        let parties: HashMap<String, String> = [
+            ("a", "party-1"), ("b", "party-2"), ("c", "party-1"), ("d", "party-3"),
+            ("e", "party-2"), ("outside", "party-3"),
+        ]
+        .into_iter()
+        .map(|(p, party)| (p.to_string(), party.to_string()))
+        .collect();
+
+        let groups = party_groups(&seats, &parties);
+        // Numbered in seat order; solo players, those alone in the match and unknown ones get none.
+        assert_eq!(groups.get("a"), Some(&1));
+        assert_eq!(groups.get("c"), Some(&1));
+        assert_eq!(groups.get("b"), Some(&2));
+        assert_eq!(groups.get("e"), Some(&2));
+        assert_eq!(groups.get("d"), None);
+        assert_eq!(groups.get("f"), None);
+        assert_eq!(groups.len(), 4);
+    }
+}
@@ -1,8 +1,11 @@
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
 
+use super::version::VersionSource;
+
 pub struct ConnectionState {
     pub connected: bool,
+    pub reconnecting: bool,
     pub port: Option<u16>,
     pub local_auth: Option<String>,
     pub access_token: Option<String>,
@@ -11,6 +14,7 @@ pub struct ConnectionState {
     pub region: Option<String>,
     pub shard: Option<String>,
     pub client_version: Option<String>,
+    pub client_version_source: Option<VersionSource>,
     pub game_name: Option<String>,
     pub game_tag: Option<String>,
     pub player_card_url: Option<String>,
@@ -22,6 +26,7 @@ impl Default for ConnectionState {
     fn default() -> Self {
         Self {
             connected: false,
+            reconnecting: false,
             port: None,
             local_auth: None,
             access_token: None,
@@ -30,6 +35,7 @@ impl Default for ConnectionState {
             region: None,
             shard: None,
             client_version: None,
+            client_version_source: None,
             game_name: None,
             game_tag: None,
             player_card_url: None,
@@ -47,6 +53,8 @@ pub struct PlayerInfo {
     pub region: String,
     pub shard: String,
     pub client_version: String,
+    #[serde(default)]
+    pub client_version_source: Option<VersionSource>,
     pub player_card_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rso_debug: Option<String>,
@@ -0,0 +1,194 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use super::http::{https_get, local_get};
+use super::logging::log_error;
+use super::logtail;
+use super::paths;
+use super::process::find_valorant_path;
+
+const SHIPPING_EXE: &str = "ShooterGame/Binaries/Win64/VALORANT-Win64-Shipping.exe";
+// Branch, build and changelist follow this marker as NUL-separated UTF-16 strings.
+const EXE_MARKER: &str = "++Ares-Core+";
+const EXE_WINDOW: usize = 512;
+
+static EXE_CACHE: Mutex<Option<(PathBuf, SystemTime, String)>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionSource {
+    GameLog,
+    ProductSession,
+    Executable,
+    Remote,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientVersion {
+    pub version: String,
+    pub source: VersionSource,
+}
+
+fn from_game_log() -> Option<String> {
+    logtail::snapshot().ok()?.client_version
+}
+
+fn from_product_session(port: u16, auth: &str) -> Option<String> {
+    let raw = local_get(port, auth, "/product-session/v1/external-sessions").ok()?;
+    let sessions: serde_json::Value = serde_json::from_str(&raw).ok()?;
+    sessions.as_object()?
+        .values()
+        .filter(|s| s["productId"].as_str() == Some("valorant"))
+        .find_map(|s| s["version"].as_str().filter(|v| v.starts_with("release-")))
+        .map(|v| v.to_string())
+}
+
+fn from_executable() -> Option<String> {
+    let install = find_valorant_path().ok()?;
+    let exe = paths::resolve().ok()?.host_path(&install).join(SHIPPING_EXE);
+    let modified = std::fs::metadata(&exe).and_then(|m| m.modified()).ok()?;
+
+    let mut cache = EXE_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((path, at, version)) = cache.as_ref() {
+        if *path == exe && *at == modified {
+            return Some(version.clone());
+        }
+    }
+    let version = scan_executable(&exe)?;
+    *cache = Some((exe, modified, version.clone()));
+    Some(version)
+}
+
+fn scan_executable(path: &Path) -> Option<String> {
+    let marker: Vec<u8> = EXE_MARKER.encode_utf16().flat_map(|c| c.to_le_bytes()).collect();
+    let mut file = File::open(path).ok()?;
+    let mut chunk = vec![0u8; 1 << 20];
+    let mut buf: Vec<u8> = Vec::new();
+    let mut found: Option<usize> = None;
+
+    loop {
+        let read = file.read(&mut chunk).ok()?;
+        buf.extend_from_slice(&chunk[..read]);
+        if found.is_none() {
+            found = buf.windows(marker.len()).position(|w| w == marker.as_slice());
+            if found.is_none() {
+                // Keep a marker-sized tail so a match split across chunks is still seen.
+                buf.drain(..buf.len().saturating_sub(marker.len()));
+            }
+        }
+        if let Some(pos) = found {
+            let start = pos + marker.len();
+            if read == 0 || buf.len() >= start + EXE_WINDOW {
+                let end = buf.len().min(start + EXE_WINDOW);
+                return parse_version_block(&buf[start..end]);
+            }
+        }
+        if read == 0 {
+            return None;
+        }
+    }
+}
+
+fn parse_version_block(bytes: &[u8]) -> Option<String> {
+    let units: Vec<u16> = bytes.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]])).collect();
+    let text = String::from_utf16_lossy(&units);
+    let parts: Vec<&str> = text.split('\0').map(str::trim).filter(|p| !p.is_empty()).collect();
+    let branch = parts.first().filter(|b| b.starts_with("release-"))?;
+    let mut numbers = parts.iter().skip(1).filter(|p| p.chars().all(|c| c.is_ascii_digit()));
+    let build = numbers.next()?;
+    let changelist = numbers.next()?;
+    Some(format!("{}-shipping-{}-{}", branch, build, changelist))
+}
+
+fn from_remote() -> Option<String> {
+    let body = https_get("https://valorant-api.com/v1/version").ok()?;
+    let v: serde_json::Value = serde_json::from_str(body.trim().trim_end_matches('\0')).ok()?;
+    v["data"]["riotClientVersion"].as_str().map(|s| s.to_string())
+}
+
+pub fn resolve(port: u16, auth: &str) -> Option<ClientVersion> {
+    let found = from_game_log().map(|v| (v, VersionSource::GameLog))
+        .or_else(|| from_product_session(port, auth).map(|v| (v, VersionSource::ProductSession)))
+        .or_else(|| from_executable().map(|v| (v, VersionSource::Executable)))
+        .or_else(|| from_remote().map(|v| (v, VersionSource::Remote)));
+    match found {
+        Some((version, source)) => Some(ClientVersion { version, source }),
+        None => {
+            log_error("[Connect] Client version not found in ShooterGame.log, product session, executable or valorant-api.com");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLOCK: &[&str] = &["release-09.07", "", "11", "", "2790213", "", "ShooterGame", ""];
+
+    fn utf16(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(|c| c.to_le_bytes()).collect()
+    }
+
+    // NUL-separated, as the strings follow the marker in the executable.
+    fn block(parts: &[&str]) -> Vec<u8> {
+        utf16(&parts.join("\0"))
+    }
+
+    // An executable of `len` filler bytes with the marker and version block written at `at`.
+    fn executable(dir: &Path, len: usize, at: usize) -> PathBuf {
+        let mut bytes = vec![0xAAu8; len];
+        let marked = [utf16(EXE_MARKER), block(BLOCK)].concat();
+        bytes.splice(at..(at + marked.len()).min(len), marked);
+        let path = dir.join("VALORANT-Win64-Shipping.exe");
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn block_matches_the_game_log_version() {
+        let line = include_str!("../../fixtures/shooter_game.log").lines()
+            .find(|l| l.contains("CI server version: "))
+            .unwrap();
+        let logged = line.split("CI server version: ").nth(1).unwrap().trim();
+        assert_eq!(parse_version_block(&block(BLOCK)).as_deref(), Some(logged));
+    }
+
+    #[test]
+    fn block_needs_a_release_branch_and_two_numbers() {
+        assert_eq!(parse_version_block(&block(&["release-09.07", "Shipping", "11", "2790213"])).as_deref(), Some("release-09.07-shipping-11-2790213"));
+        assert_eq!(parse_version_block(&block(&["main-09.07", "", "11", "", "2790213"])), None);
+        assert_eq!(parse_version_block(&block(&["release-09.07", "", "11", ""])), None);
+        assert_eq!(parse_version_block(&[]), None);
+    }
+
+    #[test]
+    fn scan_finds_a_marker_split_across_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let chunk = 1 << 20;
+        // Odd offsets too: the marker is UTF-16 but needn't be aligned in the file.
+        for at in [chunk - 7, chunk - 24, chunk - 1, chunk, 100] {
+            let path = executable(dir.path(), 3 * chunk, at);
+            assert_eq!(scan_executable(&path).as_deref(), Some("release-09.07-shipping-11-2790213"), "marker at {}", at);
+        }
+    }
+
+    #[test]
+    fn scan_reads_a_block_cut_short_by_the_end_of_file() {
+        let dir = tempfile::tempdir().unwrap();
+        // The block ends the file, well inside the window read after the marker.
+        let len = (1 << 20) + 100;
+        let path = executable(dir.path(), len, len - utf16(EXE_MARKER).len() - block(BLOCK).len());
+        assert_eq!(scan_executable(&path).as_deref(), Some("release-09.07-shipping-11-2790213"));
+
+        let empty = dir.path().join("empty.exe");
+        std::fs::write(&empty, vec![0xAAu8; 2 << 20]).unwrap();
+        assert_eq!(scan_executable(&empty), None);
+        assert_eq!(scan_executable(&dir.path().join("missing.exe")), None);
+    }
+}
@@ -0,0 +1,280 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use super::agentselect;
+use super::client::RiotClient;
+use super::dodge;
+use super::game::{coregame_match, current_coregame, current_pregame, pregame_match, session_loop_state};
+use super::logging::{log_error, log_info};
+use super::types::ConnectionState;
+
+const POLL_ACTIVE: Duration = Duration::from_millis(1500);
+const POLL_IDLE: Duration = Duration::from_secs(3);
+const POLL_WITH_EVENTS: Duration = Duration::from_secs(15);
+const INGAME_REFRESH: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GamePhase {
+    Offline,
+    Menus,
+    Pregame,
+    Ingame,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseSnapshot {
+    pub phase: GamePhase,
+    pub match_id: Option<String>,
+    pub payload: Option<serde_json::Value>,
+}
+
+impl Default for PhaseSnapshot {
+    fn default() -> Self {
+        PhaseSnapshot { phase: GamePhase::Offline, match_id: None, payload: None }
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct PhaseChanged {
+    phase: GamePhase,
+    previous: GamePhase,
+    match_id: Option<String>,
+    payload: Option<serde_json::Value>,
+}
+
+#[derive(Clone, Serialize)]
+struct MatchEvent {
+    match_id: String,
+    payload: Option<serde_json::Value>,
+}
+
+struct Wake {
+    pending: Mutex<bool>,
+    signal: Condvar,
+}
+
+static WAKE: OnceLock<Wake> = OnceLock::new();
+static CURRENT: OnceLock<Mutex<PhaseSnapshot>> = OnceLock::new();
+static EVENT_SOURCE: AtomicBool = AtomicBool::new(false);
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+fn wake_handle() -> &'static Wake {
+    WAKE.get_or_init(|| Wake { pending: Mutex::new(false), signal: Condvar::new() })
+}
+
+fn current_slot() -> &'static Mutex<PhaseSnapshot> {
+    CURRENT.get_or_init(|| Mutex::new(PhaseSnapshot::default()))
+}
+
+pub fn wake() {
+    let w = wake_handle();
+    *w.pending.lock().unwrap_or_else(|e| e.into_inner()) = true;
+    w.signal.notify_all();
+}
+
+pub fn set_event_source(active: bool) {
+    if EVENT_SOURCE.swap(active, Ordering::SeqCst) != active {
+        log_info(&format!("[Watcher] Event stream {}", if active { "attached, polling slowed" } else { "detached, polling resumed" }));
+    }
+    wake();
+}
+
+pub fn current() -> PhaseSnapshot {
+    current_slot().lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn sleep_until_woken(timeout: Duration) {
+    let w = wake_handle();
+    let pending = w.pending.lock().unwrap_or_else(|e| e.into_inner());
+    let (mut pending, _) = w.signal
+        .wait_timeout_while(pending, timeout, |p| !*p)
+        .unwrap_or_else(|e| e.into_inner());
+    *pending = false;
+}
+
+fn phase_from_loop_state(loop_state: &str) -> GamePhase {
+    match loop_state {
+        "PREGAME" => GamePhase::Pregame,
+        "INGAME" => GamePhase::Ingame,
+        _ => GamePhase::Menus,
+    }
+}
+
+fn match_id_of(payload: &serde_json::Value) -> Option<String> {
+    payload["ID"].as_str()
+        .or_else(|| payload["MatchID"].as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+}
+
+struct Watcher {
+    app: AppHandle,
+    state: Arc<Mutex<ConnectionState>>,
+    last: PhaseSnapshot,
+    found_match: Option<String>,
+    agent_select_match: Option<String>,
+    last_fetch: Instant,
+}
+
+impl Watcher {
+    fn emit<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        let _ = self.app.emit(event, payload);
+    }
+
+    fn observe(&self) -> PhaseSnapshot {
+        let connected = self.state.lock().map(|s| s.connected).unwrap_or(false);
+        if !connected {
+            return PhaseSnapshot::default();
+        }
+        let client = match RiotClient::from_state(&self.state) {
+            Ok(c) => c,
+            Err(_) => return PhaseSnapshot::default(),
+        };
+
+        let loop_phase = match session_loop_state(&self.state) {
+            Ok(Some(ls)) => Some(phase_from_loop_state(&ls)),
+            Ok(None) => None,
+            Err(e) => {
+                log_error(&format!("[Watcher] presence lookup failed: {}", e));
+                None
+            }
+        };
+
+        let same_phase = loop_phase == Some(self.last.phase);
+        let needs_payload = match self.last.phase {
+            GamePhase::Pregame => self.agent_select_match.as_ref() != self.last.match_id.as_ref(),
+            GamePhase::Ingame => self.last_fetch.elapsed() >= INGAME_REFRESH,
+            _ => false,
+        };
+        if same_phase && !needs_payload {
+            return self.last.clone();
+        }
+
+        let known_match = if same_phase { self.last.match_id.clone() } else { None };
+        let result = match (loop_phase, known_match) {
+            (Some(GamePhase::Menus), _) => return PhaseSnapshot { phase: GamePhase::Menus, match_id: None, payload: None },
+            (Some(GamePhase::Pregame), Some(id)) => pregame_match(&client, &id).map(Some),
+            (Some(GamePhase::Ingame), Some(id)) => coregame_match(&client, &id).map(Some),
+            (Some(GamePhase::Pregame), None) => current_pregame(&client),
+            (Some(GamePhase::Ingame), None) => current_coregame(&client),
+            _ => current_pregame(&client).and_then(|p| match p {
+                Some(p) => Ok(Some(p)),
+                None => current_coregame(&client),
+            }),
+        };
+
+        match result {
+            Ok(Some(payload)) => PhaseSnapshot {
+                phase: if payload["_phase"] == "pregame" { GamePhase::Pregame } else { GamePhase::Ingame },
+                match_id: match_id_of(&payload),
+                payload: Some(payload),
+            },
+            Ok(None) => PhaseSnapshot { phase: GamePhase::Menus, match_id: None, payload: None },
+            Err(e) => {
+                log_error(&format!("[Watcher] match lookup failed: {}", e));
+                self.last.clone()
+            }
+        }
+    }
+
+    fn apply(&mut self, next: PhaseSnapshot) {
+        let prev = std::mem::replace(&mut self.last, next.clone());
+        let prev_select = self.agent_select_match.clone();
+        if next.payload.is_some() && (next.phase != prev.phase || next.payload != prev.payload) {
+            self.last_fetch = Instant::now();
+        }
+
+        if next.phase != prev.phase || next.match_id != prev.match_id {
+            log_info(&format!("[Watcher] {:?} -> {:?} ({})", prev.phase, next.phase, next.match_id.as_deref().unwrap_or("-")));
+            self.emit("phase-changed", PhaseChanged {
+                phase: next.phase,
+                previous: prev.phase,
+                match_id: next.match_id.clone(),
+                payload: next.payload.clone(),
+            });
+
+            if prev.phase == GamePhase::Ingame && next.phase != GamePhase::Ingame {
+                if let Some(id) = prev.match_id.clone() {
+                    self.emit("match-ended", MatchEvent { match_id: id, payload: prev.payload.clone() });
+                }
+            }
+        } else if next.payload.is_some() && next.payload != prev.payload {
+            self.emit("match-updated", PhaseChanged {
+                phase: next.phase,
+                previous: prev.phase,
+                match_id: next.match_id.clone(),
+                payload: next.payload.clone(),
+            });
+        }
+
+        if let Some(id) = next.match_id.clone() {
+            if matches!(next.phase, GamePhase::Pregame | GamePhase::Ingame) && self.found_match.as_ref() != Some(&id) {
+                self.found_match = Some(id.clone());
+                self.emit("match-found", MatchEvent { match_id: id, payload: next.payload.clone() });
+            }
+        }
+
+        if next.phase == GamePhase::Pregame {
+            let selecting = next.payload.as_ref()
+                .map(|p| p["PregameState"] == "character_select_active")
+                .unwrap_or(false);
+            if selecting && next.match_id.is_some() && self.agent_select_match != next.match_id {
+                self.agent_select_match = next.match_id.clone();
+                self.emit("agent-select-started", MatchEvent {
+                    match_id: next.match_id.clone().unwrap_or_default(),
+                    payload: next.payload.clone(),
+                });
+            }
+        }
+
+        *current_slot().lock().unwrap_or_else(|e| e.into_inner()) = next.clone();
+
+        // Started after the snapshot is stored, since the engines check it before acting.
+        if next.phase == GamePhase::Pregame && (prev.phase != GamePhase::Pregame || prev.match_id != next.match_id) {
+            if let (Some(id), Some(payload)) = (next.match_id.clone(), next.payload.clone()) {
+                dodge::start(self.app.clone(), self.state.clone(), id, payload);
+            }
+        }
+        if self.agent_select_match != prev_select {
+            if let (Some(id), Some(payload)) = (next.match_id, next.payload.as_ref()) {
+                agentselect::start(self.app.clone(), self.state.clone(), id, payload);
+            }
+        }
+    }
+
+    fn interval(&self) -> Duration {
+        if EVENT_SOURCE.load(Ordering::SeqCst) {
+            return POLL_WITH_EVENTS;
+        }
+        match self.last.phase {
+            GamePhase::Pregame => POLL_ACTIVE,
+            _ => POLL_IDLE,
+        }
+    }
+}
+
+pub fn spawn(app: AppHandle, state: Arc<Mutex<ConnectionState>>) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(move || {
+        let mut watcher = Watcher {
+            app,
+            state,
+            last: PhaseSnapshot::default(),
+            found_match: None,
+            agent_select_match: None,
+            last_fetch: Instant::now(),
+        };
+        loop {
+            let next = watcher.observe();
+            watcher.apply(next);
+            sleep_until_woken(watcher.interval());
+        }
+    });
+}
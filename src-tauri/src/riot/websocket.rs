@@ -0,0 +1,394 @@
+use std::collections::HashSet;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tungstenite::client::IntoClientRequest;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+use super::error::RiotError;
+use super::logging::{log_error, log_info};
+use super::types::ConnectionState;
+use super::watcher;
+
+const WAMP_SUBSCRIBE: u8 = 5;
+const WAMP_UNSUBSCRIBE: u8 = 6;
+const WAMP_EVENT: u8 = 8;
+
+const READ_TIMEOUT: Duration = Duration::from_millis(500);
+// Bounds the TCP connect, both handshakes and every write, so a Riot Client that accepts the
+// connection but never answers can't stall the thread.
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+const TOPIC_PRESENCES: &str = "OnJsonApiEvent_chat_v4_presences";
+const TOPIC_CONVERSATIONS: &str = "OnJsonApiEvent_chat_v6_conversations";
+const TOPIC_MESSAGES: &str = "OnJsonApiEvent_chat_v6_messages";
+const TOPIC_ENTITLEMENTS: &str = "OnJsonApiEvent_entitlements_v1_token";
+const TOPIC_SESSIONS: &str = "OnJsonApiEvent_product-session_v1_external-sessions";
+
+const DEFAULT_TOPICS: &[&str] = &[
+    TOPIC_PRESENCES,
+    TOPIC_CONVERSATIONS,
+    TOPIC_MESSAGES,
+    TOPIC_ENTITLEMENTS,
+    TOPIC_SESSIONS,
+];
+
+type Socket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalEvent {
+    pub topic: String,
+    pub uri: String,
+    pub event_type: String,
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalEventStatus {
+    pub connected: bool,
+    pub topics: Vec<String>,
+}
+
+static TOPICS: OnceLock<Mutex<Vec<String>>> = OnceLock::new();
+static CONNECTED: AtomicBool = AtomicBool::new(false);
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+fn topics_slot() -> &'static Mutex<Vec<String>> {
+    TOPICS.get_or_init(|| Mutex::new(DEFAULT_TOPICS.iter().map(|t| t.to_string()).collect()))
+}
+
+fn wanted_topics() -> Vec<String> {
+    topics_slot().lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn set_topics(topics: Vec<String>) -> Result<Vec<String>, RiotError> {
+    let mut list: Vec<String> = if topics.is_empty() {
+        DEFAULT_TOPICS.iter().map(|t| t.to_string()).collect()
+    } else {
+        topics
+    };
+    if let Some(bad) = list.iter().find(|t| !t.starts_with("OnJsonApiEvent")) {
+        return Err(RiotError::Local(format!("Invalid topic '{}': must start with OnJsonApiEvent", bad)));
+    }
+    list.sort();
+    list.dedup();
+    *topics_slot().lock().unwrap_or_else(|e| e.into_inner()) = list.clone();
+    Ok(list)
+}
+
+pub fn status() -> LocalEventStatus {
+    LocalEventStatus {
+        connected: CONNECTED.load(Ordering::SeqCst),
+        topics: wanted_topics(),
+    }
+}
+
+fn parse_event(raw: &str) -> Option<LocalEvent> {
+    let msg: serde_json::Value = serde_json::from_str(raw).ok()?;
+    let arr = msg.as_array()?;
+    if arr.len() < 3 || arr[0].as_u64() != Some(WAMP_EVENT as u64) {
+        return None;
+    }
+    let payload = &arr[2];
+    Some(LocalEvent {
+        topic: arr[1].as_str()?.to_string(),
+        uri: payload["uri"].as_str().unwrap_or_default().to_string(),
+        event_type: payload["eventType"].as_str().unwrap_or_default().to_string(),
+        data: payload["data"].clone(),
+    })
+}
+
+fn local_creds(state: &Mutex<ConnectionState>) -> Option<(u16, String)> {
+    let s = state.lock().ok()?;
+    if !s.connected {
+        return None;
+    }
+    Some((s.port?, s.local_auth.clone()?))
+}
+
+fn open_socket(port: u16, auth: &str, timeout: Duration) -> Result<Socket, String> {
+    let mut request = format!("wss://127.0.0.1:{}", port)
+        .into_client_request()
+        .map_err(|e| format!("build request: {}", e))?;
+    request.headers_mut().insert(
+        "Authorization",
+        auth.parse().map_err(|e| format!("auth header: {}", e))?,
+    );
+
+    let stream = TcpStream::connect_timeout(&SocketAddr::from(([127, 0, 0, 1], port)), timeout)
+        .map_err(|e| format!("connect: {}", e))?;
+    stream.set_read_timeout(Some(timeout)).map_err(|e| format!("set timeout: {}", e))?;
+    stream.set_write_timeout(Some(timeout)).map_err(|e| format!("set timeout: {}", e))?;
+
+    // The TLS handshake is done here rather than by tungstenite, which panics when a timed-out
+    // read surfaces as `WouldBlock` mid-handshake.
+    let tls = native_tls::TlsConnector::builder()
+        .danger_accept_invalid_certs(true)
+        .danger_accept_invalid_hostnames(true)
+        .build()
+        .map_err(|e| format!("tls: {}", e))?;
+    let stream = tls.connect("127.0.0.1", stream).map_err(|e| match e {
+        native_tls::HandshakeError::WouldBlock(_) => format!("tls handshake: no answer within {:?}", timeout),
+        native_tls::HandshakeError::Failure(e) => format!("tls handshake: {}", e),
+    })?;
+
+    let (socket, _) = tungstenite::client(request, MaybeTlsStream::NativeTls(stream)).map_err(|e| match e {
+        tungstenite::HandshakeError::Interrupted(_) => format!("handshake: no answer within {:?}", timeout),
+        tungstenite::HandshakeError::Failure(e) => format!("handshake: {}", e),
+    })?;
+    if let MaybeTlsStream::NativeTls(tls) = socket.get_ref() {
+        tls.get_ref().set_read_timeout(Some(READ_TIMEOUT)).map_err(|e| format!("set timeout: {}", e))?;
+    }
+    Ok(socket)
+}
+
+fn send_wamp<S: Read + Write>(socket: &mut WebSocket<S>, opcode: u8, topic: &str) -> Result<(), String> {
+    let msg = serde_json::json!([opcode, topic]).to_string();
+    socket.send(Message::Text(msg)).map_err(|e| format!("send: {}", e))
+}
+
+fn sync_subscriptions<S: Read + Write>(socket: &mut WebSocket<S>, subscribed: &mut HashSet<String>) -> Result<(), String> {
+    let wanted: HashSet<String> = wanted_topics().into_iter().collect();
+    for topic in subscribed.difference(&wanted) {
+        send_wamp(socket, WAMP_UNSUBSCRIBE, topic)?;
+    }
+    for topic in wanted.difference(subscribed) {
+        send_wamp(socket, WAMP_SUBSCRIBE, topic)?;
+    }
+    *subscribed = wanted;
+    Ok(())
+}
+
+fn dispatch(state: &Mutex<ConnectionState>, event: &LocalEvent) {
+    match event.topic.as_str() {
+        TOPIC_PRESENCES => {
+            let own = state.lock().ok().and_then(|s| s.puuid.clone()).unwrap_or_default();
+            let touches_self = event.data["presences"].as_array()
+                .map(|list| list.iter().any(|p| p["puuid"].as_str() == Some(own.as_str()) && p["product"].as_str() == Some("valorant")))
+                .unwrap_or(false);
+            if touches_self {
+                watcher::wake();
+            }
+        }
+        TOPIC_ENTITLEMENTS => {
+            let access = event.data["accessToken"].as_str().filter(|s| !s.is_empty());
+            let entitlements = event.data["token"].as_str().filter(|s| !s.is_empty());
+            let subject = event.data["subject"].as_str();
+            if let (Some(access), Some(entitlements)) = (access, entitlements) {
+                if let Ok(mut s) = state.lock() {
+                    if subject.is_some() && s.puuid.as_deref() != subject {
+                        // A different account signed in; the lockfile watcher reconnects as it.
+                        log_info("[Events] Tokens belong to another account, ignoring");
+                    } else if s.connected && s.access_token.as_deref() != Some(access) {
+                        s.access_token = Some(access.to_string());
+                        s.entitlements = Some(entitlements.to_string());
+                        s.token_fetched_at = Some(Instant::now());
+                        log_info("[Events] Tokens updated by Riot Client");
+                    }
+                }
+            }
+        }
+        TOPIC_SESSIONS => watcher::wake(),
+        _ => {}
+    }
+}
+
+fn run_session(app: &AppHandle, state: &Mutex<ConnectionState>, port: u16, auth: &str) -> Result<(), String> {
+    let mut socket = open_socket(port, auth, IO_TIMEOUT)?;
+    let mut subscribed = HashSet::new();
+    sync_subscriptions(&mut socket, &mut subscribed)?;
+
+    CONNECTED.store(true, Ordering::SeqCst);
+    watcher::set_event_source(true);
+    log_info(&format!("[Events] Subscribed to {} local topics on port {}", subscribed.len(), port));
+
+    pump(&mut socket, state, port, &mut subscribed, |event| {
+        let _ = app.emit("local-event", event);
+    })
+}
+
+// Reads events until the connection drops or the Riot Client moves to another port.
+fn pump<S: Read + Write>(
+    socket: &mut WebSocket<S>,
+    state: &Mutex<ConnectionState>,
+    port: u16,
+    subscribed: &mut HashSet<String>,
+    mut on_event: impl FnMut(&LocalEvent),
+) -> Result<(), String> {
+    loop {
+        if local_creds(state).map(|(p, _)| p) != Some(port) {
+            let _ = socket.close(None);
+            return Ok(());
+        }
+        sync_subscriptions(socket, subscribed)?;
+
+        match socket.read() {
+            Ok(Message::Text(raw)) => {
+                if let Some(event) = parse_event(&raw) {
+                    dispatch(state, &event);
+                    on_event(&event);
+                }
+            }
+            Ok(Message::Close(_)) => return Err("closed by client".to_string()),
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e)) if e.kind() == std::io::ErrorKind::WouldBlock
+                    || e.kind() == std::io::ErrorKind::TimedOut => {}
+            Err(e) => return Err(format!("read: {}", e)),
+        }
+    }
+}
+
+pub fn spawn(app: AppHandle, state: Arc<Mutex<ConnectionState>>) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(move || {
+        let mut last_error = String::new();
+        loop {
+            let Some((port, auth)) = local_creds(&state) else {
+                std::thread::sleep(RETRY_DELAY);
+                continue;
+            };
+            let result = run_session(&app, &state, port, &auth);
+            if CONNECTED.swap(false, Ordering::SeqCst) {
+                watcher::set_event_source(false);
+            }
+            match result {
+                Ok(()) => {
+                    log_info("[Events] Local event stream closed");
+                    last_error.clear();
+                }
+                Err(e) => {
+                    if e != last_error {
+                        log_error(&format!("[Events] Local event stream failed: {}", e));
+                        last_error = e;
+                    }
+                    std::thread::sleep(RETRY_DELAY);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    const PUUID: &str = "00000000-0000-0000-0000-000000000001";
+
+    fn event(topic: &str, event_type: &str, data: serde_json::Value) -> Message {
+        let uri = format!("/{}", topic.trim_start_matches("OnJsonApiEvent_").replace('_', "/"));
+        Message::Text(serde_json::json!([WAMP_EVENT, topic, { "uri": uri, "eventType": event_type, "data": data }]).to_string())
+    }
+
+    // Plays the Riot Client: records the subscribe frames, pushes a presence update and a
+    // token refresh, then closes.
+    fn mock_server(listener: TcpListener) -> std::thread::JoinHandle<Vec<serde_json::Value>> {
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut ws = tungstenite::accept(stream).unwrap();
+            let mut frames = Vec::new();
+            while frames.len() < DEFAULT_TOPICS.len() {
+                if let Message::Text(raw) = ws.read().unwrap() {
+                    frames.push(serde_json::from_str(&raw).unwrap());
+                }
+            }
+            ws.send(event(TOPIC_PRESENCES, "Update", serde_json::json!({
+                "presences": [{ "puuid": PUUID, "product": "valorant", "private": "" }]
+            }))).unwrap();
+            ws.send(Message::Text("[0, \"welcome\"]".to_string())).unwrap();
+            ws.send(event(TOPIC_ENTITLEMENTS, "Update", serde_json::json!({
+                "accessToken": "new-access", "token": "new-entitlements", "subject": PUUID
+            }))).unwrap();
+            ws.close(None).unwrap();
+            while ws.read().is_ok() {}
+            frames
+        })
+    }
+
+    #[test]
+    fn subscribes_and_dispatches_events() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = mock_server(listener);
+
+        let state = Mutex::new(ConnectionState {
+            connected: true,
+            port: Some(port),
+            local_auth: Some("Basic cmlvdDp0ZXN0".to_string()),
+            puuid: Some(PUUID.to_string()),
+            access_token: Some("old-access".to_string()),
+            entitlements: Some("old-entitlements".to_string()),
+            ..Default::default()
+        });
+
+        let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        stream.set_read_timeout(Some(READ_TIMEOUT)).unwrap();
+        let (mut socket, _) = tungstenite::client(format!("ws://127.0.0.1:{}", port), stream).unwrap();
+        let mut subscribed = HashSet::new();
+        sync_subscriptions(&mut socket, &mut subscribed).unwrap();
+        let mut events = Vec::new();
+        let result = pump(&mut socket, &state, port, &mut subscribed, |e| events.push(e.clone()));
+        assert_eq!(result, Err("closed by client".to_string()));
+        // Lets the mock server see the connection end.
+        drop(socket);
+
+        let mut frames = server.join().unwrap();
+        frames.sort_by_key(|f| f[1].as_str().unwrap_or_default().to_string());
+        let mut expected: Vec<serde_json::Value> = DEFAULT_TOPICS.iter().map(|t| serde_json::json!([WAMP_SUBSCRIBE, t])).collect();
+        expected.sort_by_key(|f| f[1].as_str().unwrap_or_default().to_string());
+        assert_eq!(frames, expected);
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].topic, TOPIC_PRESENCES);
+        assert_eq!(events[0].uri, "/chat/v4/presences");
+        assert_eq!(events[0].event_type, "Update");
+        assert_eq!(events[0].data["presences"][0]["puuid"], PUUID);
+        assert_eq!(events[1].topic, TOPIC_ENTITLEMENTS);
+
+        let s = state.lock().unwrap();
+        assert_eq!(s.access_token.as_deref(), Some("new-access"));
+        assert_eq!(s.entitlements.as_deref(), Some("new-entitlements"));
+        assert!(s.token_fetched_at.is_some());
+    }
+
+    #[test]
+    fn silent_server_times_out_the_handshake() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // Accepts and never answers; ends once the client gives up and closes.
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = std::io::copy(&mut stream, &mut std::io::sink());
+        });
+
+        let started = Instant::now();
+        let err = open_socket(port, "Basic cmlvdDp0ZXN0", Duration::from_millis(300)).err().unwrap();
+        assert!(err.contains("no answer within"), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn ignores_tokens_for_another_account() {
+        let state = Mutex::new(ConnectionState {
+            connected: true,
+            puuid: Some(PUUID.to_string()),
+            access_token: Some("old-access".to_string()),
+            ..Default::default()
+        });
+        let Message::Text(raw) = event(TOPIC_ENTITLEMENTS, "Update", serde_json::json!({
+            "accessToken": "other-access", "token": "other-entitlements", "subject": "someone-else"
+        })) else { unreachable!() };
+        dispatch(&state, &parse_event(&raw).unwrap());
+        assert_eq!(state.lock().unwrap().access_token.as_deref(), Some("old-access"));
+    }
+}
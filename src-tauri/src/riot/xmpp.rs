@@ -5,6 +5,8 @@ use std::sync::Mutex;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use base64::Engine;
 
+use super::jwt;
+
 pub struct XmppLog {
     pub direction: String,
     pub data: String,
@@ -265,7 +267,7 @@ fn xmpp_read_until(stream: &mut native_tls::TlsStream<TcpStream>, marker: &str,
     }
 }
 
-fn fetch_pas_token(access_token: &str) -> Result<String, String> {
+pub fn fetch_pas_token(access_token: &str) -> Result<String, String> {
     let raw = super::http::authed_get(
         "https://riot-geo.pas.si.riotgames.com/pas/v1/service/chat",
         access_token,
@@ -273,21 +275,8 @@ fn fetch_pas_token(access_token: &str) -> Result<String, String> {
     Ok(raw.trim().to_string())
 }
 
-fn decode_affinity(pas_token: &str) -> Result<String, String> {
-    let parts: Vec<&str> = pas_token.trim().split('.').collect();
-    if parts.len() < 2 {
-        return Err("Invalid PAS JWT format".to_string());
-    }
-
-    let payload = base64::engine::general_purpose::URL_SAFE_NO_PAD
-        .decode(parts[1])
-        .or_else(|_| base64::engine::general_purpose::STANDARD_NO_PAD.decode(parts[1]))
-        .or_else(|_| base64::engine::general_purpose::STANDARD.decode(parts[1]))
-        .map_err(|e| format!("base64 decode PAS payload: {}", e))?;
-
-    let json: serde_json::Value = serde_json::from_slice(&payload)
-        .map_err(|e| format!("parse PAS payload: {}", e))?;
-
+pub fn decode_affinity(pas_token: &str) -> Result<String, String> {
+    let json = jwt::decode_payload(pas_token).map_err(|e| format!("PAS token: {}", e))?;
     json["affinity"].as_str()
         .ok_or("No affinity in PAS token".to_string())
         .map(|s| s.to_string())
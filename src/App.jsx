@@ -7,10 +7,10 @@ import appIcon from "../src-tauri/icons/icon.png";
 import { open as shellOpen } from "@tauri-apps/plugin-shell";
 import TitleBar from "./components/TitleBar";
 import Sidebar from "./components/Sidebar";
-import InstalockPage from "./components/InstalockPage";
+import InstalockPage, { buildAgentRules, loadAgentRules, parseAgentRules } from "./components/InstalockPage";
 import SettingsPage from "./components/SettingsPage";
 import LogsPage from "./components/LogsPage";
-import MapDodgePage from "./components/MapDodgePage";
+import MapDodgePage, { loadDodgeConfig } from "./components/MapDodgePage";
 import MatchInfoPage from "./components/MatchInfoPage";
 import PartyPage from "./components/PartyPage";
 import MiscPage from "./components/MiscPage";
@@ -68,8 +68,8 @@ function buildGradientCSS(ct) {
   return `linear-gradient(${ct.angle}deg, ${stops.map(s => `${s.color} ${s.pos}%`).join(", ")})`;
 }
 
-function formatTimeLeft(ageSecs) {
-  const left = Math.max(0, 600 - ageSecs);
+function formatTimeLeft(leftSecs) {
+  const left = Math.max(0, leftSecs);
   const m = Math.floor(left / 60);
   const s = left % 60;
   if (m > 0) return `${m}m ${s}s`;
@@ -103,7 +103,7 @@ function renderMarkdownInline(text) {
 
 const RECONNECT_INTERVAL = 3000;
 const HEALTH_CHECK_INTERVAL = 10000;
-const MATCH_POLL_INTERVAL = 1500;
+const OUT_OF_MATCH_CONFIRM_DELAY = 1500;
 
 export default function App() {
   const [status, setStatus] = useState("waiting");
@@ -127,55 +127,30 @@ export default function App() {
   const [closeWithGame, setCloseWithGame] = useState(() => localStorage.getItem("close_with_game") === "true");
   const [devMode, setDevMode] = useState(() => localStorage.getItem("dev_mode") === "true");
   const [disableAnimations, setDisableAnimations] = useState(() => localStorage.getItem("disable_animations") === "true");
-  const [nodeInstalled, setNodeInstalled] = useState(true);
   const [updateInfo, setUpdateInfo] = useState(null);
   const [showUpdateModal, setShowUpdateModal] = useState(false);
   const [updating, setUpdating] = useState(false);
   const [showOlderReleases, setShowOlderReleases] = useState(false);
   const [fakeStatusUnsaved, setFakeStatusUnsaved] = useState(false);
   const [logs, setLogs] = useState([]);
-  const [instalockActive, setInstalockActive] = useState(() => {
-    try {
-      const cfg = JSON.parse(localStorage.getItem("instalock-config"));
-      return cfg?.active || false;
-    } catch { return false; }
-  });
+  const [instalockActive, setInstalockActive] = useState(false);
   const [henrikApiKey, setHenrikApiKey] = useState(() => localStorage.getItem("henrik_api_key") || "");
   const [splooshimaApiKey, setSplooshimaApiKey] = useState(() => localStorage.getItem("splooshima_api_key") || "");
   const [splooshimaAvailable, setSplooshimaAvailable] = useState(true);
-  const [mapDodgeActive, setMapDodgeActive] = useState(() => {
-    try {
-      const cfg = JSON.parse(localStorage.getItem("mapdodge-config"));
-      return cfg?.active || false;
-    } catch { return false; }
-  });
+  const [mapDodgeActive, setMapDodgeActive] = useState(false);
   const [pregameMatchId, setPregameMatchId] = useState(null);
   const [refreshKey, setRefreshKey] = useState(0);
   const [autoUnqueue, setAutoUnqueue] = useState(() => localStorage.getItem("auto_unqueue") === "true");
   const [autoRequeue, setAutoRequeue] = useState(() => localStorage.getItem("auto_requeue") === "true");
-  const [selectDelay, setSelectDelay] = useState(() => {
-    const saved = localStorage.getItem("instalock_select_delay");
-    return saved ? parseInt(saved, 10) : 0;
-  });
-  const [lockDelay, setLockDelay] = useState(() => {
-    const saved = localStorage.getItem("instalock_lock_delay");
-    return saved ? parseInt(saved, 10) : 500;
-  });
+  const [selectDelay, setSelectDelay] = useState(0);
+  const [lockDelay, setLockDelay] = useState(500);
   const connectingRef = useRef(false);
-  const instalockConfigRef = useRef({ maps: [], selectedAgent: null, perMapSelections: {} });
-  const lockedMatchRef = useRef(null);
+  const instalockConfigRef = useRef({ maps: [], selectedAgent: null, perMapSelections: {}, fallbacks: [], roleGoals: [] });
+  // Backend `AgentRules` once loaded; null until then so launch never saves over them.
+  const [agentRules, setAgentRules] = useState(null);
   const lockedAgentNameRef = useRef(null);
-  const selectDelayRef = useRef(selectDelay);
-  const lockDelayRef = useRef(lockDelay);
   const lastLogKeyRef = useRef(null);
-  const mapDodgeRef = useRef((() => {
-    try {
-      const cfg = JSON.parse(localStorage.getItem("mapdodge-config"));
-      return { blacklist: new Set(cfg?.blacklist || []), maps: [] };
-    } catch { return { blacklist: new Set(), maps: [] }; }
-  })());
-  const dodgedMatchRef = useRef(null);
-  const mapDodgeActiveRef = useRef(mapDodgeActive);
+  const mapDodgeRef = useRef({ blacklist: new Set(), maps: [] });
   const gamePhaseRef = useRef(null);
   const rpcMatchInfoRef = useRef(null);
   const autoUnqueueRef = useRef(autoUnqueue);
@@ -188,19 +163,20 @@ export default function App() {
   const henrikApiKeyRef = useRef(henrikApiKey);
 
   useEffect(() => {
-    const cfg = (() => { try { return JSON.parse(localStorage.getItem("instalock-config")); } catch { return null; } })();
-    if (!cfg) return;
-    const EXCLUDED = ["The Range", "District", "Kasbah", "Drift", "Glitch", "Piazza", "Basic Training", "Skirmish A", "Skirmish B", "Skirmish C"];
-    fetch("https://valorant-api.com/v1/maps").then(r => r.json()).then(res => {
-      const maps = (res.data || []).filter(m => !EXCLUDED.includes(m.displayName));
-      const perMap = {};
-      if (cfg.perMap) {
-        for (const [mapId, saved] of Object.entries(cfg.perMap)) {
-          if (saved) perMap[mapId] = saved;
-        }
-      }
-      instalockConfigRef.current = { maps, selectedAgent: cfg.defaultAgent || null, perMapSelections: perMap };
-    }).catch(() => {});
+    loadAgentRules().then((rules) => {
+      setAgentRules(rules);
+      setInstalockActive(rules.enabled);
+      setSelectDelay(rules.select_delay_ms);
+      setLockDelay(rules.lock_delay_ms);
+      // Agent names for the Discord status and the logs.
+      return Promise.all([
+        fetch("https://valorant-api.com/v1/agents?isPlayableCharacter=true").then((r) => r.json()),
+        fetch("https://valorant-api.com/v1/maps").then((r) => r.json()),
+      ]).then(([agentsRes, mapsRes]) => {
+        const maps = mapsRes.data || [];
+        instalockConfigRef.current = { maps, ...parseAgentRules(rules, agentsRes.data || [], maps) };
+      });
+    }).catch((e) => console.error("[instalock] rules load failed:", e));
   }, []);
 
   useEffect(() => {
@@ -213,10 +189,6 @@ export default function App() {
     }
   }, []);
 
-  useEffect(() => {
-    invoke("check_node_installed").then((ok) => setNodeInstalled(ok)).catch(() => setNodeInstalled(false));
-  }, []);
-
   useEffect(() => {
     invoke("check_for_update").then((raw) => {
       try {
@@ -234,10 +206,15 @@ export default function App() {
   const wasConnectedRef = useRef(false);
   useEffect(() => { closeWithGameRef.current = closeWithGame; localStorage.setItem("close_with_game", String(closeWithGame)); }, [closeWithGame]);
   useEffect(() => { if (status === "connected") wasConnectedRef.current = true; }, [status]);
+  useEffect(() => {
+    // The backend saves the base dir now; move a value left by older versions over once.
+    const dir = localStorage.getItem("riot_base_dir");
+    if (dir) invoke("set_riot_base_dir", { path: dir }).then(() => localStorage.removeItem("riot_base_dir")).catch(() => {});
+  }, []);
 
   useEffect(() => {
     if (!closeWithGame) return;
-    if (status !== "connected" && status !== "waiting") return;
+    if (status !== "connected" && status !== "waiting" && status !== "reconnecting") return;
     if (status === "waiting" && !wasConnectedRef.current) return;
     const id = setInterval(async () => {
       if (!closeWithGameRef.current) return;
@@ -264,7 +241,11 @@ export default function App() {
     return () => window.removeEventListener("keydown", handler);
   }, [devMode]);
 
-  useEffect(() => { mapDodgeActiveRef.current = mapDodgeActive; }, [mapDodgeActive]);
+  useEffect(() => {
+    loadDodgeConfig()
+      .then((cfg) => { mapDodgeRef.current = { ...cfg, maps: [] }; setMapDodgeActive(cfg.active); })
+      .catch((e) => console.error("[mapdodge] config load failed:", e));
+  }, []);
   useEffect(() => { autoUnqueueRef.current = autoUnqueue; localStorage.setItem("auto_unqueue", String(autoUnqueue)); }, [autoUnqueue]);
   useEffect(() => { autoRequeueRef.current = autoRequeue; localStorage.setItem("auto_requeue", String(autoRequeue)); }, [autoRequeue]);
 
@@ -292,6 +273,8 @@ export default function App() {
         details = "Connecting...";
       } else if (status === "waiting") {
         details = "Waiting for Valorant";
+      } else if (status === "reconnecting") {
+        details = "Reconnecting...";
       } else if (status === "connected") {
         const phase = gamePhaseRef.current;
         const mi = rpcMatchInfoRef.current;
@@ -343,9 +326,14 @@ export default function App() {
     return () => clearInterval(interval);
   }, [status, discordRpc, instalockActive, mapDodgeActive]);
 
-  useEffect(() => { selectDelayRef.current = selectDelay; localStorage.setItem("instalock_select_delay", selectDelay); }, [selectDelay]);
   useEffect(() => { localStorage.setItem("henrik_api_key", henrikApiKey); }, [henrikApiKey]);
-  useEffect(() => { lockDelayRef.current = lockDelay; localStorage.setItem("instalock_lock_delay", lockDelay); }, [lockDelay]);
+
+  useEffect(() => {
+    if (!agentRules) return;
+    invoke("set_agent_select_rules", {
+      rules: { ...agentRules, enabled: instalockActive, select_delay_ms: selectDelay, lock_delay_ms: lockDelay },
+    }).catch((e) => console.error("[instalock] rules save failed:", e));
+  }, [agentRules, instalockActive, selectDelay, lockDelay]);
 
   useEffect(() => {
     document.documentElement.classList.toggle("no-animations", disableAnimations);
@@ -386,7 +374,31 @@ export default function App() {
   }, [addLog]);
 
   const [showRefreshModal, setShowRefreshModal] = useState(false);
-  const [tokenAge, setTokenAge] = useState(0);
+  const [tokenLeft, setTokenLeft] = useState(0);
+
+  const onConnected = async (info) => {
+    setPlayer(info);
+    setStatus("connected");
+    setRefreshKey(k => k + 1);
+    addLog("info", `[Connect] Connected as ${info.game_name}#${info.game_tag} (${info.puuid?.slice(0,8)}...)`);
+    if (info.rso_debug) {
+      try { addLog("info", "RSO Userinfo (auth.riotgames.com/userinfo)", JSON.parse(info.rso_debug)); } catch { addLog("info", "RSO Userinfo", info.rso_debug); }
+    }
+    if (info.loadout_debug) {
+      try { addLog("info", "PD Player Loadout (playerloadout)", JSON.parse(info.loadout_debug)); } catch { addLog("info", "PD Player Loadout", info.loadout_debug); }
+    }
+    const sKey = localStorage.getItem("splooshima_api_key") || "";
+    if (sKey && info.puuid) {
+      try {
+        await invoke("splooshima_lookup", { puuids: [info.puuid], apiKey: sKey });
+        setSplooshimaAvailable(true);
+        addLog("info", "[Splooshima] Health check passed — available this session");
+      } catch (sErr) {
+        setSplooshimaAvailable(false);
+        addLog("error", `[Splooshima] Health check failed — using Henrik fallback this session: ${sErr?.message ?? sErr}`);
+      }
+    }
+  };
 
   const doConnect = async () => {
     if (connectingRef.current) return;
@@ -402,27 +414,7 @@ export default function App() {
         return;
       }
       const info = await invoke("connect");
-      setPlayer(info);
-      setStatus("connected");
-      setRefreshKey(k => k + 1);
-      addLog("info", `[Connect] Connected as ${info.game_name}#${info.game_tag} (${info.puuid?.slice(0,8)}...)`);
-      if (info.rso_debug) {
-        try { addLog("info", "RSO Userinfo (auth.riotgames.com/userinfo)", JSON.parse(info.rso_debug)); } catch { addLog("info", "RSO Userinfo", info.rso_debug); }
-      }
-      if (info.loadout_debug) {
-        try { addLog("info", "PD Player Loadout (playerloadout)", JSON.parse(info.loadout_debug)); } catch { addLog("info", "PD Player Loadout", info.loadout_debug); }
-      }
-      const sKey = localStorage.getItem("splooshima_api_key") || "";
-      if (sKey && info.puuid) {
-        try {
-          await invoke("splooshima_lookup", { puuids: [info.puuid], apiKey: sKey });
-          setSplooshimaAvailable(true);
-          addLog("info", "[Splooshima] Health check passed — available this session");
-        } catch (sErr) {
-          setSplooshimaAvailable(false);
-          addLog("error", `[Splooshima] Health check failed — using Henrik fallback this session: ${sErr}`);
-        }
-      }
+      await onConnected(info);
     } catch (err) {
       const errMsg = typeof err === "string" ? err : err?.message || String(err);
       addLog("error", `[Connect] Failed: ${errMsg}`);
@@ -435,7 +427,7 @@ export default function App() {
 
   useEffect(() => {
     if (!showRefreshModal) return;
-    const id = setInterval(() => setTokenAge((t) => t + 1), 1000);
+    const id = setInterval(() => setTokenLeft((t) => Math.max(0, t - 1)), 1000);
     return () => clearInterval(id);
   }, [showRefreshModal]);
 
@@ -445,9 +437,9 @@ export default function App() {
       return;
     }
     try {
-      const age = await invoke("get_token_age");
-      setTokenAge(Number(age));
-    } catch { setTokenAge(0); }
+      const info = await invoke("get_token_info");
+      setTokenLeft(Number(info.expires_in_secs ?? 0));
+    } catch { setTokenLeft(0); }
     setShowRefreshModal(true);
   };
 
@@ -459,6 +451,26 @@ export default function App() {
     doConnect();
   };
 
+  useEffect(() => {
+    const unlisten = listen("connection-changed", (event) => {
+      const { connected, state, reason, player: info } = event.payload;
+      if (connected && info) {
+        if (connectingRef.current) return;
+        addLog("info", `[Connect] Riot Client ${reason} — connected automatically`);
+        onConnected(info);
+      } else if (state === "reconnecting") {
+        addLog("info", `[Connect] Riot Client ${reason} — reconnecting`);
+        if (info) setPlayer(info);
+        setStatus("reconnecting");
+      } else if (!connected) {
+        addLog("info", `[Connect] Riot Client ${reason} — disconnected`);
+        setPlayer(null);
+        setStatus("waiting");
+      }
+    });
+    return () => { unlisten.then(fn => fn()); };
+  }, [addLog]);
+
   useEffect(() => {
     if (status !== "waiting") return;
     let cancelled = false;
@@ -488,6 +500,9 @@ export default function App() {
         const info = await invoke("health_check");
         if (info) {
           setPlayer(info);
+        } else if (await invoke("get_status") === "reconnecting") {
+          addLog("info", "[Health] Riot Client closed — waiting for it to restart");
+          setStatus("reconnecting");
         } else {
           addLog("error", "[Health] Riot Client API returned null — connection lost");
           setPlayer(null);
@@ -499,12 +514,6 @@ export default function App() {
         setPlayer(null);
         setStatus("waiting");
       }
-      try {
-        await invoke("check_loadout");
-      } catch (err) {
-        const errMsg = typeof err === "string" ? err : err?.message || String(err);
-        addLog("error", `[Loadout] PD loadout check failed: ${errMsg}`);
-      }
       try {
         const raw = localStorage.getItem("menu_video_config");
         if (raw) {
@@ -531,142 +540,156 @@ export default function App() {
       addLog(type, message, data);
     };
 
-    const poll = async () => {
-      if (cancelled) return;
-      try {
-        const raw = await invoke("check_current_game");
-        const match = JSON.parse(raw);
-        const phase = match._phase === "pregame" ? "PREGAME" : "INGAME";
-        const matchId = match.ID || match.MatchID;
-        logOnce(`match:${matchId}:${phase}`, "match", `[${phase}] Match ${matchId} — Map: ${match.MapID}`, raw);
-
-        const currentPhase = match._phase === "pregame" ? "pregame" : "ingame";
-        gamePhaseRef.current = currentPhase;
-
-        if (currentPhase === "ingame") {
-          const myPuuid = player?.puuid;
-          const me = (match.Players || []).find((p) => p.Subject === myPuuid);
-          const myTeam = me?.TeamID;
-          const blueTeam = (match.Teams || []).find((t) => t.TeamID === "Blue");
-          const redTeam = (match.Teams || []).find((t) => t.TeamID === "Red");
-          const allyScore = myTeam === "Blue" ? (blueTeam?.RoundsWon ?? 0) : (redTeam?.RoundsWon ?? 0);
-          const enemyScore = myTeam === "Blue" ? (redTeam?.RoundsWon ?? 0) : (blueTeam?.RoundsWon ?? 0);
-          const modeUrl = match.GameMode || "";
-          const queueId = match.MatchmakingData?.QueueID || match.QueueID || "";
-          let mode = "Custom";
-          if (modeUrl.includes("competitive") || queueId === "competitive") mode = "Competitive";
-          else if (modeUrl.includes("unrated") || queueId === "unrated") mode = "Unrated";
-          else if (modeUrl.includes("deathmatch") || queueId === "deathmatch") mode = "Deathmatch";
-          else if (modeUrl.includes("spikerush") || queueId === "spikerush") mode = "Spike Rush";
-          else if (modeUrl.includes("swiftplay") || queueId === "swiftplay") mode = "Swiftplay";
-          else if (modeUrl.includes("ggteam") || queueId === "ggteam") mode = "Escalation";
-          else if (queueId === "premier") mode = "Premier";
-          rpcMatchInfoRef.current = { allyScore, enemyScore, mode, isDeathmatch: mode === "Deathmatch" };
-        } else {
-          rpcMatchInfoRef.current = null;
-        }
+    const updateRpcInfo = (match) => {
+      const myPuuid = player?.puuid;
+      const me = (match.Players || []).find((p) => p.Subject === myPuuid);
+      const myTeam = me?.TeamID;
+      const blueTeam = (match.Teams || []).find((t) => t.TeamID === "Blue");
+      const redTeam = (match.Teams || []).find((t) => t.TeamID === "Red");
+      const allyScore = myTeam === "Blue" ? (blueTeam?.RoundsWon ?? 0) : (redTeam?.RoundsWon ?? 0);
+      const enemyScore = myTeam === "Blue" ? (redTeam?.RoundsWon ?? 0) : (blueTeam?.RoundsWon ?? 0);
+      const modeUrl = match.GameMode || "";
+      const queueId = match.MatchmakingData?.QueueID || match.QueueID || "";
+      let mode = "Custom";
+      if (modeUrl.includes("competitive") || queueId === "competitive") mode = "Competitive";
+      else if (modeUrl.includes("unrated") || queueId === "unrated") mode = "Unrated";
+      else if (modeUrl.includes("deathmatch") || queueId === "deathmatch") mode = "Deathmatch";
+      else if (modeUrl.includes("spikerush") || queueId === "spikerush") mode = "Spike Rush";
+      else if (modeUrl.includes("swiftplay") || queueId === "swiftplay") mode = "Swiftplay";
+      else if (modeUrl.includes("ggteam") || queueId === "ggteam") mode = "Escalation";
+      else if (queueId === "premier") mode = "Premier";
+      rpcMatchInfoRef.current = { allyScore, enemyScore, mode, isDeathmatch: mode === "Deathmatch" };
+    };
 
-        if (match._phase === "pregame") {
-          setPregameMatchId(matchId);
-
-          if (mapDodgeActiveRef.current && dodgedMatchRef.current !== matchId) {
-            const dodgeCfg = mapDodgeRef.current;
-            if (dodgeCfg.blacklist.has(match.MapID)) {
-              dodgedMatchRef.current = matchId;
-              addLog("info", `Map blacklisted — auto-dodging ${match.MapID}`);
-              try {
-                await invoke("pregame_quit", { matchId });
-                addLog("match", "Auto-dodged blacklisted map!");
-                setPregameMatchId(null);
-                lockedMatchRef.current = null;
-              } catch (dodgeErr) {
-                const dodgeMsg = typeof dodgeErr === "string" ? dodgeErr : dodgeErr?.message || "Dodge failed";
-                addLog("error", `Auto-dodge failed: ${dodgeMsg}`);
-              }
-              if (!cancelled) setTimeout(poll, MATCH_POLL_INTERVAL);
-              return;
-            }
-          }
+    const onDodge = ({ outcome, reason, map_id }) => {
+      if (outcome === "dodged") {
+        addLog("match", `Auto-dodged ${map_id} — ${reason}`);
+        setPregameMatchId(null);
+      } else if (outcome === "dry_run") addLog("info", `[Dry run] ${reason} (${map_id})`);
+      else if (outcome === "refused") addLog("info", `Dodge skipped: ${reason}`);
+      else if (outcome === "failed") addLog("error", `Auto-dodge failed: ${reason}`);
+      else logOnce(`nododge:${map_id}`, "info", `No dodge rule matched ${map_id}`);
+    };
 
-          if (instalockActive && lockedMatchRef.current !== matchId && match.PregameState === "character_select_active") {
-            const cfg = instalockConfigRef.current;
-            const mapEntry = cfg.maps.find((m) => m.mapUrl === match.MapID);
-            const agent = mapEntry
-              ? (cfg.perMapSelections[mapEntry.uuid] || cfg.selectedAgent)
-              : cfg.selectedAgent;
-
-            if (agent && agent.uuid === "none") {
-              lockedMatchRef.current = matchId;
-              logOnce(`none:${matchId}`, "info", "Instalock disabled for this map (None selected)");
-            } else if (agent) {
-              lockedMatchRef.current = matchId;
-              const sd = selectDelayRef.current;
-              const ld = lockDelayRef.current;
-              addLog("info", `Selecting ${agent.displayName} in ${sd}ms`);
-              await new Promise((r) => setTimeout(r, sd));
-              if (cancelled) return;
-              await invoke("select_agent", { matchId, agentId: agent.uuid });
-              addLog("info", `Selected — locking in ${ld}ms`);
-              await new Promise((r) => setTimeout(r, ld));
-              if (cancelled) return;
-              await invoke("lock_agent", { matchId, agentId: agent.uuid });
-              lockedAgentNameRef.current = agent.displayName;
-              addLog("match", `Locked ${agent.displayName}!`);
-            } else {
-              logOnce(`noagent:${matchId}`, "info", "No agent configured for this map");
-            }
-          }
-        } else {
-          setPregameMatchId(null);
-        }
-      } catch (err) {
-        const msg = typeof err === "string" ? err : err?.message || "Unknown error";
-        if (!msg.includes("Not in a match")) {
-          addLog("error", msg);
-        } else {
-          const prevPhase = gamePhaseRef.current;
-          gamePhaseRef.current = null;
-
-          if (prevPhase === "pregame" && autoUnqueueRef.current) {
-            addLog("info", `[Misc] Dodge detected (phase: ${prevPhase} → none) — waiting for confirmed out-of-match`);
-            pendingUnqueueRef.current = true;
-          } else if (prevPhase === "pregame") {
-            addLog("info", `[Misc] Dodge detected but auto-unqueue is off`);
-          }
-          if (prevPhase === "ingame" && autoRequeueRef.current) {
-            addLog("info", `[Misc] Match ended (phase: ${prevPhase} → none) — waiting for confirmed out-of-match`);
-            pendingRequeueRef.current = true;
-          } else if (prevPhase === "ingame") {
-            addLog("info", `[Misc] Match ended but auto-requeue is off`);
-          }
+    let roleTable = [];
+    invoke("get_agent_roles").then((t) => { roleTable = t.agents; }).catch(() => {});
+    const agentName = (uuid) => {
+      const cfg = instalockConfigRef.current;
+      const known = [cfg.selectedAgent, ...Object.values(cfg.perMapSelections), ...(cfg.fallbacks || [])];
+      return known.find((a) => a?.uuid?.toLowerCase() === uuid)?.displayName
+        || roleTable.find((a) => a.uuid === uuid)?.name
+        || uuid;
+    };
 
-          if (!prevPhase && pendingUnqueueRef.current) {
-            pendingUnqueueRef.current = false;
-            addLog("info", "[Misc] Confirmed out-of-match — leaving queue");
-            invoke("leave_queue")
-              .then(() => addLog("info", "[Misc] Successfully left queue after dodge"))
-              .catch((e) => addLog("error", `[Misc] Failed to leave queue: ${e}`));
-          }
-          if (!prevPhase && pendingRequeueRef.current) {
-            pendingRequeueRef.current = false;
-            addLog("info", "[Misc] Confirmed out-of-match — requeuing");
-            invoke("enter_queue")
-              .then(() => addLog("info", "[Misc] Successfully requeued after match"))
-              .catch((e) => addLog("error", `[Misc] Failed to requeue: ${e}`));
-          }
+    const onDecision = ({ action, agent_id, reason }) => {
+      const name = agent_id ? agentName(agent_id) : null;
+      const why = reason.replace(/[0-9a-f]{8}-[0-9a-f-]{27}/g, agentName);
+      if (action === "select") addLog("info", `Selected ${name} — ${why}`);
+      else if (action === "lock") {
+        lockedAgentNameRef.current = name;
+        addLog("match", `Locked ${name}!`);
+      } else if (action === "fallback") addLog("info", `Falling back to ${name}: ${why}`);
+      else if (action === "skip") logOnce(`skip:${why}`, "info", `Instalock skipped: ${why}`);
+      else addLog("error", `Instalock failed${name ? ` (${name})` : ""}: ${why}`);
+    };
 
-          logOnce("not_in_match", "info", "Not in a match");
-          setPregameMatchId(null);
-          lockedMatchRef.current = null;
-          lockedAgentNameRef.current = null;
-          rpcMatchInfoRef.current = null;
-          dodgedMatchRef.current = null;
+    const confirmOutOfMatch = () => {
+      setTimeout(async () => {
+        if (cancelled) return;
+        try {
+          const snap = await invoke("get_game_phase");
+          if (snap.phase === "pregame" || snap.phase === "ingame") return;
+        } catch {
+          return;
+        }
+        if (pendingUnqueueRef.current) {
+          pendingUnqueueRef.current = false;
+          addLog("info", "[Misc] Confirmed out-of-match — leaving queue");
+          invoke("leave_queue")
+            .then(() => addLog("info", "[Misc] Successfully left queue after dodge"))
+            .catch((e) => addLog("error", `[Misc] Failed to leave queue: ${e?.message ?? e}`));
+        }
+        if (pendingRequeueRef.current) {
+          pendingRequeueRef.current = false;
+          addLog("info", "[Misc] Confirmed out-of-match — requeuing");
+          invoke("enter_queue")
+            .then(() => addLog("info", "[Misc] Successfully requeued after match"))
+            .catch((e) => addLog("error", `[Misc] Failed to requeue: ${e?.message ?? e}`));
         }
+      }, OUT_OF_MATCH_CONFIRM_DELAY);
+    };
+
+    const leaveMatch = () => {
+      const prevPhase = gamePhaseRef.current;
+      gamePhaseRef.current = null;
+
+      if (prevPhase === "pregame" && autoUnqueueRef.current) {
+        addLog("info", `[Misc] Dodge detected (phase: ${prevPhase} → none) — waiting for confirmed out-of-match`);
+        pendingUnqueueRef.current = true;
+      } else if (prevPhase === "pregame") {
+        addLog("info", `[Misc] Dodge detected but auto-unqueue is off`);
+      }
+      if (prevPhase === "ingame" && autoRequeueRef.current) {
+        addLog("info", `[Misc] Match ended (phase: ${prevPhase} → none) — waiting for confirmed out-of-match`);
+        pendingRequeueRef.current = true;
+      } else if (prevPhase === "ingame") {
+        addLog("info", `[Misc] Match ended but auto-requeue is off`);
+      }
+      if (pendingUnqueueRef.current || pendingRequeueRef.current) confirmOutOfMatch();
+
+      logOnce("not_in_match", "info", "Not in a match");
+      setPregameMatchId(null);
+      lockedAgentNameRef.current = null;
+      rpcMatchInfoRef.current = null;
+    };
+
+    const applyMatch = (phase, matchId, match) => {
+      if (!match) return;
+      gamePhaseRef.current = phase;
+      logOnce(`match:${matchId}:${phase}`, "match", `[${phase.toUpperCase()}] Match ${matchId} — Map: ${match.MapID}`, JSON.stringify(match));
+
+      if (phase === "ingame") {
+        setPregameMatchId(null);
+        updateRpcInfo(match);
+        return;
       }
-      if (!cancelled) setTimeout(poll, MATCH_POLL_INTERVAL);
+      rpcMatchInfoRef.current = null;
+      setPregameMatchId(matchId);
+    };
+
+    const onPhase = ({ phase, match_id, payload }) => {
+      if (cancelled) return;
+      if (phase === "pregame" || phase === "ingame") {
+        applyMatch(phase, match_id, payload);
+      } else {
+        leaveMatch();
+      }
+    };
+
+    const unlisteners = [
+      listen("phase-changed", (e) => onPhase(e.payload)),
+      listen("dodge-decision", (e) => {
+        if (!cancelled) onDodge(e.payload);
+      }),
+      listen("agent-select-decision", (e) => {
+        if (!cancelled) onDecision(e.payload);
+      }),
+      listen("match-updated", (e) => {
+        if (cancelled || !e.payload.payload) return;
+        if (e.payload.phase === "ingame") updateRpcInfo(e.payload.payload);
+      }),
+    ];
+
+    invoke("get_game_phase")
+      .then((snap) => {
+        if (snap.phase === "pregame" || snap.phase === "ingame") onPhase(snap);
+      })
+      .catch(() => {});
+
+    return () => {
+      cancelled = true;
+      unlisteners.forEach((u) => u.then((fn) => fn()));
     };
-    poll();
-    return () => { cancelled = true; };
   }, [instalockActive, mapDodgeActive, status, addLog]);
 
   useEffect(() => { splooshimaApiKeyRef.current = splooshimaApiKey; }, [splooshimaApiKey]);
@@ -676,16 +699,15 @@ export default function App() {
   useEffect(() => {
     if (status !== "connected") return;
     let cancelled = false;
-    const PREFETCH_INTERVAL = 3000;
     const HENRIK_WAIT = 3000;
 
-    const prefetch = async () => {
-      if (cancelled) return;
+    const prefetch = async (snap) => {
+      if (cancelled || !snap?.payload || !snap.match_id) return;
+      if (snap.phase !== "pregame" && snap.phase !== "ingame") return;
       try {
-        const raw = await invoke("check_current_game");
-        const match = JSON.parse(raw);
-        const matchId = match.ID || match.MatchID;
-        const phase = match._phase === "pregame" ? "PREGAME" : "INGAME";
+        const match = snap.payload;
+        const matchId = snap.match_id;
+        const phase = snap.phase === "pregame" ? "PREGAME" : "INGAME";
         const key = `${matchId}_${phase}`;
         if (prefetchedMatchRef.current === key) return;
 
@@ -727,7 +749,7 @@ export default function App() {
             });
             addLog("info", `[Prefetch] Splooshima resolved ${sData.found ?? 0}/${sData.requested ?? 0}`);
           } catch (e) {
-            addLog("error", `[Prefetch] Splooshima failed: ${e}`);
+            addLog("error", `[Prefetch] Splooshima failed: ${e?.message ?? e}`);
           }
         }
 
@@ -783,9 +805,9 @@ export default function App() {
       } catch {}
     };
 
-    prefetch();
-    const timer = setInterval(prefetch, PREFETCH_INTERVAL);
-    return () => { cancelled = true; clearInterval(timer); };
+    const unlisten = listen("phase-changed", (e) => prefetch(e.payload));
+    invoke("get_game_phase").then(prefetch).catch(() => {});
+    return () => { cancelled = true; unlisten.then((fn) => fn()); };
   }, [status, addLog]);
 
   const handleDodge = async () => {
@@ -794,7 +816,6 @@ export default function App() {
       await invoke("pregame_quit", { matchId: pregameMatchId });
       addLog("info", `Dodged match ${pregameMatchId}`);
       setPregameMatchId(null);
-      lockedMatchRef.current = null;
     } catch (err) {
       const msg = typeof err === "string" ? err : err?.message || "Dodge failed";
       addLog("error", `Dodge failed: ${msg}`);
@@ -812,39 +833,6 @@ export default function App() {
       } : undefined}
     >
       <TitleBar simplifiedTheme={simplifiedTheme} minimizeToTray={minimizeToTray} />
-      {!nodeInstalled && (
-        <div className="fixed inset-0 z-[200] flex items-center justify-center bg-black/80 backdrop-blur-sm">
-          <div className="max-w-sm p-6 rounded-xl bg-base-700 border border-border shadow-2xl text-center space-y-4">
-            <div className="w-14 h-14 mx-auto rounded-full bg-val-red/10 border border-val-red/20 flex items-center justify-center">
-              <svg width="26" height="26" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round" className="text-val-red">
-                <circle cx="12" cy="12" r="10" />
-                <line x1="12" y1="8" x2="12" y2="12" />
-                <line x1="12" y1="16" x2="12.01" y2="16" />
-              </svg>
-            </div>
-            <h2 className="text-base font-display font-bold text-text-primary">Node.js Required</h2>
-            <p className="text-xs font-body text-text-muted leading-relaxed">
-              Valorant Thing requires Node.js to communicate with Riot's APIs. Install Node.js, then restart the app.
-            </p>
-            <div className="flex items-center justify-center gap-3 pt-1">
-              <button
-                onClick={() => import("@tauri-apps/plugin-shell").then(m => m.open("https://nodejs.org"))}
-                className="inline-flex items-center gap-2 px-4 py-2 rounded-lg border border-text-muted/20 text-xs font-display font-medium text-text-primary hover:border-text-muted/40 transition-colors cursor-pointer bg-transparent"
-              >
-                <svg width="14" height="14" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round"><path d="M18 13v6a2 2 0 01-2 2H5a2 2 0 01-2-2V8a2 2 0 012-2h6" /><polyline points="15 3 21 3 21 9" /><line x1="10" y1="14" x2="21" y2="3" /></svg>
-                Download Node.js
-              </button>
-              <button
-                onClick={() => window.location.reload()}
-                className="inline-flex items-center gap-2 px-4 py-2 rounded-lg border border-text-muted/20 text-xs font-display font-medium text-text-primary hover:border-text-muted/40 transition-colors cursor-pointer bg-transparent"
-              >
-                <svg width="14" height="14" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round"><polyline points="23 4 23 10 17 10" /><path d="M20.49 15a9 9 0 11-2.12-9.36L23 10" /></svg>
-                Restart App
-              </button>
-            </div>
-          </div>
-        </div>
-      )}
       <AnimatePresence>
       {updateInfo && showUpdateModal && (
         <motion.div key="update-overlay" initial={{ opacity: 0 }} animate={{ opacity: 1 }} exit={{ opacity: 0 }} transition={{ duration: 0.2 }} className="fixed inset-0 z-[150] flex items-center justify-center bg-black/80 backdrop-blur-md">
@@ -977,7 +965,7 @@ export default function App() {
             <motion.div key="instalock" className="flex-1 flex min-h-0" initial={{ opacity: 0, y: 6 }} animate={{ opacity: 1, y: 0 }} exit={{ opacity: 0, y: -6 }} transition={{ duration: 0.15, ease: "easeOut" }}>
             <InstalockPage
               onActiveChange={setInstalockActive}
-              onConfigChange={(cfg) => { instalockConfigRef.current = cfg; }}
+              onConfigChange={(cfg) => { instalockConfigRef.current = cfg; setAgentRules((r) => ({ ...r, ...buildAgentRules(cfg) })); }}
               connected={status === "connected"}
             />
             </motion.div>
@@ -991,7 +979,7 @@ export default function App() {
             <motion.div key="mapdodge" className="flex-1 flex min-h-0" initial={{ opacity: 0, y: 6 }} animate={{ opacity: 1, y: 0 }} exit={{ opacity: 0, y: -6 }} transition={{ duration: 0.15, ease: "easeOut" }}>
             <MapDodgePage
               onActiveChange={setMapDodgeActive}
-              onBlacklistChange={(cfg) => { mapDodgeRef.current = cfg; }}
+              onConfigChange={(cfg) => { mapDodgeRef.current = cfg; }}
               connected={status === "connected"}
             />
             </motion.div>
@@ -1111,7 +1099,7 @@ export default function App() {
                 <path d="M12 6v6l4 2" />
               </svg>
               <span className="text-[11px] font-mono text-text-muted">
-                Token has <span className="text-text-primary font-semibold">{formatTimeLeft(tokenAge)}</span> remaining
+                Token has <span className="text-text-primary font-semibold">{formatTimeLeft(tokenLeft)}</span> remaining
               </span>
             </div>
             <div className="flex gap-2">
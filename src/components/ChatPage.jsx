@@ -1,10 +1,12 @@
 import { useState, useEffect, useRef, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { motion, AnimatePresence } from "framer-motion";
 
 const noAnim = () => localStorage.getItem("disable_animations") === "true";
 const T0 = { duration: 0 };
 const POLL_INTERVAL = 2000;
+const EVENT_POLL_INTERVAL = 15000;
 
 const CONV_LABELS = {
   "ares-parties": "Party",
@@ -67,6 +69,15 @@ export default function ChatPage({ connected, addLog }) {
   const messagesEndRef = useRef(null);
   const cancelledRef = useRef(false);
   const prevMsgCountRef = useRef(0);
+  const [eventsLive, setEventsLive] = useState(false);
+
+  useEffect(() => {
+    if (!connected) { setEventsLive(false); return; }
+    const check = () => invoke("get_local_event_status").then((s) => setEventsLive(!!s?.connected)).catch(() => setEventsLive(false));
+    check();
+    const interval = setInterval(check, 10000);
+    return () => clearInterval(interval);
+  }, [connected]);
 
   const scrollToBottom = useCallback(() => {
     messagesEndRef.current?.scrollIntoView({ behavior: "smooth" });
@@ -149,18 +160,26 @@ export default function ChatPage({ connected, addLog }) {
     }
     setLoading(true);
     fetchConversations();
-    const interval = setInterval(fetchConversations, 5000);
-    return () => { cancelledRef.current = true; clearInterval(interval); };
-  }, [connected]);
+    const interval = setInterval(fetchConversations, eventsLive ? EVENT_POLL_INTERVAL : 5000);
+    const unlisten = listen("local-event", (e) => {
+      if (e.payload.topic === "OnJsonApiEvent_chat_v6_conversations") fetchConversations();
+    });
+    return () => { cancelledRef.current = true; clearInterval(interval); unlisten.then((fn) => fn()); };
+  }, [connected, eventsLive]);
 
   useEffect(() => {
     if (!activeCid || !connected) return;
     prevMsgCountRef.current = 0;
     fetchMessages();
     fetchParticipants();
-    const interval = setInterval(fetchMessages, POLL_INTERVAL);
-    return () => clearInterval(interval);
-  }, [activeCid, connected]);
+    const interval = setInterval(fetchMessages, eventsLive ? EVENT_POLL_INTERVAL : POLL_INTERVAL);
+    const unlisten = listen("local-event", (e) => {
+      if (e.payload.topic === "OnJsonApiEvent_chat_v6_messages" && (e.payload.data?.messages || []).some((m) => m.cid === activeCid)) {
+        fetchMessages();
+      }
+    });
+    return () => { clearInterval(interval); unlisten.then((fn) => fn()); };
+  }, [activeCid, connected, eventsLive]);
 
   const handleSend = async () => {
     const text = input.trim();
@@ -174,7 +193,7 @@ export default function ChatPage({ connected, addLog }) {
       addLog?.("info", `[Chat] Sent message to ${getConvType(convMapRef.current[activeCid]) || activeCid}`);
       await fetchMessages();
     } catch (e) {
-      addLog?.("error", `[Chat] Send failed: ${e}`);
+      addLog?.("error", `[Chat] Send failed: ${e?.message ?? e}`);
       setInput(text);
     }
     setSending(false);
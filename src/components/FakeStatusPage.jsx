@@ -1,5 +1,6 @@
 import { useState, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { motion } from "framer-motion";
 
 const TIER_UUID = "03621f52-342b-cf4e-4f86-9350a49c6d04";
@@ -305,6 +306,12 @@ export default function FakeStatusPage({ connected, showLogsSetting, onUnsavedCh
 
   useEffect(() => { fetchStatus(); }, []);
 
+  // The backend drops XMPP when the Riot Client closes and restores it after reconnecting.
+  useEffect(() => {
+    const unlisten = listen("connection-changed", () => { fetchStatus(); });
+    return () => { unlisten.then(fn => fn()); };
+  }, []);
+
   useEffect(() => {
     if (!hasResumed.current && xmppStatus?.connected) {
       hasResumed.current = true;
@@ -57,11 +57,23 @@ export default function HomePage({ connected, player, refreshKey, onRefresh }) {
   const [maps, setMaps] = useState({});
   const [matches, setMatches] = useState(null);
   const [matchLoading, setMatchLoading] = useState(false);
+  const [profiles, setProfiles] = useState(null);
+  const [viewing, setViewing] = useState(null);
   const lastFetchRef = useRef(0);
   const lastAutoRefresh = useRef(0);
 
   useEffect(() => { getMapData().then(setMaps); }, []);
 
+  useEffect(() => {
+    setStats(null);
+    setMatches(null);
+    setViewing(null);
+  }, [player?.puuid]);
+
+  useEffect(() => {
+    invoke("list_profiles").then(setProfiles).catch(() => {});
+  }, [connected, stats]);
+
   const fetchStats = useCallback(async () => {
     if (!connected) return;
     setLoading(true);
@@ -148,20 +160,26 @@ export default function HomePage({ connected, player, refreshKey, onRefresh }) {
     );
   }
 
-  const cardSmall = stats?.cardId ? `https://media.valorant-api.com/playercards/${stats.cardId}/smallart.png` : player?.player_card_url;
-  const cardWide = stats?.cardId ? `https://media.valorant-api.com/playercards/${stats.cardId}/wideart.png` : null;
-  const level = stats?.level || 0;
-  const gameName = player?.game_name || "Player";
-  const gameTag = player?.game_tag || "0000";
-
-  const currentTier = stats?.currentTier || 0;
-  const currentRR = stats?.currentRR || 0;
-  const peakTier = stats?.peakTier || 0;
-  const wins = stats?.wins || 0;
-  const losses = stats?.losses || 0;
+  const viewed = viewing ? profiles?.profiles?.[viewing] : null;
+  const shown = viewed ? viewed.home_stats : stats;
+  const otherProfiles = Object.values(profiles?.profiles || {})
+    .filter(p => p.puuid !== player?.puuid)
+    .sort((a, b) => b.last_seen - a.last_seen);
+
+  const cardSmall = shown?.cardId ? `https://media.valorant-api.com/playercards/${shown.cardId}/smallart.png` : (viewed || player)?.player_card_url;
+  const cardWide = shown?.cardId ? `https://media.valorant-api.com/playercards/${shown.cardId}/wideart.png` : null;
+  const level = shown?.level || 0;
+  const gameName = (viewed || player)?.game_name || "Player";
+  const gameTag = (viewed || player)?.game_tag || "0000";
+
+  const currentTier = shown?.currentTier || 0;
+  const currentRR = shown?.currentRR || 0;
+  const peakTier = shown?.peakTier || 0;
+  const wins = shown?.wins || 0;
+  const losses = shown?.losses || 0;
   const totalPlayed = wins + losses;
   const winRate = totalPlayed > 0 ? Math.round((wins / totalPlayed) * 100) : 0;
-  const totalGames = stats?.totalGames || 0;
+  const totalGames = shown?.totalGames || 0;
 
   return (
     <div className="flex-1 flex flex-col min-h-0 overflow-y-auto">
@@ -204,11 +222,39 @@ export default function HomePage({ connected, player, refreshKey, onRefresh }) {
       </div>
 
       <div className="p-4 space-y-3">
-        {error && (
+        {otherProfiles.length > 0 && (
+          <div className="flex items-center gap-1.5 flex-wrap">
+            <span className="text-[10px] font-display font-medium text-text-muted uppercase tracking-wider mr-1">Accounts</span>
+            {[player, ...otherProfiles].filter(Boolean).map((p) => {
+              const active = p.puuid === player?.puuid;
+              const selected = active ? !viewing : viewing === p.puuid;
+              const tier = active ? stats?.currentTier : p.last_rank?.tier;
+              return (
+                <button
+                  key={p.puuid}
+                  onClick={() => setViewing(active ? null : p.puuid)}
+                  className={`flex items-center gap-1.5 px-2 py-1 rounded-md border text-xs font-body transition-colors ${selected ? "bg-base-600 border-border text-text-primary" : "border-transparent text-text-muted hover:text-text-primary"}`}
+                >
+                  {tier ? <img src={rankIcon(tier)} alt="" className="w-4 h-4" /> : null}
+                  <span>{p.game_name}<span className="text-text-muted/60">#{p.game_tag}</span></span>
+                  {active && <span className="w-1.5 h-1.5 rounded-full bg-status-green" />}
+                </button>
+              );
+            })}
+          </div>
+        )}
+
+        {viewed && (
+          <p className="text-[11px] font-body text-text-muted">
+            Cached stats from {viewed.last_rank ? new Date(viewed.last_rank.updated_at * 1000).toLocaleString() : "a previous session"}. Sign in to this account to refresh.
+          </p>
+        )}
+
+        {error && !viewed && (
           <div className="px-3 py-2 rounded-lg bg-status-red/10 border border-status-red/20 text-xs font-body text-status-red">{error}</div>
         )}
 
-        {loading && !stats && (
+        {loading && !shown && (
           <div className="grid grid-cols-4 gap-3 animate-pulse">
             {[0, 1, 2, 3].map((i) => (
               <div key={i} className="p-3 rounded-xl bg-base-700 border border-border space-y-2">
@@ -225,7 +271,7 @@ export default function HomePage({ connected, player, refreshKey, onRefresh }) {
           </div>
         )}
 
-        {stats && (
+        {shown && (
           <motion.div initial="hidden" animate="show" variants={{ hidden: {}, show: { transition: { staggerChildren: 0.06 } } }} className="grid grid-cols-4 gap-3">
             <StatCard label="Current Rank" loading={loading}>
               <div className="flex items-center gap-2.5">
@@ -256,9 +302,9 @@ export default function HomePage({ connected, player, refreshKey, onRefresh }) {
           </motion.div>
         )}
 
-        <h3 className="text-xs font-display font-semibold text-text-primary uppercase tracking-wider">Match History</h3>
+        {!viewed && <h3 className="text-xs font-display font-semibold text-text-primary uppercase tracking-wider">Match History</h3>}
 
-        {matchLoading && !matches && (
+        {!viewed && matchLoading && !matches && (
           <div className="space-y-1.5 animate-pulse">
             {[0, 1, 2, 3, 4].map((i) => (
               <div key={i} className="h-14 rounded-lg bg-base-700 border border-border flex items-center px-3 gap-3">
@@ -278,7 +324,7 @@ export default function HomePage({ connected, player, refreshKey, onRefresh }) {
         )}
 
         <div className={`space-y-1.5 ${matchLoading ? "opacity-60 pointer-events-none" : ""}`}>
-          {(matches || []).map((m, i) => {
+          {(viewed ? [] : matches || []).map((m, i) => {
             const delay = Math.min(i * 0.03, 0.5);
             const mapData = maps[m.map];
             const mapName = mapData?.name || m.map;
@@ -43,26 +43,79 @@ const AGENT_SILHOUETTE = (
 
 const NONE_AGENT = { uuid: "none", displayName: "None", displayIcon: null };
 
-const CONFIG_KEY = "instalock-config";
-
-function saveConfig(selectedAgent, perMapSelections, active) {
-  const slim = (a) => a ? { uuid: a.uuid, displayName: a.displayName, displayIcon: a.displayIcon } : null;
+const LEGACY_CONFIG_KEY = "instalock-config";
+const LEGACY_DELAY_KEYS = ["instalock_select_delay", "instalock_lock_delay"];
+const ROLES = ["controller", "initiator", "sentinel", "duelist"];
+
+// Turns the page's config into the agent parts of the backend `AgentRules`. Maps are keyed by
+// `mapUrl` (the pregame `MapID`); "None" becomes an empty list, which skips the map.
+export function buildAgentRules(cfg) {
+  const fallbacks = (cfg.fallbacks || []).map((a) => a.uuid);
   const perMap = {};
-  for (const [mapId, agent] of Object.entries(perMapSelections)) {
-    perMap[mapId] = slim(agent);
+  for (const map of cfg.maps || []) {
+    const agent = cfg.perMapSelections?.[map.uuid];
+    if (agent && map.mapUrl) perMap[map.mapUrl] = agent.uuid === "none" ? [] : [agent.uuid];
   }
-  localStorage.setItem(CONFIG_KEY, JSON.stringify({
-    defaultAgent: slim(selectedAgent),
-    perMap,
-    active,
-  }));
+  const selected = cfg.selectedAgent;
+  return {
+    default: !selected ? fallbacks : selected.uuid === "none" ? [] : [selected.uuid],
+    per_map: perMap,
+    fallbacks,
+    role_goals: cfg.roleGoals || [],
+  };
 }
 
-function loadConfig() {
-  try {
-    const raw = localStorage.getItem(CONFIG_KEY);
-    return raw ? JSON.parse(raw) : null;
-  } catch { return null; }
+// Reverse of `buildAgentRules`, resolving UUIDs against the valorant-api.com agents and maps.
+export function parseAgentRules(rules, agents, maps) {
+  const find = (uuid) => agents.find((a) => a.uuid.toLowerCase() === uuid) || null;
+  const fallbackIds = rules?.fallbacks || [];
+  const fallbacks = fallbackIds.map(find).filter(Boolean);
+  const def = rules?.default || [];
+  let selectedAgent = null;
+  if (!def.length) selectedAgent = fallbacks.length ? NONE_AGENT : null;
+  else if (def.join() !== fallbackIds.join()) selectedAgent = find(def[0]);
+  const perMapSelections = {};
+  for (const map of maps) {
+    const list = rules?.per_map?.[map.mapUrl];
+    if (!list) continue;
+    const agent = list.length ? find(list[0]) : NONE_AGENT;
+    if (agent) perMapSelections[map.uuid] = agent;
+  }
+  return {
+    selectedAgent,
+    perMapSelections,
+    fallbacks,
+    roleGoals: (rules?.role_goals || []).filter((r) => ROLES.includes(r)),
+    active: !!rules?.enabled,
+  };
+}
+
+// The backend's saved rules. Config and delays left in localStorage by older versions are pushed once, then dropped.
+export async function loadAgentRules() {
+  const legacy = (() => { try { return JSON.parse(localStorage.getItem(LEGACY_CONFIG_KEY)); } catch { return null; } })();
+  const [selectDelay, lockDelay] = LEGACY_DELAY_KEYS.map((k) => localStorage.getItem(k));
+  if (legacy || selectDelay !== null || lockDelay !== null) {
+    const rules = await invoke("get_agent_select_rules");
+    if (legacy) {
+      const res = await fetch("https://valorant-api.com/v1/maps").then((r) => r.json());
+      const perMapSelections = {};
+      for (const [mapId, saved] of Object.entries(legacy.perMap || {})) {
+        if (saved) perMapSelections[mapId] = saved;
+      }
+      Object.assign(rules, buildAgentRules({
+        maps: res.data || [],
+        selectedAgent: legacy.defaultAgent || null,
+        perMapSelections,
+        fallbacks: (legacy.fallbacks || []).filter(Boolean),
+        roleGoals: legacy.roleGoals || [],
+      }), { enabled: !!legacy.active });
+    }
+    if (selectDelay !== null) rules.select_delay_ms = parseInt(selectDelay, 10) || 0;
+    if (lockDelay !== null) rules.lock_delay_ms = parseInt(lockDelay, 10) || 0;
+    await invoke("set_agent_select_rules", { rules });
+    [LEGACY_CONFIG_KEY, ...LEGACY_DELAY_KEYS].forEach((k) => localStorage.removeItem(k));
+  }
+  return invoke("get_agent_select_rules");
 }
 
 export default function InstalockPage({ onActiveChange, onConfigChange, connected }) {
@@ -73,6 +126,8 @@ export default function InstalockPage({ onActiveChange, onConfigChange, connecte
   const [selectedAgent, setSelectedAgent] = useState(null);
   const [selectedMap, setSelectedMap] = useState(null);
   const [perMapSelections, setPerMapSelections] = useState({});
+  const [fallbacks, setFallbacks] = useState([]);
+  const [roleGoals, setRoleGoals] = useState([]);
   const [active, setActive] = useState(false);
   const [loading, setLoading] = useState(true);
   const [ownedAgents, setOwnedAgents] = useState(null);
@@ -82,6 +137,7 @@ export default function InstalockPage({ onActiveChange, onConfigChange, connecte
     invoke("get_owned_agents")
       .then((ids) => setOwnedAgents(new Set(ids)))
       .catch(() => setOwnedAgents(null));
+    invoke("refresh_agent_roles").catch(() => {});
   }, []);
 
   useEffect(() => {
@@ -101,41 +157,25 @@ export default function InstalockPage({ onActiveChange, onConfigChange, connecte
         );
         setMaps(playable);
 
-        const cfg = loadConfig();
-        if (cfg) {
-          const findAgent = (saved) => {
-            if (!saved) return null;
-            if (saved.uuid === "none") return NONE_AGENT;
-            return sorted.find((a) => a.uuid === saved.uuid) || null;
-          };
-          if (cfg.defaultAgent) setSelectedAgent(findAgent(cfg.defaultAgent));
-          if (cfg.perMap) {
-            const restored = {};
-            for (const [mapId, saved] of Object.entries(cfg.perMap)) {
-              const agent = findAgent(saved);
-              if (agent) restored[mapId] = agent;
-              else if (saved?.uuid === "none") restored[mapId] = NONE_AGENT;
-            }
-            setPerMapSelections(restored);
-          }
-          if (cfg.active) {
-            setActive(true);
-            onActiveChange?.(true);
-          }
+        return loadAgentRules().then((rules) => {
+          const cfg = parseAgentRules(rules, sorted, playable);
+          setSelectedAgent(cfg.selectedAgent);
+          setPerMapSelections(cfg.perMapSelections);
+          setFallbacks(cfg.fallbacks);
+          setRoleGoals(cfg.roleGoals);
+          setActive(cfg.active);
+          onActiveChange?.(cfg.active);
           configLoaded.current = true;
-        }
+        });
       })
       .catch((e) => console.error("[instalock] fetch failed:", e))
       .finally(() => setLoading(false));
   }, []);
 
   useEffect(() => {
-    if (!loading) saveConfig(selectedAgent, perMapSelections, active);
-  }, [selectedAgent, perMapSelections, active, loading]);
-
-  useEffect(() => {
-    if (!loading) onConfigChange?.({ maps, selectedAgent, perMapSelections });
-  }, [maps, selectedAgent, perMapSelections, loading]);
+    // App saves what this reports; never with defaults when the saved rules could not be read.
+    if (!loading && configLoaded.current) onConfigChange?.({ maps, selectedAgent, perMapSelections, fallbacks, roleGoals });
+  }, [maps, selectedAgent, perMapSelections, fallbacks, roleGoals, loading]);
 
   const FREE_AGENTS = new Set(["brimstone", "jett", "phoenix", "sage", "sova"]);
   const isOwned = (agent) => !ownedAgents || ownedAgents.has(agent.uuid.toLowerCase()) || FREE_AGENTS.has(agent.displayName.toLowerCase());
@@ -170,6 +210,17 @@ export default function InstalockPage({ onActiveChange, onConfigChange, connecte
     }
   };
 
+  const handleFallbackToggle = (agent) => {
+    if (!isOwned(agent)) return;
+    setFallbacks((prev) => prev.some((a) => a.uuid === agent.uuid)
+      ? prev.filter((a) => a.uuid !== agent.uuid)
+      : [...prev, agent]);
+  };
+
+  const handleRoleToggle = (role) => {
+    setRoleGoals((prev) => prev.includes(role) ? prev.filter((r) => r !== role) : [...prev, role]);
+  };
+
   const handleNoneClick = () => {
     if (!selectedMap) return;
     setPerMapSelections((prev) => {
@@ -274,7 +325,11 @@ export default function InstalockPage({ onActiveChange, onConfigChange, connecte
           <AllMapsView
             agents={filteredAgents}
             selectedAgent={selectedAgent}
+            fallbacks={fallbacks}
             onAgentClick={handleAgentClick}
+            onFallbackToggle={handleFallbackToggle}
+            roleGoals={roleGoals}
+            onRoleToggle={handleRoleToggle}
             isOwned={isOwned}
           />
         ) : (
@@ -297,12 +352,38 @@ export default function InstalockPage({ onActiveChange, onConfigChange, connecte
   );
 }
 
-function AllMapsView({ agents, selectedAgent, onAgentClick, isOwned }) {
+function AllMapsView({ agents, selectedAgent, fallbacks, onAgentClick, onFallbackToggle, roleGoals, onRoleToggle, isOwned }) {
   return (
     <div>
-      <p className="text-text-secondary text-xs font-display tracking-wide mb-3">
-        Select Agent
-      </p>
+      <div className="flex items-center gap-1.5 mb-3">
+        <p className="text-text-secondary text-xs font-display tracking-wide mr-1">
+          Ensure team has
+        </p>
+        {ROLES.map((role) => {
+          const rank = roleGoals.indexOf(role) + 1;
+          return (
+            <button
+              key={role}
+              onClick={() => onRoleToggle(role)}
+              className={`px-2 py-0.5 rounded-md border text-[11px] font-display capitalize transition-colors duration-150 ${
+                rank ? "bg-accent-blue/10 border-accent-blue/60 text-accent-blue" : "border-border text-text-muted hover:text-text-secondary"
+              }`}
+            >
+              {rank ? `${rank}. ${role}` : role}
+            </button>
+          );
+        })}
+      </div>
+      <div className="flex items-baseline justify-between mb-3">
+        <p className="text-text-secondary text-xs font-display tracking-wide">
+          Select Agent
+        </p>
+        <p className="text-text-muted text-[11px] font-body">
+          {fallbacks.length > 0
+            ? `Fallbacks: ${fallbacks.map((a) => a.displayName).join(" → ")}`
+            : "Right-click agents to add fallbacks (used if taken or not owned)"}
+        </p>
+      </div>
       <div className="grid grid-cols-[repeat(auto-fill,minmax(80px,1fr))] gap-1.5">
         {agents.map((agent, i) => (
           <motion.div key={agent.uuid} initial={{ opacity: 0, scale: 0.9 }} animate={{ opacity: 1, scale: 1 }} transition={noAnim() ? T0 : { duration: 0.15, delay: Math.min(i * 0.02, 0.4) }}>
@@ -310,6 +391,8 @@ function AllMapsView({ agents, selectedAgent, onAgentClick, isOwned }) {
             agent={agent}
             selected={selectedAgent?.uuid === agent.uuid}
             onClick={() => onAgentClick(agent)}
+            onContextMenu={() => onFallbackToggle(agent)}
+            fallbackRank={fallbacks.findIndex((a) => a.uuid === agent.uuid) + 1 || null}
             owned={isOwned(agent)}
           />
           </motion.div>
@@ -409,13 +492,14 @@ function PerMapView({ agents, maps, search, selectedMap, onMapSelect, onMapBack,
   );
 }
 
-function AgentCard({ agent, selected, onClick, owned = true }) {
+function AgentCard({ agent, selected, onClick, onContextMenu, fallbackRank, owned = true }) {
   const [showTooltip, setShowTooltip] = useState(false);
 
   return (
     <div className="relative" onMouseEnter={() => !owned && setShowTooltip(true)} onMouseLeave={() => setShowTooltip(false)}>
       <button
         onClick={onClick}
+        onContextMenu={(e) => { if (!onContextMenu) return; e.preventDefault(); onContextMenu(); }}
         disabled={!owned}
         className={`group flex flex-col items-center gap-1 p-1.5 rounded-lg border transition-all duration-150 w-full ${
           !owned
@@ -441,6 +525,11 @@ function AgentCard({ agent, selected, onClick, owned = true }) {
           {agent.displayName}
         </span>
       </button>
+      {fallbackRank && (
+        <span className="absolute top-1 right-1 w-4 h-4 rounded-full bg-base-900 border border-border text-[9px] font-display text-text-secondary flex items-center justify-center pointer-events-none">
+          {fallbackRank}
+        </span>
+      )}
       {showTooltip && (
         <div className="absolute -top-8 left-1/2 -translate-x-1/2 px-2 py-1 rounded bg-base-900 border border-border text-[10px] font-body text-text-muted whitespace-nowrap z-10 pointer-events-none">
           Agent Locked
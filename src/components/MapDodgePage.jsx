@@ -1,8 +1,10 @@
-import { useState, useEffect, useMemo } from "react";
+import { useState, useEffect, useMemo, useRef } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { motion } from "framer-motion";
 
 const EXCLUDED_MAPS = ["The Range", "District", "Kasbah", "Drift", "Glitch", "Piazza", "Basic Training", "Skirmish A", "Skirmish B", "Skirmish C"];
-const CONFIG_KEY = "mapdodge-config";
+const LEGACY_CONFIG_KEY = "mapdodge-config";
 const noAnim = () => localStorage.getItem("disable_animations") === "true";
 const T0 = { duration: 0 };
 
@@ -13,52 +15,222 @@ const SEARCH_ICON = (
   </svg>
 );
 
-function loadConfig() {
-  try {
-    const raw = localStorage.getItem(CONFIG_KEY);
-    return raw ? JSON.parse(raw) : null;
-  } catch { return null; }
+const QUEUES = [
+  { id: "", label: "Any queue" },
+  { id: "competitive", label: "Competitive" },
+  { id: "unrated", label: "Unrated" },
+  { id: "swiftplay", label: "Swiftplay" },
+  { id: "premier", label: "Premier" },
+  { id: "spikerush", label: "Spike Rush" },
+  { id: "hurm", label: "Team Deathmatch" },
+];
+
+const PARTY = [
+  { id: "any", label: "Any party" },
+  { id: "solo", label: "Solo only" },
+  { id: "group", label: "In a party" },
+];
+
+const RANK_FILTERS = [
+  { id: "", label: "Any rank" },
+  { id: "ally:avg", label: "Ally avg" },
+  { id: "ally:max", label: "Ally max" },
+  { id: "enemy:avg", label: "Enemy avg" },
+  { id: "enemy:max", label: "Enemy max" },
+];
+
+const RANKS = [
+  { id: 3, label: "Iron 1" }, { id: 4, label: "Iron 2" }, { id: 5, label: "Iron 3" },
+  { id: 6, label: "Bronze 1" }, { id: 7, label: "Bronze 2" }, { id: 8, label: "Bronze 3" },
+  { id: 9, label: "Silver 1" }, { id: 10, label: "Silver 2" }, { id: 11, label: "Silver 3" },
+  { id: 12, label: "Gold 1" }, { id: 13, label: "Gold 2" }, { id: 14, label: "Gold 3" },
+  { id: 15, label: "Platinum 1" }, { id: 16, label: "Platinum 2" }, { id: 17, label: "Platinum 3" },
+  { id: 18, label: "Diamond 1" }, { id: 19, label: "Diamond 2" }, { id: 20, label: "Diamond 3" },
+  { id: 21, label: "Ascendant 1" }, { id: 22, label: "Ascendant 2" }, { id: 23, label: "Ascendant 3" },
+  { id: 24, label: "Immortal 1" }, { id: 25, label: "Immortal 2" }, { id: 26, label: "Immortal 3" },
+  { id: 27, label: "Radiant" },
+];
+
+const inputClass = "px-2 py-1 bg-base-800 border border-border rounded-lg text-xs font-body text-text-primary placeholder:text-text-muted/40 outline-none focus:border-border-light transition-colors";
+
+// Reverse of `buildDodgeConfig`: the backend `DodgeConfig` as the page's config.
+export function parseDodgeConfig(config) {
+  const blacklist = new Set();
+  const rules = [];
+  (config?.rules || []).forEach((r, i) => {
+    if (r.name === "Blacklisted map" && !r.queues?.length && !r.ranks?.length && r.min_party_size == null && r.max_party_size == null) {
+      r.maps.forEach((m) => blacklist.add(m));
+      return;
+    }
+    const rank = r.ranks?.[0];
+    rules.push({
+      id: i + 1,
+      name: r.name,
+      map: r.maps?.[0] || "",
+      queue: r.queues?.[0] || "",
+      party: r.max_party_size === 1 ? "solo" : r.min_party_size === 2 ? "group" : "any",
+      rankFilter: rank ? `${rank.team}:${rank.stat}` : "",
+      rankOp: rank?.max_tier != null ? "lte" : "gte",
+      rankTier: rank?.min_tier ?? rank?.max_tier ?? 18,
+    });
+  });
+  return {
+    blacklist,
+    rules,
+    dryRun: !!config?.dry_run,
+    maxDodges: config?.max_dodges_per_day || 0,
+    maxPenalty: config?.max_penalty_rr || 0,
+    penaltyFirst: config?.penalty_rr_first || 0,
+    penaltyRepeat: config?.penalty_rr_repeat || 0,
+    active: !!config?.enabled,
+  };
 }
 
-function saveConfig(blacklist, active) {
-  localStorage.setItem(CONFIG_KEY, JSON.stringify({ blacklist: [...blacklist], active }));
+// The backend's saved config. A config left in localStorage by older versions is pushed once, then dropped.
+export async function loadDodgeConfig() {
+  const legacy = (() => { try { return JSON.parse(localStorage.getItem(LEGACY_CONFIG_KEY)); } catch { return null; } })();
+  if (legacy) {
+    await invoke("set_dodge_config", { config: buildDodgeConfig(legacy, !!legacy.active) });
+    localStorage.removeItem(LEGACY_CONFIG_KEY);
+  }
+  return parseDodgeConfig(await invoke("get_dodge_config"));
 }
 
-export default function MapDodgePage({ onActiveChange, onBlacklistChange, connected }) {
+// Turns the page's config into the backend `DodgeConfig`. Blacklisted maps become one unconditional rule.
+export function buildDodgeConfig(cfg, active) {
+  const rules = [];
+  const blacklist = [...(cfg?.blacklist || [])];
+  if (blacklist.length) rules.push({ name: "Blacklisted map", maps: blacklist, queues: [], ranks: [] });
+  for (const r of cfg?.rules || []) {
+    const [team, stat] = (r.rankFilter || "").split(":");
+    rules.push({
+      name: r.name || "Unnamed rule",
+      maps: r.map ? [r.map] : [],
+      queues: r.queue ? [r.queue] : [],
+      min_party_size: r.party === "group" ? 2 : null,
+      max_party_size: r.party === "solo" ? 1 : null,
+      ranks: team ? [{
+        team,
+        stat,
+        min_tier: r.rankOp === "gte" ? r.rankTier : null,
+        max_tier: r.rankOp === "lte" ? r.rankTier : null,
+      }] : [],
+    });
+  }
+  return {
+    enabled: active,
+    dry_run: !!cfg?.dryRun,
+    rules,
+    max_dodges_per_day: cfg?.maxDodges || null,
+    max_penalty_rr: cfg?.maxPenalty || null,
+    penalty_rr_first: cfg?.penaltyFirst || null,
+    penalty_rr_repeat: cfg?.penaltyRepeat || null,
+  };
+}
+
+function Select({ value, onChange, options }) {
+  const [open, setOpen] = useState(false);
+  const ref = useRef(null);
+  const selected = options.find((o) => o.id === value) || options[0];
+
+  useEffect(() => {
+    const handler = (e) => { if (ref.current && !ref.current.contains(e.target)) setOpen(false); };
+    document.addEventListener("mousedown", handler);
+    return () => document.removeEventListener("mousedown", handler);
+  }, []);
+
+  return (
+    <div className="relative" ref={ref}>
+      <button type="button" onClick={() => setOpen((v) => !v)}
+        className="w-full flex items-center gap-2 px-2 py-1 bg-base-800 border border-border rounded-lg text-xs font-body text-text-primary hover:border-border-light transition-colors"
+      >
+        <span className="flex-1 text-left truncate">{selected.label}</span>
+        <svg width="10" height="10" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" className={`text-text-muted transition-transform shrink-0 ${open ? "rotate-180" : ""}`}>
+          <path d="M6 9l6 6 6-6" />
+        </svg>
+      </button>
+      {open && (
+        <div className="absolute z-50 mt-1 left-0 right-0 bg-base-800 border border-border rounded-lg shadow-xl max-h-56 overflow-y-auto">
+          {options.map((o) => (
+            <button key={o.id} onClick={() => { onChange(o.id); setOpen(false); }}
+              className={`w-full text-left px-2 py-1 text-xs font-body hover:bg-base-600 transition-colors ${o.id === value ? "bg-base-600 text-text-primary" : "text-text-secondary"}`}
+            >
+              {o.label}
+            </button>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
+
+export default function MapDodgePage({ onActiveChange, onConfigChange, connected }) {
   const [maps, setMaps] = useState([]);
   const [search, setSearch] = useState("");
   const [blacklist, setBlacklist] = useState(new Set());
+  const [rules, setRules] = useState([]);
+  const [dryRun, setDryRun] = useState(false);
+  const [maxDodges, setMaxDodges] = useState(0);
+  const [maxPenalty, setMaxPenalty] = useState(0);
+  const [penaltyFirst, setPenaltyFirst] = useState(0);
+  const [penaltyRepeat, setPenaltyRepeat] = useState(0);
+  const [stats, setStats] = useState(null);
   const [active, setActive] = useState(false);
   const [loading, setLoading] = useState(true);
+  const configLoadedRef = useRef(false);
 
   useEffect(() => {
-    fetch("https://valorant-api.com/v1/maps")
+    const mapsLoaded = fetch("https://valorant-api.com/v1/maps")
       .then((r) => r.json())
       .then((res) => {
         const playable = (res.data || []).filter(
           (m) => !EXCLUDED_MAPS.includes(m.displayName)
         );
         setMaps(playable);
+      })
+      .catch((e) => console.error("[mapdodge] fetch failed:", e));
 
-        const cfg = loadConfig();
-        if (cfg) {
-          if (cfg.blacklist) setBlacklist(new Set(cfg.blacklist));
-          if (cfg.active) {
-            setActive(true);
-            onActiveChange?.(true);
-          }
-        }
+    const configLoaded = loadDodgeConfig()
+      .then((cfg) => {
+        setBlacklist(cfg.blacklist);
+        setRules(cfg.rules);
+        setDryRun(cfg.dryRun);
+        setMaxDodges(cfg.maxDodges);
+        setMaxPenalty(cfg.maxPenalty);
+        setPenaltyFirst(cfg.penaltyFirst);
+        setPenaltyRepeat(cfg.penaltyRepeat);
+        setActive(cfg.active);
+        onActiveChange?.(cfg.active);
+        configLoadedRef.current = true;
       })
-      .catch((e) => console.error("[mapdodge] fetch failed:", e))
-      .finally(() => setLoading(false));
+      .catch((e) => console.error("[mapdodge] config load failed:", e));
+
+    Promise.all([mapsLoaded, configLoaded]).finally(() => setLoading(false));
   }, []);
 
   useEffect(() => {
-    if (!loading) {
-      saveConfig(blacklist, active);
-      onBlacklistChange?.({ blacklist, maps });
-    }
-  }, [blacklist, active, loading]);
+    // Never save over the backend's config with defaults when it could not be read.
+    if (loading || !configLoadedRef.current) return;
+    const cfg = { blacklist, rules, dryRun, maxDodges, maxPenalty, penaltyFirst, penaltyRepeat };
+    invoke("set_dodge_config", { config: buildDodgeConfig(cfg, active) })
+      .catch((e) => console.error("[mapdodge] config save failed:", e));
+    onConfigChange?.({ ...cfg, maps });
+  }, [blacklist, rules, dryRun, maxDodges, maxPenalty, penaltyFirst, penaltyRepeat, active, loading]);
+
+  useEffect(() => {
+    if (!connected) return;
+    const refresh = () => invoke("get_dodge_stats").then(setStats).catch(() => {});
+    refresh();
+    const unlisten = listen("dodge-decision", refresh);
+    return () => { unlisten.then((fn) => fn()); };
+  }, [connected]);
+
+  const addRule = () => setRules((prev) => [...prev, {
+    id: Date.now(), name: `Rule ${prev.length + 1}`, map: "", queue: "competitive", party: "any", rankFilter: "", rankOp: "gte", rankTier: 18,
+  }]);
+  const updateRule = (id, key, value) => setRules((prev) => prev.map((r) => r.id === id ? { ...r, [key]: value } : r));
+  const removeRule = (id) => setRules((prev) => prev.filter((r) => r.id !== id));
+  const mapOptions = useMemo(() => [{ id: "", label: "Any map" }, ...maps.map((m) => ({ id: m.mapUrl, label: m.displayName }))], [maps]);
 
   const filteredMaps = useMemo(() => {
     if (!search.trim()) return maps;
@@ -116,8 +288,19 @@ export default function MapDodgePage({ onActiveChange, onBlacklistChange, connec
 
         <span className="text-xs font-body text-text-muted">
           {blacklist.size} map{blacklist.size !== 1 ? "s" : ""} blacklisted
+          {rules.length > 0 && ` · ${rules.length} rule${rules.length !== 1 ? "s" : ""}`}
         </span>
 
+        <button
+          onClick={() => setDryRun((v) => !v)}
+          title="Log which rule would fire without leaving the match"
+          className={`px-2 py-1 rounded-md border text-[11px] font-display transition-colors duration-150 ${
+            dryRun ? "bg-accent-blue/10 border-accent-blue/60 text-accent-blue" : "border-border text-text-muted hover:text-text-secondary"
+          }`}
+        >
+          Dry run
+        </button>
+
         <div className="flex items-center gap-2 ml-1">
           <span className={`text-xs font-display tracking-wide ${!connected ? "text-text-muted" : active ? "text-status-green" : "text-text-muted"}`}>
             {!connected ? "Off" : active ? "Active" : "Inactive"}
@@ -151,6 +334,62 @@ export default function MapDodgePage({ onActiveChange, onBlacklistChange, connec
             </motion.div>
           ))}
         </div>
+
+        <div className="flex items-center gap-2 mt-5 mb-3">
+          <p className="text-text-secondary text-xs font-display tracking-wide">
+            Conditional rules — first match dodges
+          </p>
+          <div className="flex-1" />
+          <button onClick={addRule} className="px-2 py-1 rounded-md border border-border text-[11px] font-display text-text-secondary hover:text-text-primary hover:border-border-light transition-colors">
+            + Add rule
+          </button>
+        </div>
+        <div className="flex flex-col gap-2">
+          {rules.map((rule) => (
+            <div key={rule.id} className="grid grid-cols-[1fr_1fr_1fr_1fr_1fr_auto] gap-2 items-center p-2 rounded-lg bg-base-700 border border-border">
+              <input value={rule.name} onChange={(e) => updateRule(rule.id, "name", e.target.value)} placeholder="Rule name" className={inputClass} />
+              <Select value={rule.map} onChange={(v) => updateRule(rule.id, "map", v)} options={mapOptions} />
+              <Select value={rule.queue} onChange={(v) => updateRule(rule.id, "queue", v)} options={QUEUES} />
+              <Select value={rule.party} onChange={(v) => updateRule(rule.id, "party", v)} options={PARTY} />
+              <div className="flex gap-1">
+                <div className="flex-1"><Select value={rule.rankFilter} onChange={(v) => updateRule(rule.id, "rankFilter", v)} options={RANK_FILTERS} /></div>
+                {rule.rankFilter && (
+                  <>
+                    <button onClick={() => updateRule(rule.id, "rankOp", rule.rankOp === "gte" ? "lte" : "gte")} className="px-1.5 rounded-md border border-border text-xs text-text-secondary hover:text-text-primary">
+                      {rule.rankOp === "gte" ? "≥" : "≤"}
+                    </button>
+                    <div className="flex-1"><Select value={rule.rankTier} onChange={(v) => updateRule(rule.id, "rankTier", v)} options={RANKS} /></div>
+                  </>
+                )}
+              </div>
+              <button onClick={() => removeRule(rule.id)} className="px-1.5 text-text-muted hover:text-val-red text-xs transition-colors" title="Remove rule">✕</button>
+            </div>
+          ))}
+          {rules.length === 0 && (
+            <p className="text-text-muted text-[11px] font-body italic">
+              e.g. dodge Breeze in Competitive only when solo, or when the ally average is Diamond 1 or above.
+            </p>
+          )}
+        </div>
+
+        <div className="flex items-center gap-3 mt-5 p-2 rounded-lg bg-base-700 border border-border text-xs font-body text-text-secondary">
+          <span>Stop dodging after</span>
+          <input type="number" min={0} value={maxDodges} onChange={(e) => setMaxDodges(Math.max(0, Number(e.target.value) || 0))} className={`${inputClass} w-14`} />
+          <span>dodges or</span>
+          <input type="number" min={0} value={maxPenalty} onChange={(e) => setMaxPenalty(Math.max(0, Number(e.target.value) || 0))} className={`${inputClass} w-14`} />
+          <span>RR penalty per 24h (0 = no limit)</span>
+          <span className="ml-2" title="Competitive dodge penalty used for the RR limit (0 = default, 3 then 10)">Penalty</span>
+          <input type="number" min={0} value={penaltyFirst} placeholder="3" onChange={(e) => setPenaltyFirst(Math.max(0, Number(e.target.value) || 0))} className={`${inputClass} w-14`} />
+          <span>RR first,</span>
+          <input type="number" min={0} value={penaltyRepeat} placeholder="10" onChange={(e) => setPenaltyRepeat(Math.max(0, Number(e.target.value) || 0))} className={`${inputClass} w-14`} />
+          <span>RR after</span>
+          <div className="flex-1" />
+          {stats && (
+            <span className="text-text-muted">
+              Today: {stats.dodges_today} dodge{stats.dodges_today !== 1 ? "s" : ""}, {stats.penalty_rr_today} RR
+            </span>
+          )}
+        </div>
       </div>
     </div>
   );
@@ -11,6 +11,8 @@ const MAPS_URL = "https://valorant-api.com/v1/maps";
 const POLL_INTERVAL = 2000;
 const HENRIK_RATE_WAIT = 3000;
 const sleep = (ms) => new Promise((r) => setTimeout(r, ms));
+const PARTY_COLORS = ["bg-accent-blue/20 text-accent-blue", "bg-status-yellow/20 text-status-yellow", "bg-status-green/20 text-status-green", "bg-val-red/20 text-val-red"];
+const FEATURED_WEAPONS = ["Vandal", "Phantom", "Operator", "Sheriff", "Melee"];
 
 export default function MatchInfoPage({ henrikApiKey, splooshimaApiKey, splooshimaAvailable, player: selfPlayer, connected, addLog }) {
   const myPuuid = selfPlayer?.puuid;
@@ -25,6 +27,7 @@ export default function MatchInfoPage({ henrikApiKey, splooshimaApiKey, splooshi
   const [loading, setLoading] = useState(true);
   const [fetching, setFetching] = useState(false);
   const [error, setError] = useState(null);
+  const [loadouts, setLoadouts] = useState({});
   const fetchedMatchRef = useRef(null);
   const cancelledRef = useRef(false);
 
@@ -149,10 +152,36 @@ export default function MatchInfoPage({ henrikApiKey, splooshimaApiKey, splooshi
       setPlayers(withCached);
       setLoading(false);
 
-      const needsAccount = withCached.filter((p) => !p.account);
-
       setError(null);
-      if (needsAccount.length > 0) setFetching(true);
+      setFetching(true);
+
+      const roster = {};
+      try {
+        const res = await invoke("get_enriched_roster", { matchId });
+        if (cancelledRef.current) return;
+        res.players.forEach((r) => {
+          roster[r.puuid] = r;
+          if (r.name) setCache(r.puuid, "account", { name: r.name, tag: r.tag, account_level: r.account_level || null });
+          if (r.current_tier > 0) setCache(r.puuid, "mmr", { currenttier: r.current_tier, ranking_in_tier: r.current_rr, peaktier: r.peak_tier });
+        });
+        setPlayers((prev) => prev.map((p) => {
+          const r = roster[p.puuid];
+          if (!r) return p;
+          return {
+            ...p,
+            account: getCached(p.puuid, "account") || p.account,
+            mmr: getCached(p.puuid, "mmr") || p.mmr,
+            winRate: r.win_rate,
+            actGames: r.act_games,
+            partyGroup: r.party_group,
+            _loading: !r.name && p._loading,
+          };
+        }));
+      } catch (e) {
+        addLog?.("error", `[MatchInfo] Roster lookup failed — falling back`, { error: String(e?.message ?? e) });
+      }
+
+      const needsAccount = withCached.filter((p) => !p.account && !roster[p.puuid]?.name);
 
       if (needsAccount.length > 0) {
         const puuidsToResolve = needsAccount.map((p) => p.puuid);
@@ -181,7 +210,7 @@ export default function MatchInfoPage({ henrikApiKey, splooshimaApiKey, splooshi
             });
             unresolvedPuuids = unresolvedPuuids.filter((id) => !resolved[id]);
           } catch (e) {
-            addLog?.("error", `[Splooshima] Lookup failed — falling back`, { error: String(e) });
+            addLog?.("error", `[Splooshima] Lookup failed — falling back`, { error: String(e?.message ?? e) });
           }
         }
 
@@ -200,7 +229,7 @@ export default function MatchInfoPage({ henrikApiKey, splooshimaApiKey, splooshi
             addLog?.("info", `[Henrik] Account resolved for ${puuid.slice(0, 8)}…`, j);
             return j.data ? { puuid, name: j.data.name, tag: j.data.tag, account_level: j.data.account_level } : null;
           } catch (e) {
-            addLog?.("error", `[Henrik] Account lookup failed for ${puuid.slice(0, 8)}…`, { error: String(e) });
+            addLog?.("error", `[Henrik] Account lookup failed for ${puuid.slice(0, 8)}…`, { error: String(e?.message ?? e) });
             return null;
           }
         };
@@ -234,52 +263,16 @@ export default function MatchInfoPage({ henrikApiKey, splooshimaApiKey, splooshi
           }
         }
 
-        if (unresolvedPuuids.length > 0) {
-          try {
-            const raw = await invoke("resolve_player_names", { puuids: unresolvedPuuids });
-            if (cancelledRef.current) return;
-            const names = JSON.parse(raw);
-            names.forEach((n) => {
-              if (n.name) {
-                resolved[n.puuid] = n;
-                setCache(n.puuid, "account", n);
-              }
-            });
-          } catch {}
-        }
-
         setPlayers((prev) => prev.map((p) => {
           const r = resolved[p.puuid];
           const cachedMmr = getCached(p.puuid, "mmr") || null;
           return r ? { ...p, account: { ...p.account, ...r }, mmr: cachedMmr || p.mmr, _loading: false } : { ...p, mmr: cachedMmr || p.mmr, _loading: false };
         }));
-        setFetching(false);
       }
+      setFetching(false);
 
-      const needsMmr = withCached.filter((p) => !getCached(p.puuid, "mmr"));
-      if (needsMmr.length === 0) return;
-
-      const fetchMmr = (puuid) =>
-        invoke("get_player_mmr", { targetPuuid: puuid })
-          .then((raw) => {
-            const json = JSON.parse(raw);
-            const tier = json.currenttier || 0;
-            const rr = json.ranking_in_tier || 0;
-            if (tier === 0 && rr === 0) return { puuid, data: null, needsHenrik: true };
-            return { puuid, data: { currenttier: tier, ranking_in_tier: rr } };
-          })
-          .catch(() => ({ puuid, data: null, needsHenrik: true }));
-
-      let mmrResults = await Promise.all(needsMmr.map((p) => fetchMmr(p.puuid)));
-      if (cancelledRef.current) return;
-
-      mmrResults.filter((r) => r.data).forEach((r) => setCache(r.puuid, "mmr", r.data));
-      setPlayers((prev) => prev.map((p) => {
-        const r = mmrResults.find((a) => a.puuid === p.puuid);
-        return r?.data ? { ...p, mmr: r.data } : p;
-      }));
+      const mmrFailed = withCached.filter((p) => !getCached(p.puuid, "mmr")).map((p) => p.puuid);
 
-      const mmrFailed = mmrResults.filter((r) => r.needsHenrik).map((r) => r.puuid);
       if (mmrFailed.length > 0 && henrikApiKey) {
         const henrikMmrFetch = async (puuid) => {
           try {
@@ -303,7 +296,7 @@ export default function MatchInfoPage({ henrikApiKey, splooshimaApiKey, splooshi
             }
             return null;
           } catch (e) {
-            addLog?.("error", `[Henrik] MMR lookup failed for ${puuid.slice(0, 8)}…`, { error: String(e) });
+            addLog?.("error", `[Henrik] MMR lookup failed for ${puuid.slice(0, 8)}…`, { error: String(e?.message ?? e) });
             return null;
           }
         };
@@ -331,6 +324,24 @@ export default function MatchInfoPage({ henrikApiKey, splooshimaApiKey, splooshi
     }
   }, []);
 
+  useEffect(() => {
+    if (!matchId || !matchPhase) {
+      setLoadouts({});
+      return;
+    }
+    let cancelled = false;
+    invoke("get_match_loadouts", { matchId })
+      .then((res) => {
+        if (cancelled) return;
+        const m = {};
+        res.players.forEach((p) => { m[p.puuid] = p; });
+        setLoadouts(m);
+        if (!res.resolved) addLog?.("error", "[MatchInfo] Skin names unavailable, content index could not be built");
+      })
+      .catch((e) => { if (!cancelled) addLog?.("error", "[MatchInfo] Loadouts unavailable", { error: String(e?.message ?? e) }); });
+    return () => { cancelled = true; };
+  }, [matchId, matchPhase]);
+
   useEffect(() => {
     cancelledRef.current = false;
     setLoading(true);
@@ -428,7 +439,7 @@ export default function MatchInfoPage({ henrikApiKey, splooshimaApiKey, splooshi
               </p>
               {teamData.ally.map((p, i) => (
                 <motion.div key={p.puuid} initial={{ opacity: 0, y: 6 }} animate={{ opacity: 1, y: 0 }} transition={noAnim() ? T0 : { duration: 0.15, delay: i * 0.04 }}>
-                <PlayerCard player={p} agents={agents} tiers={tiers} isSelf={p.puuid === myPuuid} />
+                <PlayerCard player={p} agents={agents} tiers={tiers} loadout={loadouts[p.puuid]} isSelf={p.puuid === myPuuid} />
                 </motion.div>
               ))}
             </div>
@@ -439,7 +450,7 @@ export default function MatchInfoPage({ henrikApiKey, splooshimaApiKey, splooshi
               </p>
               {teamData.enemy.map((p, i) => (
                 <motion.div key={p.puuid} initial={{ opacity: 0, y: 6 }} animate={{ opacity: 1, y: 0 }} transition={noAnim() ? T0 : { duration: 0.15, delay: i * 0.04 }}>
-                <PlayerCard player={p} agents={agents} tiers={tiers} isSelf={p.puuid === myPuuid} />
+                <PlayerCard player={p} agents={agents} tiers={tiers} loadout={loadouts[p.puuid]} isSelf={p.puuid === myPuuid} />
                 </motion.div>
               ))}
             </div>
@@ -448,7 +459,7 @@ export default function MatchInfoPage({ henrikApiKey, splooshimaApiKey, splooshi
           <div className="space-y-1.5">
             {teamData.all.map((p, i) => (
               <motion.div key={p.puuid} initial={{ opacity: 0, y: 6 }} animate={{ opacity: 1, y: 0 }} transition={noAnim() ? T0 : { duration: 0.15, delay: i * 0.04 }}>
-              <PlayerCard player={p} agents={agents} tiers={tiers} isSelf={p.puuid === myPuuid} />
+              <PlayerCard player={p} agents={agents} tiers={tiers} loadout={loadouts[p.puuid]} isSelf={p.puuid === myPuuid} />
               </motion.div>
             ))}
           </div>
@@ -563,7 +574,7 @@ function MatchHeader({ mapId, maps, matchPhase, matchInfo, matchId, playerCount,
   );
 }
 
-function PlayerCard({ player, agents, tiers, isSelf }) {
+function PlayerCard({ player, agents, tiers, loadout, isSelf }) {
   const agent = agents[player.characterId?.toLowerCase()];
   const acct = player.account;
   const mmr = player.mmr;
@@ -611,6 +622,14 @@ function PlayerCard({ player, agents, tiers, isSelf }) {
               {isSelf && (
                 <span className="text-[9px] font-display font-bold text-val-red/70 uppercase tracking-wider ml-0.5">you</span>
               )}
+              {player.partyGroup != null && (
+                <span
+                  className={`text-[9px] font-display font-bold px-1 rounded ${PARTY_COLORS[(player.partyGroup - 1) % PARTY_COLORS.length]}`}
+                  title="Queued together"
+                >
+                  P{player.partyGroup}
+                </span>
+              )}
             </div>
             <div className="flex items-center gap-1.5 flex-wrap">
               <span className="text-[11px] font-body text-text-primary">
@@ -631,13 +650,47 @@ function PlayerCard({ player, agents, tiers, isSelf }) {
                   <span className="text-[11px] font-body text-text-primary">Peak:</span>
                   <img src={peakTierInfo.icon} alt="" className="w-3.5 h-3.5" />
                   <span className="text-[11px] font-display font-semibold text-text-primary">{peakTierInfo.name}</span>
-                  <span className="text-[11px] font-body text-text-primary/70">{mmr?.peak_rr ?? 0}RR</span>
+                  {mmr?.peak_rr != null && (
+                    <span className="text-[11px] font-body text-text-primary/70">{mmr.peak_rr}RR</span>
+                  )}
+                </>
+              )}
+              {player.winRate != null && (
+                <>
+                  <span className="text-[11px] text-text-primary/50">·</span>
+                  <span className="text-[11px] font-body text-text-primary" title={`${player.actGames} competitive games this act`}>
+                    {Math.round(player.winRate * 100)}% WR
+                  </span>
                 </>
               )}
             </div>
           </>
         )}
       </div>
+
+      {loadout && <SkinStrip loadout={loadout} />}
+    </div>
+  );
+}
+
+function SkinStrip({ loadout }) {
+  const featured = FEATURED_WEAPONS
+    .map((name) => loadout.weapons.find((w) => w.weapon.name === name))
+    // Default skins are not worth the space.
+    .filter((w) => w?.skin?.icon && !w.skin.name?.startsWith("Standard") && w.skin.name !== "Melee");
+  if (featured.length === 0) return null;
+
+  return (
+    <div className="flex items-center gap-1 shrink-0">
+      {featured.map((w) => (
+        <div
+          key={w.weapon.id}
+          title={[w.skin.name, w.chroma?.name !== w.skin.name && w.chroma?.name, w.buddy?.name && `Buddy: ${w.buddy.name}`].filter(Boolean).join("\n")}
+          className="w-12 h-7 rounded bg-base-600/60 flex items-center justify-center overflow-hidden"
+        >
+          <img src={w.chroma?.icon || w.skin.icon} alt="" className="max-w-full max-h-full object-contain" />
+        </div>
+      ))}
     </div>
   );
 }
@@ -134,7 +134,7 @@ export default function PartyPage({ connected, addLog, onRefresh }) {
       addLog?.("info", "[Party] Join by code succeeded");
       setShowJoin(false); setJoinCode(""); fetchParty();
     } catch (e) {
-      addLog?.("error", `[Party] Join by code failed: ${e}`);
+      addLog?.("error", `[Party] Join by code failed: ${e?.message ?? e}`);
     }
   };
 
@@ -151,7 +151,7 @@ export default function PartyPage({ connected, addLog, onRefresh }) {
         addLog?.("info", `[Friends] ${f.game_name}#${f.game_tag} status=${f.status} lv=${f.account_level} card=${!!f.player_card_url} product=${f.product}`);
       });
     } catch (e) {
-      addLog?.("error", `[Party] Failed to fetch friends: ${e}`);
+      addLog?.("error", `[Party] Failed to fetch friends: ${e?.message ?? e}`);
       setFriends([]);
     }
     setFriendsLoading(false);
@@ -172,7 +172,7 @@ export default function PartyPage({ connected, addLog, onRefresh }) {
       setInvitedPuuids(prev => new Set([...prev, friend.puuid]));
       addLog?.("info", `[Party] Invited ${friend.game_name}#${friend.game_tag}`);
     } catch (e) {
-      addLog?.("error", `[Party] Invite failed: ${e}`);
+      addLog?.("error", `[Party] Invite failed: ${e?.message ?? e}`);
     }
     setInvitingPuuid(null);
   };
@@ -192,7 +192,7 @@ export default function PartyPage({ connected, addLog, onRefresh }) {
       addLog?.("info", `[Party] Changed queue to ${queueId}`);
       fetchParty();
     } catch (e) {
-      addLog?.("error", `[Party] Queue change failed: ${e}`);
+      addLog?.("error", `[Party] Queue change failed: ${e?.message ?? e}`);
     }
     setChangingQueue(false);
     setShowQueuePicker(false);
@@ -203,7 +203,7 @@ export default function PartyPage({ connected, addLog, onRefresh }) {
       const raw = await invoke("get_custom_configs");
       setCustomConfigs(JSON.parse(raw));
     } catch (e) {
-      addLog?.("error", `[Party] Failed to fetch custom configs: ${e}`);
+      addLog?.("error", `[Party] Failed to fetch custom configs: ${e?.message ?? e}`);
     }
   };
 
@@ -227,7 +227,7 @@ export default function PartyPage({ connected, addLog, onRefresh }) {
       addLog?.("info", `[Custom] OK`);
       fetchParty();
     } catch (e) {
-      addLog?.("error", `[Custom] Failed: ${e}`);
+      addLog?.("error", `[Custom] Failed: ${e?.message ?? e}`);
     }
     setSavingCustom(false);
   };
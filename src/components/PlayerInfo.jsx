@@ -13,6 +13,12 @@ const STATUS_CONFIG = {
     textColor: "text-status-yellow",
     pulse: true,
   },
+  reconnecting: {
+    label: "Reconnecting",
+    color: "bg-status-yellow",
+    textColor: "text-status-yellow",
+    pulse: true,
+  },
   waiting: {
     label: "Waiting for game",
     color: "bg-status-yellow",
@@ -4,6 +4,7 @@ import { motion } from "framer-motion";
 import { open } from "@tauri-apps/plugin-shell";
 import { save } from "@tauri-apps/plugin-dialog";
 import { writeTextFile } from "@tauri-apps/plugin-fs";
+import { invoke } from "@tauri-apps/api/core";
 
 const noAnim = () => localStorage.getItem("disable_animations") === "true";
 const T0 = { duration: 0 };
@@ -146,14 +147,25 @@ function buildPreviewGradient(ct) {
   return `linear-gradient(${ct.angle}deg, ${sorted.map(s => `${s.color} ${s.pos}%`).join(", ")})`;
 }
 
+const REGIONS = ["na", "latam", "br", "eu", "ap", "kr", "pbe"];
+
 const CONFIG_KEYS = [
   "show_logs", "app_theme", "simplified_theme", "custom_theme",
   "discord_rpc", "start_with_windows", "start_minimized", "minimize_to_tray",
-  "henrik_api_key", "splooshima_api_key", "mapdodge-config", "auto_unqueue", "auto_requeue",
-  "instalock_select_delay", "instalock_lock_delay", "instalock-config",
+  "henrik_api_key", "splooshima_api_key", "auto_unqueue", "auto_requeue",
   "fake-status-config",
 ];
 
+// Configs the backend saves in the app data dir: export key → [getter, setter, setter argument].
+const BACKEND_CONFIGS = {
+  dodge_config: ["get_dodge_config", "set_dodge_config", "config"],
+  agent_select_rules: ["get_agent_select_rules", "set_agent_select_rules", "rules"],
+  riot_dir: ["get_riot_base_dir", "set_riot_base_dir", "path"],
+};
+
+// Keys older exports carried in localStorage. The page that owns them moves them to the backend on load.
+const LEGACY_KEYS = ["mapdodge-config", "instalock-config", "instalock_select_delay", "instalock_lock_delay", "riot_base_dir"];
+
 export default function SettingsPage({
   showLogs, onShowLogsChange,
   selectDelay, onSelectDelayChange,
@@ -175,6 +187,46 @@ export default function SettingsPage({
   const fileRef = useRef(null);
   const configFileRef = useRef(null);
   const [presetOpen, setPresetOpen] = useState(false);
+  const [cacheCleared, setCacheCleared] = useState(null);
+  const [riotBaseDir, setRiotBaseDir] = useState("");
+  const [riotPaths, setRiotPaths] = useState(null);
+  const [riotPathsError, setRiotPathsError] = useState(null);
+  const [regionOverride, setRegionOverride] = useState(null);
+  const [regionError, setRegionError] = useState(null);
+  const [diagnostics, setDiagnostics] = useState(null);
+  const [diagnosticsRunning, setDiagnosticsRunning] = useState(false);
+
+  useEffect(() => {
+    invoke("get_riot_base_dir").then((dir) => setRiotBaseDir(dir || "")).catch(() => {});
+    invoke("get_riot_paths")
+      .then((p) => { setRiotPaths(p); setRiotPathsError(null); })
+      .catch((e) => setRiotPathsError(e?.message ?? e));
+  }, []);
+
+  useEffect(() => {
+    invoke("get_region_override").then(setRegionOverride).catch(() => {});
+  }, []);
+
+  const applyRegionOverride = async (region) => {
+    try {
+      const applied = await invoke("set_region_override", { region, shard: null });
+      setRegionOverride(applied);
+      setRegionError(null);
+    } catch (e) {
+      setRegionError(e?.message ?? e);
+    }
+  };
+
+  const applyRiotBaseDir = async () => {
+    const value = riotBaseDir.trim();
+    try {
+      setRiotPaths(await invoke("set_riot_base_dir", { path: value || null }));
+      setRiotPathsError(null);
+    } catch (e) {
+      setRiotPaths(null);
+      setRiotPathsError(e?.message ?? e);
+    }
+  };
 
   const exportTheme = () => {
     const blob = new Blob([JSON.stringify(customTheme, null, 2)], { type: "application/json" });
@@ -209,6 +261,13 @@ export default function SettingsPage({
       const val = localStorage.getItem(key);
       if (val !== null) config[key] = val;
     }
+    for (const [key, [getter]] of Object.entries(BACKEND_CONFIGS)) {
+      try {
+        config[key] = await invoke(getter);
+      } catch (e) {
+        console.error(`Export of ${key} failed:`, e);
+      }
+    }
     try {
       const path = await save({
         defaultPath: "config.valthing",
@@ -221,16 +280,51 @@ export default function SettingsPage({
     }
   };
 
+  const clearCache = async () => {
+    try {
+      setCacheCleared(await invoke("clear_response_cache"));
+    } catch (e) {
+      console.error("Clear cache failed:", e);
+    }
+  };
+
+  const runDiagnostics = async () => {
+    setDiagnosticsRunning(true);
+    try {
+      setDiagnostics(await invoke("run_diagnostics"));
+    } catch (e) {
+      console.error("Diagnostics failed:", e);
+    }
+    setDiagnosticsRunning(false);
+  };
+
+  const exportDiagnostics = async () => {
+    if (!diagnostics) return;
+    try {
+      const path = await save({
+        defaultPath: `diagnostics-${diagnostics.started_at}.json`,
+        filters: [{ name: "JSON", extensions: ["json"] }],
+      });
+      if (!path) return;
+      await writeTextFile(path, JSON.stringify(diagnostics, null, 2));
+    } catch (e) {
+      console.error("Export failed:", e);
+    }
+  };
+
   const importConfig = (e) => {
     const file = e.target.files?.[0];
     if (!file) return;
     const reader = new FileReader();
-    reader.onload = (ev) => {
+    reader.onload = async (ev) => {
       try {
         const data = JSON.parse(ev.target.result);
-        for (const key of CONFIG_KEYS) {
+        for (const key of [...CONFIG_KEYS, ...LEGACY_KEYS]) {
           if (key in data) localStorage.setItem(key, data[key]);
         }
+        for (const [key, [, setter, arg]] of Object.entries(BACKEND_CONFIGS)) {
+          if (key in data) await invoke(setter, { [arg]: data[key] });
+        }
         window.location.reload();
       } catch {}
     };
@@ -581,6 +675,55 @@ export default function SettingsPage({
         </div>
       </motion.div>
 
+      <motion.div variants={{ hidden: { opacity: 0, y: 8 }, show: { opacity: 1, y: 0 } }} transition={noAnim() ? T0 : { duration: 0.2 }} className="p-4 rounded-xl bg-base-700 border border-border space-y-3">
+        <h2 className="text-sm font-display font-semibold text-text-primary">Riot Client Folder</h2>
+        <p className="text-xs font-body text-text-muted">
+          Leave empty to auto-detect. Set a Wine/Proton prefix or the folder used as LocalAppData if the lockfile isn't found.
+        </p>
+        <input
+          type="text"
+          value={riotBaseDir}
+          onChange={(e) => setRiotBaseDir(e.target.value)}
+          onBlur={applyRiotBaseDir}
+          onKeyDown={(e) => { if (e.key === "Enter") applyRiotBaseDir(); }}
+          placeholder="Auto-detect"
+          className="w-full px-3 py-2 bg-base-600 border border-border rounded-lg text-xs font-body text-text-primary placeholder:text-text-muted/50 outline-none focus:border-val-red/60 transition-colors"
+        />
+        {riotPaths && (
+          <p className="text-[10px] font-body text-text-muted break-all">Using {riotPaths.source}: {riotPaths.local_app_data}</p>
+        )}
+        {riotPathsError && (
+          <p className="text-[10px] font-body text-val-red break-all">{riotPathsError}</p>
+        )}
+      </motion.div>
+
+      <motion.div variants={{ hidden: { opacity: 0, y: 8 }, show: { opacity: 1, y: 0 } }} transition={noAnim() ? T0 : { duration: 0.2 }} className="p-4 rounded-xl bg-base-700 border border-border space-y-3">
+        <h2 className="text-sm font-display font-semibold text-text-primary">Region</h2>
+        <p className="text-xs font-body text-text-muted">
+          Auto reads it from ShooterGame.log, then the Riot Client. Pick one if connecting fails on first launch. Takes effect on the next connect.
+        </p>
+        <div className="flex items-center gap-1.5 flex-wrap">
+          {[null, ...REGIONS].map((r) => {
+            const selected = (regionOverride?.region ?? null) === r;
+            return (
+              <button
+                key={r ?? "auto"}
+                onClick={() => applyRegionOverride(r)}
+                className={`px-2.5 py-1 rounded-md border text-xs font-body uppercase transition-colors ${selected ? "bg-base-600 border-val-red text-text-primary" : "bg-base-600 border-border text-text-muted hover:text-text-primary"}`}
+              >
+                {r ?? "Auto"}
+              </button>
+            );
+          })}
+        </div>
+        {regionOverride && (
+          <p className="text-[10px] font-body text-text-muted">Using region {regionOverride.region}, shard {regionOverride.shard}</p>
+        )}
+        {regionError && (
+          <p className="text-[10px] font-body text-val-red break-all">{regionError}</p>
+        )}
+      </motion.div>
+
       <motion.div variants={{ hidden: { opacity: 0, y: 8 }, show: { opacity: 1, y: 0 } }} transition={noAnim() ? T0 : { duration: 0.2 }} className="p-4 rounded-xl bg-base-700 border border-border space-y-3">
         <h2 className="text-sm font-display font-semibold text-text-primary">Config</h2>
         <p className="text-xs font-body text-text-muted">Export or import your entire configuration including agents, maps, theme, and all settings.</p>
@@ -600,7 +743,58 @@ export default function SettingsPage({
             Import Config
           </button>
           <input ref={configFileRef} type="file" accept=".valthing" onChange={importConfig} className="hidden" />
+          <button
+            onClick={clearCache}
+            className="flex items-center gap-1.5 px-3 py-1.5 rounded-lg bg-base-600 border border-border text-xs font-body text-text-primary hover:bg-base-500 transition-colors"
+          >
+            <svg width="12" height="12" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2"><path d="M3 6h18M8 6V4h8v2M19 6l-1 14H6L5 6" /></svg>
+            Clear Cache
+          </button>
+          {cacheCleared !== null && (
+            <span className="text-xs font-body text-text-muted">Removed {cacheCleared} cached {cacheCleared === 1 ? "response" : "responses"}</span>
+          )}
+        </div>
+      </motion.div>
+
+      <motion.div variants={{ hidden: { opacity: 0, y: 8 }, show: { opacity: 1, y: 0 } }} transition={noAnim() ? T0 : { duration: 0.2 }} className="p-4 rounded-xl bg-base-700 border border-border space-y-3">
+        <h2 className="text-sm font-display font-semibold text-text-primary">Connection Diagnostics</h2>
+        <p className="text-xs font-body text-text-muted">Checks each connection step in order. Export the report and attach it to bug reports.</p>
+        <div className="flex items-center gap-2">
+          <button
+            onClick={runDiagnostics}
+            disabled={diagnosticsRunning}
+            className="flex items-center gap-1.5 px-3 py-1.5 rounded-lg bg-base-600 border border-border text-xs font-body text-text-primary hover:bg-base-500 transition-colors disabled:opacity-50"
+          >
+            <svg width="12" height="12" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" className={diagnosticsRunning ? "animate-spin" : ""}><polyline points="23 4 23 10 17 10" /><path d="M20.49 15a9 9 0 11-2.12-9.36L23 10" /></svg>
+            {diagnosticsRunning ? "Running..." : "Run Diagnostics"}
+          </button>
+          {diagnostics && (
+            <button
+              onClick={exportDiagnostics}
+              className="flex items-center gap-1.5 px-3 py-1.5 rounded-lg bg-base-600 border border-border text-xs font-body text-text-primary hover:bg-base-500 transition-colors"
+            >
+              <svg width="12" height="12" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2"><path d="M21 15v4a2 2 0 01-2 2H5a2 2 0 01-2-2v-4M7 10l5 5 5-5M12 15V3" /></svg>
+              Export Report
+            </button>
+          )}
+          {diagnostics && (
+            <span className={`text-xs font-body ${diagnostics.passed ? "text-status-green" : "text-val-red"}`}>
+              {diagnostics.passed ? "All checks passed" : "Some checks failed"} · {diagnostics.total_ms}ms
+            </span>
+          )}
         </div>
+        {diagnostics && (
+          <div className="space-y-1">
+            {diagnostics.steps.map((step) => (
+              <div key={step.name} className="flex items-baseline gap-2 text-[11px] font-body">
+                <span className={`w-9 shrink-0 font-mono uppercase ${step.status === "pass" ? "text-status-green" : step.status === "fail" ? "text-val-red" : "text-text-muted"}`}>{step.status}</span>
+                <span className="w-36 shrink-0 text-text-primary">{step.name}</span>
+                <span className="flex-1 text-text-muted break-all">{step.detail}</span>
+                <span className="shrink-0 font-mono text-text-muted tabular-nums">{step.duration_ms}ms</span>
+              </div>
+            ))}
+          </div>
+        )}
       </motion.div>
 
       <motion.div variants={{ hidden: { opacity: 0, y: 8 }, show: { opacity: 1, y: 0 } }} transition={noAnim() ? T0 : { duration: 0.2 }} className="p-4 rounded-xl bg-base-700 border border-border space-y-1">